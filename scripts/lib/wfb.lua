@@ -82,6 +82,77 @@ function M.ramping_vus_options(vus, duration)
   }
 end
 
+-- Parses WFB_STAGES ("30s:100,60s:100") into ramping-vus stages.
+function M.parse_stages(spec)
+  if spec == nil or spec == "" then
+    return nil
+  end
+
+  local stages = {}
+  for duration, target in string.gmatch(spec, "([^:,]+):(%d+)") do
+    table.insert(stages, { duration = duration, target = tonumber(target) })
+  end
+  if #stages == 0 then
+    return nil
+  end
+  return stages
+end
+
+-- Scenario options for the executor selected by the runner's load profile
+-- (WFB_EXECUTOR / WFB_STAGES / WFB_RATE). Falls back to `ramping_vus_options`.
+function M.options(vus, duration)
+  local executor = env.WFB_EXECUTOR
+
+  if executor == "constant-vus" then
+    return {
+      scenarios = {
+        main = {
+          executor = "constant-vus",
+          vus = vus,
+          duration = duration,
+          exec = "Default",
+        },
+      },
+    }
+  end
+
+  if executor == "constant-arrival-rate" then
+    local rate = tonumber(env.WFB_RATE or "")
+    if rate == nil or rate < 1 then
+      error("WFB_RATE is required for constant-arrival-rate")
+    end
+    return {
+      scenarios = {
+        main = {
+          executor = "constant-arrival-rate",
+          rate = math.floor(rate),
+          timeUnit = "1s",
+          duration = duration,
+          preAllocatedVUs = vus,
+          maxVUs = vus,
+          exec = "Default",
+        },
+      },
+    }
+  end
+
+  local stages = M.parse_stages(env.WFB_STAGES)
+  if stages ~= nil then
+    return {
+      scenarios = {
+        main = {
+          executor = "ramping-vus",
+          startVUs = 0,
+          stages = stages,
+          exec = "Default",
+        },
+      },
+    }
+  end
+
+  return M.ramping_vus_options(vus, duration)
+end
+
 function M.to_num(v)
   if type(v) == "number" then
     return v
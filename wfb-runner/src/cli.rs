@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::selection::Selection;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -9,6 +11,10 @@ pub struct Args {
     #[arg(short, long, default_value = "./config")]
     pub config: PathBuf,
 
+    /// Result storage backend (fs, sqlite)
+    #[arg(long, env = "WFB_STORAGE", default_value_t = wfb_storage::StorageBackend::Fs)]
+    pub storage: wfb_storage::StorageBackend,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -30,24 +36,58 @@ pub enum Commands {
         /// Skip building and deploying dbs
         #[arg(long, default_value_t = false)]
         skip_db_build: bool,
+
+        /// Number of measured iterations per test; results include 95% confidence intervals when > 1
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+
+        /// Comma-separated request rates for a constant-arrival-rate sweep after each test
+        /// (e.g. 1000,5000,20000); overrides `rates` from load profiles
+        #[arg(long, value_delimiter = ',')]
+        arrival_rates: Vec<u64>,
+
+        /// Also retry tests whose last attempt in this run failed (they are skipped by default)
+        #[arg(long, default_value_t = false)]
+        retry_failed: bool,
+
+        /// Only re-run tests whose last attempt in this run failed
+        #[arg(long, default_value_t = false, conflicts_with = "retry_failed")]
+        only_failed: bool,
+
+        #[command(flatten)]
+        selection: Selection,
     },
     Verify {
         /// Environment to use
         #[arg(short, long, default_value = "local")]
         env: String,
 
-        /// Filter by specific benchmark name
-        #[arg(short, long)]
-        benchmark: Option<String>,
+        #[command(flatten)]
+        selection: Selection,
+    },
+    /// Compare two runs and fail when a benchmark regresses
+    Compare {
+        /// Baseline run ID
+        base: String,
+
+        /// Run ID to compare against the baseline
+        head: String,
 
-        /// Filter by specific language
-        #[arg(short, long)]
-        language: Option<String>,
+        /// Environment to compare
+        #[arg(short, long, default_value = "local")]
+        env: String,
 
-        /// Filter by specific test case (plaintext, json_aggregate, static_files)
-        #[arg(short, long)]
-        testcase: Option<String>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = CompareFormat::Table)]
+        format: CompareFormat,
+
+        /// Allowed change in RPS, p99 latency, memory or error rate, in percent, before a test counts as regressed
+        #[arg(short, long, default_value_t = 5.0)]
+        threshold: f64,
     },
+    /// Copy the results of the filesystem store into the SQLite database, so an existing
+    /// data directory can switch to `--storage sqlite`
+    Import,
     Dev {
         /// Benchmark to run
         name: String,
@@ -57,3 +97,10 @@ pub enum Commands {
         env: String,
     },
 }
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum CompareFormat {
+    Table,
+    Json,
+    Markdown,
+}
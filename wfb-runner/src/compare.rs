@@ -0,0 +1,146 @@
+use humanize_bytes::humanize_bytes_binary;
+use wfb_storage::{MetricDelta, RunComparison, Significance, TestCaseComparison, Verdict};
+
+use crate::cli::CompareFormat;
+use crate::runner::benchmark::format_latency;
+
+const HEADERS: [&str; 9] = [
+    "Benchmark",
+    "Test",
+    "RPS",
+    "P50",
+    "P99",
+    "Memory",
+    "Errors",
+    "Significance",
+    "Verdict",
+];
+
+pub fn render(comparison: &RunComparison, format: CompareFormat) -> anyhow::Result<String> {
+    Ok(match format {
+        CompareFormat::Json => serde_json::to_string_pretty(comparison)?,
+        CompareFormat::Table => render_table(comparison),
+        CompareFormat::Markdown => render_markdown(comparison),
+    })
+}
+
+fn rows(comparison: &RunComparison) -> Vec<[String; 9]> {
+    comparison.tests.iter().map(row).collect()
+}
+
+fn row(t: &TestCaseComparison) -> [String; 9] {
+    [
+        format!("{}/{}", t.language, t.benchmark),
+        t.test.clone(),
+        delta(&t.requests_per_sec, |v| format!("{:.0}", v)),
+        delta(&t.latency_p50, |v| format_latency(v as u64)),
+        delta(&t.latency_p99, |v| format_latency(v as u64)),
+        delta(&t.memory_usage_bytes, |v| {
+            humanize_bytes_binary!(v as u64).to_string()
+        }),
+        delta(&t.total_errors, |v| format!("{:.0}", v)),
+        significance(t.significance).to_string(),
+        verdict(t.verdict).to_string(),
+    ]
+}
+
+fn delta(d: &MetricDelta, fmt: impl Fn(f64) -> String) -> String {
+    match d.change_pct {
+        Some(pct) => format!("{} → {} ({:+.1}%)", fmt(d.base), fmt(d.head), pct),
+        None => format!("{} → {}", fmt(d.base), fmt(d.head)),
+    }
+}
+
+fn significance(s: Significance) -> &'static str {
+    match s {
+        Significance::Significant => "significant",
+        Significance::NotSignificant => "not significant",
+        Significance::Unknown => "n/a",
+    }
+}
+
+fn verdict(v: Verdict) -> &'static str {
+    match v {
+        Verdict::Improved => "improved",
+        Verdict::Regressed => "REGRESSED",
+        Verdict::Unchanged => "unchanged",
+        Verdict::Noise => "noise",
+    }
+}
+
+fn summary_lines(comparison: &RunComparison) -> Vec<String> {
+    let mut lines = Vec::new();
+    for t in &comparison.only_in_base {
+        lines.push(format!(
+            "missing in {}: {}/{}/{}",
+            comparison.head_run, t.language, t.benchmark, t.test
+        ));
+    }
+    for t in &comparison.only_in_head {
+        lines.push(format!(
+            "new in {}: {}/{}/{}",
+            comparison.head_run, t.language, t.benchmark, t.test
+        ));
+    }
+    lines.push(format!(
+        "{} compared, {} regressed (threshold {:.1}%)",
+        comparison.tests.len(),
+        comparison.regressions().count(),
+        comparison.threshold_pct
+    ));
+    lines
+}
+
+fn render_table(comparison: &RunComparison) -> String {
+    let rows = rows(comparison);
+    let mut widths = HEADERS.map(|h| h.chars().count());
+    for r in &rows {
+        for (w, cell) in widths.iter_mut().zip(r) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(c, w)| format!("{:<w$}", c, w = w))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = format!(
+        "{} → {} ({})\n\n",
+        comparison.base_run, comparison.head_run, comparison.environment
+    );
+    out.push_str(&line(&HEADERS.map(String::from)));
+    out.push('\n');
+    out.push_str(&line(&widths.map(|w| "-".repeat(w))));
+    out.push('\n');
+    for r in &rows {
+        out.push_str(&line(r));
+        out.push('\n');
+    }
+    out.push('\n');
+    out.push_str(&summary_lines(comparison).join("\n"));
+    out
+}
+
+fn render_markdown(comparison: &RunComparison) -> String {
+    let mut out = format!(
+        "### `{}` → `{}` ({})\n\n",
+        comparison.base_run, comparison.head_run, comparison.environment
+    );
+    out.push_str(&format!("| {} |\n", HEADERS.join(" | ")));
+    out.push_str(&format!("|{}\n", " --- |".repeat(HEADERS.len())));
+    for r in rows(comparison) {
+        out.push_str(&format!("| {} |\n", r.join(" | ")));
+    }
+    out.push('\n');
+    for l in summary_lines(comparison) {
+        out.push_str(&format!("- {}\n", l));
+    }
+    out
+}
@@ -1,7 +1,13 @@
+// Built-in load defaults; `load_profile` config documents override them per environment/benchmark.
 pub const BENCHMARK_DURATION_PER_TEST_SECS: u64 = 60 * 4;
 pub const BENCHMARK_WARMUP_DURATION_SECS: u64 = 30;
+pub const BENCHMARK_RATE_STEP_DURATION_SECS: u64 = 60;
+pub const BENCHMARK_SLO_P99_MS: f64 = 10.0;
 pub const BENCHMARK_WARMUP_MAX_VUS: u64 = 4;
 
+// App log lines kept in the run journal for a failed test.
+pub const APP_LOG_TAIL_LINES: usize = 50;
+
 // Verification runs: keep short/light; correctness-focused.
 pub const VERIFY_DURATION_SECS: u64 = 3;
 pub const VERIFY_MAX_VUS: u64 = 4;
@@ -27,11 +33,26 @@
 pub const SCRIPT_DB_COMPLEX: &str = "/scripts/wfb_db_complex.lua";
 pub const SCRIPT_GRPC_AGGREGATE: &str = "/scripts/wfb_grpc_aggregate.lua";
 
+// Default measured-phase VU targets per test.
 pub const UVS_PLAINTEXT: u64 = 1024;
 pub const UVS_JSON: u64 = 512;
 pub const UVS_GRPC: u64 = 512;
 pub const UVS_DB_COMPLEX: u64 = 128;
 pub const UVS_STATIC: u64 = 128;
 
+// Docker Engine API backend: remote daemons are reached by forwarding this socket over SSH.
+pub const DOCKER_SOCKET: &str = "/var/run/docker.sock";
+pub const DOCKER_API_TIMEOUT_SECS: u64 = 120;
+pub const DOCKER_TUNNEL_RETRIES: u32 = 100;
+
+// Kubernetes environments: pod manifests are written here for `kubectl apply`.
+pub const KUBE_MANIFEST_PATH: &str = "/tmp/wfb/kube";
+pub const KUBE_POD_RETRIES: u32 = 120;
+
+// SSH executor: concurrent channels per shared session before another one is opened
+// (OpenSSH's default `MaxSessions` is 10).
+pub const SSH_MAX_CHANNELS_PER_SESSION: usize = 8;
+pub const SSH_KEEPALIVE_INTERVAL_SECS: u32 = 15;
+
 pub const CONTAINER_HEALTH_RETRIES: u32 = 30;
 pub const CONTAINER_HEALTH_INTERVAL_SECS: u64 = 1;
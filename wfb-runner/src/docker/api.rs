@@ -0,0 +1,392 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::Context;
+use bollard::Docker;
+use bollard::container::LogOutput;
+use bollard::models::{ContainerStatsResponse, HealthStatusEnum};
+use bollard::query_parameters::{
+    CreateContainerOptionsBuilder, InspectContainerOptions, ListContainersOptionsBuilder,
+    LogsOptionsBuilder, RemoveContainerOptionsBuilder, StartContainerOptions, StatsOptionsBuilder,
+    StopContainerOptions,
+};
+use futures_util::StreamExt;
+use tokio::process::{Child, Command};
+use wfb_storage::SshConnection;
+
+use super::command::DockerRunCommand;
+use super::{ContainerHealth, ContainerStats, HealthStatus};
+use crate::consts;
+
+/// Client of one Docker daemon's Engine API.
+#[derive(Clone)]
+pub struct DockerApi {
+    docker: Docker,
+    /// Tunnel to a remote daemon; open for as long as any clone of the client is alive.
+    _tunnel: Option<Arc<SshTunnel>>,
+}
+
+impl DockerApi {
+    /// The local daemon, at `DOCKER_HOST` or the default socket.
+    pub fn connect_local() -> anyhow::Result<Self> {
+        let docker = Docker::connect_with_local_defaults()
+            .context("Failed to connect to the local Docker daemon")?;
+        Ok(Self {
+            docker,
+            _tunnel: None,
+        })
+    }
+
+    /// The daemon of a remote host, through its socket forwarded over SSH.
+    pub async fn connect_ssh(connection: &SshConnection) -> anyhow::Result<Self> {
+        let tunnel = SshTunnel::open(connection).await?;
+        let docker = Docker::connect_with_unix(
+            &tunnel.socket.to_string_lossy(),
+            consts::DOCKER_API_TIMEOUT_SECS,
+            bollard::API_DEFAULT_VERSION,
+        )
+        .with_context(|| {
+            format!(
+                "Failed to connect to the Docker daemon on {}",
+                connection.ip
+            )
+        })?;
+        docker
+            .ping()
+            .await
+            .with_context(|| format!("Docker daemon on {} did not answer", connection.ip))?;
+        Ok(Self {
+            docker,
+            _tunnel: Some(Arc::new(tunnel)),
+        })
+    }
+
+    /// Creates and starts the container; unless it is detached, streams its stdout to
+    /// `on_stdout` until it exits, like `docker run`.
+    pub async fn run(
+        &self,
+        cmd: &DockerRunCommand<'_>,
+        on_stdout: impl Fn(&str) + Send + Sync + 'static,
+    ) -> anyhow::Result<String> {
+        let name = cmd.name();
+        let created = self
+            .docker
+            .create_container(
+                Some(CreateContainerOptionsBuilder::new().name(name).build()),
+                cmd.to_create_body()?,
+            )
+            .await
+            .with_context(|| format!("Failed to create container {}", name))?;
+        self.docker
+            .start_container(name, None::<StartContainerOptions>)
+            .await
+            .with_context(|| format!("Failed to start container {}", name))?;
+        if cmd.is_detached() {
+            return Ok(created.id);
+        }
+
+        let options = LogsOptionsBuilder::new()
+            .follow(true)
+            .stdout(true)
+            .stderr(true)
+            .build();
+        let mut stream = self.docker.logs(name, Some(options));
+        let mut lines = LineBuffer::default();
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        while let Some(chunk) = stream.next().await {
+            match chunk? {
+                LogOutput::StdOut { message } => {
+                    for line in lines.push(&message) {
+                        on_stdout(&line);
+                        stdout.push_str(&line);
+                        stdout.push('\n');
+                    }
+                }
+                LogOutput::StdErr { message } => {
+                    stderr.push_str(&String::from_utf8_lossy(&message));
+                }
+                _ => {}
+            }
+        }
+        if let Some(line) = lines.finish() {
+            on_stdout(&line);
+            stdout.push_str(&line);
+            stdout.push('\n');
+        }
+
+        let exit_code = self
+            .docker
+            .inspect_container(name, None::<InspectContainerOptions>)
+            .await?
+            .state
+            .and_then(|state| state.exit_code)
+            .unwrap_or_default();
+        if exit_code != 0 {
+            anyhow::bail!(
+                "Container {} exited with status {}\nStderr:\n{}",
+                name,
+                exit_code,
+                stderr
+            );
+        }
+        Ok(stdout)
+    }
+
+    pub async fn health(&self, name: &str) -> anyhow::Result<ContainerHealth> {
+        let inspect = self
+            .docker
+            .inspect_container(name, None::<InspectContainerOptions>)
+            .await
+            .with_context(|| format!("Failed to inspect container {}", name))?;
+        let Some(health) = inspect.state.and_then(|state| state.health) else {
+            return Ok(ContainerHealth {
+                status: HealthStatus::None,
+                last_log: None,
+            });
+        };
+
+        let status = match health.status {
+            Some(HealthStatusEnum::STARTING) => HealthStatus::Starting,
+            Some(HealthStatusEnum::HEALTHY) => HealthStatus::Healthy,
+            Some(HealthStatusEnum::UNHEALTHY) => HealthStatus::Unhealthy,
+            _ => HealthStatus::None,
+        };
+        let last_log = health
+            .log
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .find_map(|result| {
+                let output = result.output?;
+                let output = output.trim();
+                (!output.is_empty())
+                    .then(|| format!("[{}] {}", result.exit_code.unwrap_or_default(), output))
+            });
+        Ok(ContainerHealth { status, last_log })
+    }
+
+    /// Samples the container's usage from the daemon's stats stream until the task is aborted.
+    pub async fn monitor_stats(&self, name: &str, on_sample: impl Fn(ContainerStats)) {
+        loop {
+            let options = StatsOptionsBuilder::new().stream(true).build();
+            let mut stream = self.docker.stats(name, Some(options));
+            while let Some(Ok(stats)) = stream.next().await {
+                if let Some(sample) = stats_sample(&stats) {
+                    on_sample(sample);
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    pub async fn logs(&self, name: &str, stdout: bool, stderr: bool) -> anyhow::Result<String> {
+        let options = LogsOptionsBuilder::new()
+            .stdout(stdout)
+            .stderr(stderr)
+            .build();
+        let mut stream = self.docker.logs(name, Some(options));
+        let mut logs = String::new();
+        while let Some(chunk) = stream.next().await {
+            logs.push_str(&chunk?.to_string());
+        }
+        Ok(logs)
+    }
+
+    pub async fn logs_follow(
+        &self,
+        name: &str,
+        on_stdout: impl Fn(&str) + Send + Sync + 'static,
+    ) -> anyhow::Result<String> {
+        let options = LogsOptionsBuilder::new()
+            .follow(true)
+            .stdout(true)
+            .stderr(true)
+            .build();
+        let mut stream = self.docker.logs(name, Some(options));
+        let mut lines = LineBuffer::default();
+        let mut logs = String::new();
+        while let Some(chunk) = stream.next().await {
+            for line in lines.push(&chunk?.into_bytes()) {
+                on_stdout(&line);
+                logs.push_str(&line);
+                logs.push('\n');
+            }
+        }
+        Ok(logs)
+    }
+
+    pub async fn stop_and_remove(&self, name: &str) {
+        let _ = self
+            .docker
+            .stop_container(name, None::<StopContainerOptions>)
+            .await;
+        let _ = self
+            .docker
+            .remove_container(name, Some(RemoveContainerOptionsBuilder::new().build()))
+            .await;
+    }
+
+    pub async fn remove_all_containers(&self) {
+        let options = ListContainersOptionsBuilder::new().all(true).build();
+        let Ok(containers) = self.docker.list_containers(Some(options)).await else {
+            return;
+        };
+        for id in containers.into_iter().filter_map(|c| c.id) {
+            let _ = self
+                .docker
+                .remove_container(
+                    &id,
+                    Some(RemoveContainerOptionsBuilder::new().force(true).build()),
+                )
+                .await;
+        }
+    }
+}
+
+/// Usage the way `docker stats` reports it: memory without the page cache, CPU as a
+/// percentage of one core. `None` for the first sample, which has no previous CPU reading.
+fn stats_sample(stats: &ContainerStatsResponse) -> Option<ContainerStats> {
+    let cpu = stats.cpu_stats.as_ref()?;
+    let precpu = stats.precpu_stats.as_ref()?;
+    let system_delta = cpu
+        .system_cpu_usage?
+        .checked_sub(precpu.system_cpu_usage?)
+        .filter(|delta| *delta > 0)?;
+    let usage = |stats: &bollard::models::ContainerCpuStats| {
+        stats
+            .cpu_usage
+            .as_ref()
+            .and_then(|usage| usage.total_usage)
+            .unwrap_or_default()
+    };
+    let cpu_delta = usage(cpu).saturating_sub(usage(precpu));
+    let online_cpus = cpu.online_cpus.map(u64::from).unwrap_or_else(|| {
+        cpu.cpu_usage
+            .as_ref()
+            .and_then(|usage| usage.percpu_usage.as_ref())
+            .map_or(1, |percpu| percpu.len() as u64)
+    });
+
+    let memory = stats.memory_stats.as_ref()?;
+    let inactive_file = memory
+        .stats
+        .as_ref()
+        .and_then(|s| {
+            s.get("inactive_file")
+                .or_else(|| s.get("total_inactive_file"))
+        })
+        .copied()
+        .unwrap_or_default();
+
+    Some(ContainerStats {
+        memory_bytes: memory.usage?.saturating_sub(inactive_file),
+        cpu_percent: cpu_delta as f64 / system_delta as f64 * online_cpus as f64 * 100.0,
+    })
+}
+
+/// Splits streamed log chunks into lines.
+#[derive(Default)]
+struct LineBuffer {
+    pending: String,
+}
+
+impl LineBuffer {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.push_str(&String::from_utf8_lossy(chunk));
+        let mut lines = Vec::new();
+        while let Some(end) = self.pending.find('\n') {
+            let line: String = self.pending.drain(..=end).collect();
+            lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+        lines
+    }
+
+    fn finish(self) -> Option<String> {
+        (!self.pending.is_empty()).then_some(self.pending)
+    }
+}
+
+/// `ssh -L` forwarding a local unix socket to the Docker socket of a remote host.
+struct SshTunnel {
+    socket: PathBuf,
+    _child: Child,
+}
+
+impl SshTunnel {
+    async fn open(connection: &SshConnection) -> anyhow::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let socket = std::env::temp_dir().join(format!(
+            "wfb-docker-{}-{}.sock",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&socket);
+
+        let mut child = Command::new("ssh")
+            .arg("-i")
+            .arg(&connection.ssh_key_path)
+            .arg("-p")
+            .arg(connection.port.to_string())
+            .args([
+                "-o",
+                "BatchMode=yes",
+                "-o",
+                "StrictHostKeyChecking=accept-new",
+            ])
+            .args(["-o", "ExitOnForwardFailure=yes", "-N"])
+            .arg("-L")
+            .arg(format!("{}:{}", socket.display(), consts::DOCKER_SOCKET))
+            .arg(format!("{}@{}", connection.user, connection.ip))
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to start ssh for the Docker API tunnel")?;
+
+        for _ in 0..consts::DOCKER_TUNNEL_RETRIES {
+            if socket.exists() {
+                return Ok(Self {
+                    socket,
+                    _child: child,
+                });
+            }
+            if let Some(status) = child.try_wait()? {
+                anyhow::bail!(
+                    "Docker API tunnel to {} exited with {}",
+                    connection.ip,
+                    status
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        anyhow::bail!(
+            "Timed out opening the Docker API tunnel to {}",
+            connection.ip
+        )
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineBuffer;
+
+    #[test]
+    fn line_buffer_joins_lines_split_across_chunks() {
+        let mut lines = LineBuffer::default();
+        assert_eq!(lines.push(b"{\"tick\":"), Vec::<String>::new());
+        assert_eq!(
+            lines.push(b"1}\r\n{\"tick\":2}\n{"),
+            ["{\"tick\":1}", "{\"tick\":2}"]
+        );
+        assert_eq!(lines.finish().as_deref(), Some("{"));
+    }
+}
@@ -1,33 +1,111 @@
 #![allow(dead_code)]
+use std::collections::HashMap;
 use std::fmt;
 
-fn docker_cmd(sudo: bool) -> &'static str {
-    if sudo { "sudo docker" } else { "docker" }
+use bollard::models::{ContainerCreateBody, HostConfig, PortBinding, ResourcesUlimits};
+use wfb_storage::{ContainerRuntime, ResourceLimits};
+
+/// The container engine command line: `docker`, `podman` or `nerdctl`, with `sudo` when needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerCli {
+    runtime: ContainerRuntime,
+    sudo: bool,
+}
+
+impl ContainerCli {
+    /// `sudo` is ignored for Podman, which runs rootless.
+    pub fn new(runtime: ContainerRuntime, sudo: bool) -> Self {
+        Self {
+            runtime,
+            sudo: sudo && runtime != ContainerRuntime::Podman,
+        }
+    }
+
+    pub fn runtime(&self) -> ContainerRuntime {
+        self.runtime
+    }
+
+    /// Name containers reach the machine they run on by. Podman writes
+    /// `host.containers.internal` into every container's `/etc/hosts` itself; Docker and
+    /// nerdctl only know `host.docker.internal` through a `host-gateway` entry.
+    pub fn host_alias(&self) -> &'static str {
+        match self.runtime {
+            ContainerRuntime::Podman => "host.containers.internal",
+            ContainerRuntime::Docker | ContainerRuntime::Nerdctl => "host.docker.internal",
+        }
+    }
+
+    /// Podman resolves unqualified image names against a list of registries and refuses
+    /// to guess without a terminal, so Docker Hub images are qualified for it.
+    fn qualify_image(&self, image: &str) -> String {
+        let first = image.split('/').next().unwrap_or(image);
+        let has_registry = image.contains('/')
+            && (first.contains('.') || first.contains(':') || first == "localhost");
+        if self.runtime == ContainerRuntime::Podman && !has_registry {
+            format!("docker.io/{}", image)
+        } else {
+            image.to_string()
+        }
+    }
+}
+
+impl Default for ContainerCli {
+    fn default() -> Self {
+        Self::new(ContainerRuntime::Docker, false)
+    }
+}
+
+impl fmt::Display for ContainerCli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.sudo {
+            f.write_str("sudo ")?;
+        }
+        f.write_str(match self.runtime {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Nerdctl => "nerdctl",
+        })
+    }
+}
+
+/// Quotes a user-supplied value for `sh -c`; plain words are passed through unchanged.
+pub(crate) fn shell_quote(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,+@%".contains(c));
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
 }
 
 pub struct DockerBuildCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     docker_file: Option<&'a str>,
     tag: &'a str,
     context_path: &'a str,
     platform: Option<&'a str>,
     output: Option<&'a str>,
+    build_args: Vec<(&'a str, &'a str)>,
 }
 
 impl<'a> DockerBuildCommand<'a> {
     pub fn new(
-        sudo: bool,
+        cli: ContainerCli,
         docker_file: Option<&'a str>,
         tag: &'a str,
         context_path: &'a str,
     ) -> Self {
         Self {
-            sudo,
+            cli,
             docker_file,
             tag,
             context_path,
             platform: None,
             output: None,
+            build_args: Vec::new(),
         }
     }
 
@@ -40,6 +118,11 @@ pub fn with_output(mut self, output: &'a str) -> Self {
         self.output = Some(output);
         self
     }
+
+    pub fn with_build_arg(mut self, key: &'a str, value: &'a str) -> Self {
+        self.build_args.push((key, value));
+        self
+    }
 }
 
 impl<'a> fmt::Display for DockerBuildCommand<'a> {
@@ -59,13 +142,21 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         } else {
             String::new()
         };
+        // Sorted so the same config always renders the same command.
+        let mut build_args = self.build_args.clone();
+        build_args.sort();
+        let build_args_arg: String = build_args
+            .iter()
+            .map(|(k, v)| format!("--build-arg {} ", shell_quote(&format!("{}={}", k, v))))
+            .collect();
         write!(
             f,
-            "{} build {} {} {} -t {}:latest {}",
-            docker_cmd(self.sudo),
+            "{} build {} {} {} {}-t {}:latest {}",
+            self.cli,
             platform_arg,
             output_arg,
             docker_file_arg,
+            build_args_arg,
             self.tag,
             self.context_path
         )
@@ -73,15 +164,15 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 pub struct DockerSaveCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     image: &'a str,
     output_path: &'a str,
 }
 
 impl<'a> DockerSaveCommand<'a> {
-    pub fn new(sudo: bool, image: &'a str, output_path: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, image: &'a str, output_path: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             image,
             output_path,
         }
@@ -93,39 +184,37 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "{} save -o {} {}:latest",
-            docker_cmd(self.sudo),
-            self.output_path,
-            self.image
+            self.cli, self.output_path, self.image
         )
     }
 }
 
 pub struct DockerLoadCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     input_path: &'a str,
 }
 
 impl<'a> DockerLoadCommand<'a> {
-    pub fn new(sudo: bool, input_path: &'a str) -> Self {
-        Self { sudo, input_path }
+    pub fn new(cli: ContainerCli, input_path: &'a str) -> Self {
+        Self { cli, input_path }
     }
 }
 
 impl<'a> fmt::Display for DockerLoadCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} load -i {}", docker_cmd(self.sudo), self.input_path)
+        write!(f, "{} load -i {}", self.cli, self.input_path)
     }
 }
 
 pub struct DockerPullCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     image: &'a str,
     tag: &'a str,
 }
 
 impl<'a> DockerPullCommand<'a> {
-    pub fn new(sudo: bool, image: &'a str, tag: &'a str) -> Self {
-        Self { sudo, image, tag }
+    pub fn new(cli: ContainerCli, image: &'a str, tag: &'a str) -> Self {
+        Self { cli, image, tag }
     }
 }
 
@@ -134,22 +223,102 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "{} pull {}:{}",
-            docker_cmd(self.sudo),
-            self.image,
+            self.cli,
+            self.cli.qualify_image(self.image),
             self.tag
         )
     }
 }
 
+pub struct DockerTagCommand<'a> {
+    cli: ContainerCli,
+    source: &'a str,
+    target: &'a str,
+}
+
+impl<'a> DockerTagCommand<'a> {
+    pub fn new(cli: ContainerCli, source: &'a str, target: &'a str) -> Self {
+        Self {
+            cli,
+            source,
+            target,
+        }
+    }
+}
+
+impl<'a> fmt::Display for DockerTagCommand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} tag {} {}", self.cli, self.source, self.target)
+    }
+}
+
+/// Succeeds only when the image exists locally.
+pub struct DockerImageInspectCommand<'a> {
+    cli: ContainerCli,
+    image: &'a str,
+}
+
+impl<'a> DockerImageInspectCommand<'a> {
+    pub fn new(cli: ContainerCli, image: &'a str) -> Self {
+        Self { cli, image }
+    }
+}
+
+impl<'a> fmt::Display for DockerImageInspectCommand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} image inspect --format ok {}", self.cli, self.image)
+    }
+}
+
+/// Lists the tags of the local images of a repository, one per line.
+pub struct DockerImageTagsCommand<'a> {
+    cli: ContainerCli,
+    repository: &'a str,
+}
+
+impl<'a> DockerImageTagsCommand<'a> {
+    pub fn new(cli: ContainerCli, repository: &'a str) -> Self {
+        Self { cli, repository }
+    }
+}
+
+impl<'a> fmt::Display for DockerImageTagsCommand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} images --format '{{{{.Tag}}}}' {}",
+            self.cli, self.repository
+        )
+    }
+}
+
+/// Removes an image tag; the image itself goes with its last tag.
+pub struct DockerRmiCommand<'a> {
+    cli: ContainerCli,
+    image: &'a str,
+}
+
+impl<'a> DockerRmiCommand<'a> {
+    pub fn new(cli: ContainerCli, image: &'a str) -> Self {
+        Self { cli, image }
+    }
+}
+
+impl<'a> fmt::Display for DockerRmiCommand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} rmi {}", self.cli, self.image)
+    }
+}
+
 pub struct DockerStopCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     container_name: &'a str,
 }
 
 impl<'a> DockerStopCommand<'a> {
-    pub fn new(sudo: bool, container_name: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, container_name: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             container_name,
         }
     }
@@ -157,19 +326,19 @@ pub fn new(sudo: bool, container_name: &'a str) -> Self {
 
 impl<'a> fmt::Display for DockerStopCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} stop {}", docker_cmd(self.sudo), self.container_name)
+        write!(f, "{} stop {}", self.cli, self.container_name)
     }
 }
 
 pub struct DockerRmCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     container_name: &'a str,
 }
 
 impl<'a> DockerRmCommand<'a> {
-    pub fn new(sudo: bool, container_name: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, container_name: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             container_name,
         }
     }
@@ -177,20 +346,20 @@ pub fn new(sudo: bool, container_name: &'a str) -> Self {
 
 impl<'a> fmt::Display for DockerRmCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} rm {}", docker_cmd(self.sudo), self.container_name)
+        write!(f, "{} rm {}", self.cli, self.container_name)
     }
 }
 
 pub struct DockerInspectCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     container_name: &'a str,
     format: &'a str,
 }
 
 impl<'a> DockerInspectCommand<'a> {
-    pub fn new(sudo: bool, container_name: &'a str, format: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, container_name: &'a str, format: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             container_name,
             format,
         }
@@ -202,23 +371,21 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "{} inspect --format \"{}\" {}",
-            docker_cmd(self.sudo),
-            self.format,
-            self.container_name
+            self.cli, self.format, self.container_name
         )
     }
 }
 
 pub struct DockerStatsCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     container_name: &'a str,
     format: &'a str,
 }
 
 impl<'a> DockerStatsCommand<'a> {
-    pub fn new(sudo: bool, container_name: &'a str, format: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, container_name: &'a str, format: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             container_name,
             format,
         }
@@ -230,15 +397,13 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "{} stats --no-stream --format \"{}\" {}",
-            docker_cmd(self.sudo),
-            self.format,
-            self.container_name
+            self.cli, self.format, self.container_name
         )
     }
 }
 
 pub struct DockerRunCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     image: &'a str,
     name: &'a str,
     ports: Vec<(u16, u16)>, // host, container
@@ -249,13 +414,16 @@ pub struct DockerRunCommand<'a> {
     ulimit: Option<&'a str>,
     sysctl: Vec<(&'a str, &'a str)>,
     extra_hosts: Vec<&'a str>,
+    cpus: Option<f64>,
+    cpuset_cpus: Option<&'a str>,
+    memory: Option<&'a str>,
     args: Vec<&'a str>,
 }
 
 impl<'a> DockerRunCommand<'a> {
-    pub fn new(sudo: bool, image: &'a str, name: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, image: &'a str, name: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             image,
             name,
             ports: Vec::new(),
@@ -266,6 +434,9 @@ pub fn new(sudo: bool, image: &'a str, name: &'a str) -> Self {
             ulimit: None,
             sysctl: Vec::new(),
             extra_hosts: Vec::new(),
+            cpus: None,
+            cpuset_cpus: None,
+            memory: None,
             args: Vec::new(),
         }
     }
@@ -309,15 +480,205 @@ pub fn add_host(mut self, host: &'a str) -> Self {
         self
     }
 
+    /// Makes [`ContainerCli::host_alias`] resolve to the host inside the container.
+    /// Older Podman releases reject the `host-gateway` address, and Podman needs no entry.
+    pub fn host_gateway(self) -> Self {
+        match self.cli.runtime() {
+            ContainerRuntime::Podman => self,
+            ContainerRuntime::Docker | ContainerRuntime::Nerdctl => {
+                self.add_host("host.docker.internal:host-gateway")
+            }
+        }
+    }
+
+    pub fn cpus(mut self, cpus: f64) -> Self {
+        self.cpus = Some(cpus);
+        self
+    }
+
+    pub fn cpuset_cpus(mut self, cpuset: &'a str) -> Self {
+        self.cpuset_cpus = Some(cpuset);
+        self
+    }
+
+    pub fn memory(mut self, memory: &'a str) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Applies every limit set in `limits`.
+    pub fn limits(mut self, limits: &'a ResourceLimits) -> Self {
+        if let Some(cpus) = limits.cpus {
+            self = self.cpus(cpus);
+        }
+        if let Some(cpuset) = &limits.cpuset_cpus {
+            self = self.cpuset_cpus(cpuset);
+        }
+        if let Some(memory) = &limits.memory {
+            self = self.memory(memory);
+        }
+        if let Some(network) = &limits.network {
+            self = self.network(network);
+        }
+        self
+    }
+
     pub fn arg(mut self, arg: &'a str) -> Self {
         self.args.push(arg);
         self
     }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.detach
+    }
+
+    pub fn image(&self) -> &str {
+        self.image
+    }
+
+    /// Published ports as `(host, container)`.
+    pub fn ports(&self) -> &[(u16, u16)] {
+        &self.ports
+    }
+
+    pub fn env_vars(&self) -> &[(&'a str, String)] {
+        &self.env
+    }
+
+    /// Bind mounts as `(host path, container path)`.
+    pub fn volumes(&self) -> &[(&'a str, &'a str)] {
+        &self.volumes
+    }
+
+    pub fn sysctls(&self) -> &[(&'a str, &'a str)] {
+        &self.sysctl
+    }
+
+    pub fn args(&self) -> &[&'a str] {
+        &self.args
+    }
+
+    pub fn network_name(&self) -> Option<&str> {
+        self.network
+    }
+
+    pub fn cpu_limit(&self) -> Option<f64> {
+        self.cpus
+    }
+
+    pub fn cpuset(&self) -> Option<&'a str> {
+        self.cpuset_cpus
+    }
+
+    /// The `--ulimit` value, e.g. `nofile=1000000:1000000`.
+    pub fn ulimit_spec(&self) -> Option<&'a str> {
+        self.ulimit
+    }
+
+    pub fn memory_limit_bytes(&self) -> anyhow::Result<Option<i64>> {
+        self.memory
+            .map(|memory| {
+                parse_memory_bytes(memory)
+                    .ok_or_else(|| anyhow::anyhow!("invalid memory limit '{}'", memory))
+            })
+            .transpose()
+    }
+
+    /// The same container as an Engine API create request.
+    pub fn to_create_body(&self) -> anyhow::Result<ContainerCreateBody> {
+        let ulimits = self
+            .ulimit
+            .map(|ulimit| {
+                let (name, limits) = ulimit
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("invalid ulimit '{}'", ulimit))?;
+                let (soft, hard) = limits.split_once(':').unwrap_or((limits, limits));
+                Ok::<_, anyhow::Error>(vec![ResourcesUlimits {
+                    name: Some(name.to_string()),
+                    soft: Some(soft.parse()?),
+                    hard: Some(hard.parse()?),
+                }])
+            })
+            .transpose()?;
+        let memory = self.memory_limit_bytes()?;
+
+        let port_key = |container: u16| format!("{}/tcp", container);
+        let host_config = HostConfig {
+            port_bindings: Some(
+                self.ports
+                    .iter()
+                    .map(|(host, container)| {
+                        let binding = PortBinding {
+                            host_ip: None,
+                            host_port: Some(host.to_string()),
+                        };
+                        (port_key(*container), Some(vec![binding]))
+                    })
+                    .collect(),
+            ),
+            binds: Some(
+                self.volumes
+                    .iter()
+                    .map(|(host, container)| format!("{}:{}", host, container))
+                    .collect(),
+            ),
+            network_mode: self.network.map(str::to_string),
+            ulimits,
+            sysctls: Some(
+                self.sysctl
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            extra_hosts: Some(self.extra_hosts.iter().map(|h| h.to_string()).collect()),
+            nano_cpus: self.cpus.map(|cpus| (cpus * 1e9) as i64),
+            cpuset_cpus: self.cpuset_cpus.map(str::to_string),
+            memory,
+            ..Default::default()
+        };
+
+        Ok(ContainerCreateBody {
+            image: Some(format!("{}:latest", self.image)),
+            env: Some(
+                self.env
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect(),
+            ),
+            cmd: (!self.args.is_empty()).then(|| self.args.iter().map(|a| a.to_string()).collect()),
+            exposed_ports: Some(
+                self.ports
+                    .iter()
+                    .map(|(_, container)| (port_key(*container), HashMap::new()))
+                    .collect(),
+            ),
+            host_config: Some(host_config),
+            ..Default::default()
+        })
+    }
+}
+
+/// Parses a Docker memory size (`512m`, `8g`, plain bytes) into bytes.
+fn parse_memory_bytes(value: &str) -> Option<i64> {
+    let value = value.trim().to_ascii_lowercase();
+    let value = value.strip_suffix('b').unwrap_or(&value);
+    let (number, multiplier) = match value.chars().last()? {
+        'k' => (&value[..value.len() - 1], 1024),
+        'm' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    let number: f64 = number.parse().ok()?;
+    Some((number * multiplier as f64) as i64)
 }
 
 impl<'a> fmt::Display for DockerRunCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} run ", docker_cmd(self.sudo))?;
+        write!(f, "{} run ", self.cli)?;
 
         if self.detach {
             write!(f, "-d ")?;
@@ -337,7 +698,19 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         }
 
         if let Some(net) = self.network {
-            write!(f, "--network {} ", net)?;
+            write!(f, "--network {} ", shell_quote(net))?;
+        }
+
+        if let Some(cpus) = self.cpus {
+            write!(f, "--cpus {} ", cpus)?;
+        }
+
+        if let Some(cpuset) = self.cpuset_cpus {
+            write!(f, "--cpuset-cpus {} ", shell_quote(cpuset))?;
+        }
+
+        if let Some(memory) = self.memory {
+            write!(f, "--memory {} ", shell_quote(memory))?;
         }
 
         for (host, container) in &self.ports {
@@ -355,7 +728,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}:latest", self.image)?;
 
         for arg in &self.args {
-            write!(f, " {}", arg)?;
+            write!(f, " {}", shell_quote(arg))?;
         }
 
         Ok(())
@@ -363,17 +736,19 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 pub struct DockerLogsCommand<'a> {
-    sudo: bool,
+    cli: ContainerCli,
     container_name: &'a str,
     follow: bool,
+    stderr_only: bool,
 }
 
 impl<'a> DockerLogsCommand<'a> {
-    pub fn new(sudo: bool, container_name: &'a str) -> Self {
+    pub fn new(cli: ContainerCli, container_name: &'a str) -> Self {
         Self {
-            sudo,
+            cli,
             container_name,
             follow: false,
+            stderr_only: false,
         }
     }
 
@@ -381,17 +756,154 @@ pub fn follow(mut self, follow: bool) -> Self {
         self.follow = follow;
         self
     }
+
+    /// Drop the container's stdout and return only what it wrote to stderr.
+    pub fn stderr_only(mut self, stderr_only: bool) -> Self {
+        self.stderr_only = stderr_only;
+        self
+    }
 }
 
 impl<'a> fmt::Display for DockerLogsCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let follow_arg = if self.follow { "-f " } else { "" };
+        let redirect = if self.stderr_only {
+            "2>&1 >/dev/null"
+        } else {
+            "2>&1"
+        };
         write!(
             f,
-            "{} logs {}{} 2>&1",
-            docker_cmd(self.sudo),
-            follow_arg,
-            self.container_name
+            "{} logs {}{} {}",
+            self.cli, follow_arg, self.container_name, redirect
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ContainerCli, DockerBuildCommand, DockerImageTagsCommand, DockerLogsCommand,
+        DockerPullCommand, DockerRunCommand,
+    };
+    use wfb_storage::ContainerRuntime;
+
+    #[test]
+    fn build_and_run_commands_pass_benchmark_arguments() {
+        let build = DockerBuildCommand::new(ContainerCli::default(), None, "app", "/tmp/app")
+            .with_build_arg("WORKERS", "4")
+            .with_build_arg("GC", "server mode");
+        assert_eq!(
+            build.to_string(),
+            "docker build    --build-arg 'GC=server mode' --build-arg WORKERS=4 -t app:latest /tmp/app"
+        );
+
+        let run = DockerRunCommand::new(ContainerCli::default(), "app", "app")
+            .arg("--workers=4")
+            .arg("it's");
+        assert_eq!(
+            run.to_string(),
+            "docker run -d --name app app:latest --workers=4 'it'\\''s'"
+        );
+    }
+
+    #[test]
+    fn run_command_applies_resource_limits() {
+        let limits = wfb_storage::ResourceLimits {
+            cpus: Some(4.0),
+            cpuset_cpus: Some("0-3".to_string()),
+            memory: Some("8g".to_string()),
+            network: None,
+        };
+        let run = DockerRunCommand::new(ContainerCli::default(), "app", "app").limits(&limits);
+        assert_eq!(
+            run.to_string(),
+            "docker run -d --name app --cpus 4 --cpuset-cpus 0-3 --memory 8g app:latest"
+        );
+    }
+
+    #[test]
+    fn run_command_converts_to_an_api_create_body() {
+        let limits = wfb_storage::ResourceLimits {
+            cpus: Some(1.5),
+            cpuset_cpus: None,
+            memory: Some("8g".to_string()),
+            network: Some("host".to_string()),
+        };
+        let body = DockerRunCommand::new(ContainerCli::default(), "app", "app")
+            .port(54320, 8080)
+            .env("DB_PORT", "54350")
+            .ulimit("nofile=1000000:1000000")
+            .limits(&limits)
+            .arg("--workers=4")
+            .to_create_body()
+            .unwrap();
+        let host = body.host_config.unwrap();
+
+        assert_eq!(body.image.as_deref(), Some("app:latest"));
+        assert_eq!(body.env, Some(vec!["DB_PORT=54350".to_string()]));
+        assert_eq!(body.cmd, Some(vec!["--workers=4".to_string()]));
+        assert_eq!(
+            host.port_bindings.unwrap()["8080/tcp"].as_ref().unwrap()[0]
+                .host_port
+                .as_deref(),
+            Some("54320")
+        );
+        assert_eq!(host.nano_cpus, Some(1_500_000_000));
+        assert_eq!(host.memory, Some(8 * 1024 * 1024 * 1024));
+        assert_eq!(host.network_mode.as_deref(), Some("host"));
+        assert_eq!(host.ulimits.unwrap()[0].hard, Some(1_000_000));
+    }
+
+    #[test]
+    fn commands_follow_the_container_runtime() {
+        let run = |cli| {
+            DockerRunCommand::new(cli, "app", "app")
+                .ulimit("nofile=1000000:1000000")
+                .host_gateway()
+                .to_string()
+        };
+        let podman = ContainerCli::new(ContainerRuntime::Podman, true);
+        let nerdctl = ContainerCli::new(ContainerRuntime::Nerdctl, true);
+
+        assert_eq!(
+            run(ContainerCli::new(ContainerRuntime::Docker, true)),
+            "sudo docker run -d --name app --ulimit nofile=1000000:1000000 --add-host host.docker.internal:host-gateway app:latest"
+        );
+        assert_eq!(
+            run(podman),
+            "podman run -d --name app --ulimit nofile=1000000:1000000 app:latest"
+        );
+        assert_eq!(podman.host_alias(), "host.containers.internal");
+        assert_eq!(
+            run(nerdctl),
+            "sudo nerdctl run -d --name app --ulimit nofile=1000000:1000000 --add-host host.docker.internal:host-gateway app:latest"
+        );
+        assert_eq!(
+            DockerPullCommand::new(podman, "nogcio/wrkr", "latest").to_string(),
+            "podman pull docker.io/nogcio/wrkr:latest"
+        );
+        assert_eq!(
+            DockerPullCommand::new(nerdctl, "nogcio/wrkr", "latest").to_string(),
+            "sudo nerdctl pull nogcio/wrkr:latest"
+        );
+        assert_eq!(
+            DockerImageTagsCommand::new(podman, "app").to_string(),
+            "podman images --format '{{.Tag}}' app"
+        );
+    }
+
+    #[test]
+    fn logs_command_can_keep_only_stderr() {
+        assert_eq!(
+            DockerLogsCommand::new(ContainerCli::default(), "wrkr-runner").to_string(),
+            "docker logs wrkr-runner 2>&1"
+        );
+        assert_eq!(
+            DockerLogsCommand::new(ContainerCli::default(), "wrkr-runner")
+                .stderr_only(true)
+                .to_string(),
+            "docker logs wrkr-runner 2>&1 >/dev/null"
+        );
+    }
+}
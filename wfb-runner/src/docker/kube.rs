@@ -0,0 +1,622 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Context;
+use indicatif::ProgressBar;
+use serde_json::{Value, json};
+use wfb_storage::KubernetesEnvironment;
+
+use super::command::{ContainerCli, DockerRunCommand, DockerTagCommand, shell_quote};
+use super::{ContainerHealth, ContainerStats, HealthStatus};
+use crate::consts;
+use crate::exec::Executor;
+
+/// Label on every object the runner creates, so `prepare` can clear the namespace.
+const MANAGED_LABEL: &str = "wfb.io/managed";
+/// Label tying a pod's services and config maps to it.
+const POD_LABEL: &str = "wfb.io/pod";
+
+/// Container waiting reasons that will not resolve on their own.
+const FATAL_WAITING_REASONS: &[&str] = &[
+    "ErrImagePull",
+    "ImagePullBackOff",
+    "InvalidImageName",
+    "CrashLoopBackOff",
+    "CreateContainerConfigError",
+    "CreateContainerError",
+];
+
+/// Pods of one role (app, database or wrkr) in a Kubernetes namespace, driven with
+/// `kubectl` on this machine.
+///
+/// Every run command becomes a pod pinned to the role's nodes, plus a `wfb-<port>` service
+/// per published port and a config map per mounted directory. Extra hosts are replaced by
+/// those services. A cpuset becomes a whole-CPU request of a Guaranteed pod where possible;
+/// ulimits and other cpusets have no pod equivalent, so running such a command fails unless
+/// the environment sets `allow_dropped_constraints`.
+#[derive(Debug, Clone)]
+pub struct KubeTarget {
+    context: Option<String>,
+    namespace: String,
+    registry: String,
+    node_selector: HashMap<String, String>,
+    allow_dropped_constraints: bool,
+    /// Constraints dropped since the last `take_dropped_constraints`.
+    dropped: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl KubeTarget {
+    pub fn new(env: &KubernetesEnvironment, node_selector: &HashMap<String, String>) -> Self {
+        Self {
+            context: env.context.clone(),
+            namespace: env.namespace.clone(),
+            registry: env.registry.trim_end_matches('/').to_string(),
+            node_selector: node_selector.clone(),
+            allow_dropped_constraints: env.allow_dropped_constraints,
+            dropped: Arc::default(),
+        }
+    }
+
+    /// Constraints dropped from the commands run since the last call.
+    pub fn take_dropped_constraints(&self) -> BTreeSet<String> {
+        std::mem::take(&mut *self.dropped.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Name of the service in front of a published host port.
+    pub fn service_name(port: u16) -> String {
+        format!("wfb-{}", port)
+    }
+
+    fn kubectl(&self) -> String {
+        match &self.context {
+            Some(context) => format!(
+                "kubectl --context {} -n {}",
+                shell_quote(context),
+                shell_quote(&self.namespace)
+            ),
+            None => format!("kubectl -n {}", shell_quote(&self.namespace)),
+        }
+    }
+
+    /// Where the cluster pulls `image` from: local images (no `/`) live in the registry,
+    /// the rest are public images pulled as they are.
+    pub fn image_ref(&self, image: &str) -> String {
+        if image.contains('/') {
+            format!("{}:latest", image)
+        } else {
+            format!("{}/{}:latest", self.registry, image)
+        }
+    }
+
+    /// Tags a freshly built local image for the registry and pushes it.
+    pub async fn push<E: Executor>(
+        &self,
+        executor: &E,
+        cli: ContainerCli,
+        image: &str,
+        pb: &ProgressBar,
+    ) -> anyhow::Result<()> {
+        let image_ref = self.image_ref(image);
+        executor
+            .execute(DockerTagCommand::new(cli, image, &image_ref), pb)
+            .await?;
+        executor
+            .execute(format!("{} push {}", cli, image_ref), pb)
+            .await
+            .map(|_| ())
+    }
+
+    /// The pod, services and config maps for `cmd`, as one `List` for `kubectl apply`.
+    pub fn manifest(&self, cmd: &DockerRunCommand<'_>) -> anyhow::Result<Value> {
+        let pod = pod_name(cmd.name());
+        let labels = json!({ MANAGED_LABEL: "true", POD_LABEL: pod });
+        let metadata = |name: &str| json!({ "name": name, "labels": labels });
+
+        let mut items = Vec::new();
+        let mut volumes = Vec::new();
+        let mut mounts = Vec::new();
+        for (index, (host, container)) in cmd.volumes().iter().enumerate() {
+            let name = format!("{}-v{}", pod, index);
+            let (data, paths) = config_map_files(Path::new(host))?;
+            items.push(json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": metadata(&name),
+                "data": data,
+            }));
+            volumes.push(json!({
+                "name": format!("v{}", index),
+                "configMap": { "name": name, "items": paths },
+            }));
+            mounts.push(json!({ "name": format!("v{}", index), "mountPath": container }));
+        }
+
+        let mut limits = serde_json::Map::new();
+        let exclusive_cpus = exclusive_cpus(cmd)?;
+        if let Some(cpus) = exclusive_cpus.map(f64::from).or(cmd.cpu_limit()) {
+            limits.insert("cpu".into(), json!(cpus.to_string()));
+        }
+        if let Some(memory) = cmd.memory_limit_bytes()? {
+            limits.insert("memory".into(), json!(memory.to_string()));
+        }
+        let resources = if exclusive_cpus.is_some() {
+            json!({ "limits": limits, "requests": limits })
+        } else {
+            json!({ "limits": limits })
+        };
+
+        let mut container = json!({
+            "name": "main",
+            "image": self.image_ref(cmd.image()),
+            "imagePullPolicy": "Always",
+            "env": cmd
+                .env_vars()
+                .iter()
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect::<Vec<_>>(),
+            "ports": cmd
+                .ports()
+                .iter()
+                .map(|(_, container)| json!({ "containerPort": container }))
+                .collect::<Vec<_>>(),
+            "volumeMounts": mounts,
+            "resources": resources,
+        });
+        if !cmd.args().is_empty() {
+            container["args"] = json!(cmd.args());
+        }
+        // Stands in for the image's HEALTHCHECK, which Kubernetes ignores.
+        if let Some((_, port)) = cmd.ports().first() {
+            container["readinessProbe"] = json!({
+                "tcpSocket": { "port": port },
+                "periodSeconds": 1,
+            });
+        }
+
+        let mut spec = json!({
+            "restartPolicy": "Never",
+            "terminationGracePeriodSeconds": 5,
+            "nodeSelector": self.node_selector,
+            "containers": [container],
+            "volumes": volumes,
+        });
+        if cmd.network_name() == Some("host") {
+            spec["hostNetwork"] = json!(true);
+        }
+        if !cmd.sysctls().is_empty() {
+            spec["securityContext"] = json!({
+                "sysctls": cmd
+                    .sysctls()
+                    .iter()
+                    .map(|(name, value)| json!({ "name": name, "value": value }))
+                    .collect::<Vec<_>>(),
+            });
+        }
+        items.push(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": metadata(&pod),
+            "spec": spec,
+        }));
+
+        for (host, container) in cmd.ports() {
+            items.push(json!({
+                "apiVersion": "v1",
+                "kind": "Service",
+                "metadata": metadata(&Self::service_name(*host)),
+                "spec": {
+                    "selector": { POD_LABEL: pod },
+                    "ports": [{ "port": host, "targetPort": container }],
+                },
+            }));
+        }
+
+        Ok(json!({ "apiVersion": "v1", "kind": "List", "items": items }))
+    }
+
+    /// Creates the pod; unless it is detached, streams its output to `on_stdout` until it
+    /// exits, like `docker run`.
+    pub async fn run<E: Executor>(
+        &self,
+        executor: &E,
+        cmd: &DockerRunCommand<'_>,
+        on_stdout: impl Fn(&str) + Send + Sync + 'static,
+        pb: &ProgressBar,
+    ) -> anyhow::Result<String> {
+        let pod = pod_name(cmd.name());
+        let dropped = dropped_constraints(cmd)?;
+        if !dropped.is_empty() {
+            if !self.allow_dropped_constraints {
+                anyhow::bail!(
+                    "Pod {} cannot apply {}; set `allow_dropped_constraints: true` on the \
+                     environment to run without them",
+                    pod,
+                    dropped.join(", ")
+                );
+            }
+            let mut recorded = self.dropped.lock().unwrap_or_else(|e| e.into_inner());
+            for constraint in dropped {
+                if recorded.insert(constraint.clone()) {
+                    pb.println(format!("   ! {}: dropping {}", pod, constraint));
+                }
+            }
+        }
+        // The manifest carries whole script directories, too large for a command line.
+        tokio::fs::create_dir_all(consts::KUBE_MANIFEST_PATH).await?;
+        let manifest_path = format!("{}/{}.json", consts::KUBE_MANIFEST_PATH, pod);
+        tokio::fs::write(&manifest_path, serde_json::to_vec(&self.manifest(cmd)?)?).await?;
+        executor
+            .execute(format!("{} apply -f {}", self.kubectl(), manifest_path), pb)
+            .await
+            .with_context(|| format!("Failed to create pod {}", pod))?;
+        if cmd.is_detached() {
+            return Ok(pod);
+        }
+
+        self.wait_until_started(executor, &pod).await?;
+        let stdout = executor
+            .execute_with_std_out(format!("{} logs -f {}", self.kubectl(), pod), on_stdout, pb)
+            .await?;
+
+        // The pod may still report running for a moment after its log stream ends.
+        for _ in 0..consts::KUBE_POD_RETRIES {
+            let pod_json = self.pod(executor, &pod).await?;
+            if let Some(exit_code) = terminated_exit_code(&pod_json) {
+                if exit_code != 0 {
+                    let logs = self.logs(executor, &pod).await.unwrap_or_default();
+                    anyhow::bail!("Pod {} exited with status {}\n{}", pod, exit_code, logs);
+                }
+                return Ok(stdout);
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        anyhow::bail!("Timeout waiting for pod {} to exit", pod)
+    }
+
+    /// Waits until the pod's container has started, so its logs can be followed.
+    async fn wait_until_started<E: Executor>(&self, executor: &E, pod: &str) -> anyhow::Result<()> {
+        for _ in 0..consts::KUBE_POD_RETRIES {
+            let pod_json = self.pod(executor, pod).await?;
+            let health = pod_health(&pod_json);
+            if health.status == HealthStatus::Unhealthy {
+                anyhow::bail!(
+                    "Pod {} failed to start: {}",
+                    pod,
+                    health.last_log.unwrap_or_default()
+                );
+            }
+            let state = &pod_json["status"]["containerStatuses"][0]["state"];
+            if state.get("running").is_some() || state.get("terminated").is_some() {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        anyhow::bail!("Timeout waiting for pod {} to start", pod)
+    }
+
+    async fn pod<E: Executor>(&self, executor: &E, pod: &str) -> anyhow::Result<Value> {
+        let output = executor
+            .execute(
+                format!("{} get pod {} -o json", self.kubectl(), pod),
+                &ProgressBar::hidden(),
+            )
+            .await?;
+        serde_json::from_str(&output).with_context(|| format!("Invalid pod JSON for {}", pod))
+    }
+
+    pub async fn health<E: Executor>(
+        &self,
+        executor: &E,
+        name: &str,
+    ) -> anyhow::Result<ContainerHealth> {
+        let pod_json = self.pod(executor, &pod_name(name)).await?;
+        Ok(pod_health(&pod_json))
+    }
+
+    /// Samples the pod's usage from the kubelet summary API of its node until the task is
+    /// aborted. The kubelet refreshes these figures every few seconds.
+    pub async fn monitor_stats<E: Executor>(
+        &self,
+        executor: &E,
+        name: &str,
+        on_sample: impl Fn(ContainerStats),
+    ) {
+        let pod = pod_name(name);
+        let pb = ProgressBar::hidden();
+        let mut node = None;
+        loop {
+            if node.is_none() {
+                node =
+                    self.pod(executor, &pod).await.ok().and_then(|pod_json| {
+                        pod_json["spec"]["nodeName"].as_str().map(str::to_string)
+                    });
+            }
+            if let Some(node) = &node {
+                let cmd = format!(
+                    "{} get --raw /api/v1/nodes/{}/proxy/stats/summary",
+                    self.kubectl(),
+                    node
+                );
+                if let Ok(output) = executor.execute(cmd, &pb).await
+                    && let Ok(summary) = serde_json::from_str::<Value>(&output)
+                    && let Some(sample) = summary_sample(&summary, &self.namespace, &pod)
+                {
+                    on_sample(sample);
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// The pod's output; Kubernetes keeps stdout and stderr in one stream.
+    pub async fn logs<E: Executor>(&self, executor: &E, name: &str) -> anyhow::Result<String> {
+        let cmd = format!("{} logs {}", self.kubectl(), pod_name(name));
+        executor.execute(cmd, &ProgressBar::hidden()).await
+    }
+
+    pub async fn logs_follow<E: Executor>(
+        &self,
+        executor: &E,
+        name: &str,
+        on_stdout: impl Fn(&str) + Send + Sync + 'static,
+    ) -> anyhow::Result<String> {
+        let cmd = format!("{} logs -f {}", self.kubectl(), pod_name(name));
+        executor
+            .execute_with_std_out(cmd, on_stdout, &ProgressBar::hidden())
+            .await
+    }
+
+    /// Deletes the pod with its services and config maps, waiting until it is gone so
+    /// the name can be reused.
+    pub async fn stop_and_remove<E: Executor>(&self, executor: &E, name: &str, pb: &ProgressBar) {
+        let cmd = format!(
+            "{} delete pod,service,configmap -l {}={} --ignore-not-found --wait=true",
+            self.kubectl(),
+            POD_LABEL,
+            pod_name(name)
+        );
+        let _ = executor.execute(cmd, pb).await;
+    }
+
+    /// Creates the namespace if needed and deletes everything the runner left in it.
+    pub async fn remove_all<E: Executor>(&self, executor: &E, pb: &ProgressBar) {
+        let namespace = format!(
+            "{} create namespace {} --dry-run=client -o json | {} apply -f -",
+            self.kubectl(),
+            shell_quote(&self.namespace),
+            self.kubectl()
+        );
+        let _ = executor.execute(namespace, pb).await;
+
+        let cmd = format!(
+            "{} delete pod,service,configmap -l {}=true --ignore-not-found --wait=true",
+            self.kubectl(),
+            MANAGED_LABEL
+        );
+        let _ = executor.execute(cmd, pb).await;
+    }
+}
+
+/// Turns a container name into a valid pod name (a DNS label).
+/// Whole CPUs to request for the command's cpuset. With the kubelet's static CPU manager
+/// policy, a Guaranteed pod (requests equal to limits) with an integer CPU count gets that
+/// many exclusive cores, the nearest equivalent of pinning. That needs a memory limit and
+/// no `cpus` limit that differs from the cpuset size.
+fn exclusive_cpus(cmd: &DockerRunCommand<'_>) -> anyhow::Result<Option<u32>> {
+    let Some(count) = cmd.cpuset().and_then(cpuset_size) else {
+        return Ok(None);
+    };
+    if cmd.memory_limit_bytes()?.is_none() {
+        return Ok(None);
+    }
+    Ok(match cmd.cpu_limit() {
+        Some(cpus) if cpus != f64::from(count) => None,
+        _ => Some(count),
+    })
+}
+
+/// Number of CPUs in a cpuset list such as `0-3,8`.
+fn cpuset_size(cpuset: &str) -> Option<u32> {
+    cpuset.split(',').try_fold(0, |count, part| {
+        let size = match part.trim().split_once('-') {
+            Some((first, last)) => last.parse::<u32>().ok()?.checked_sub(first.parse().ok()?)? + 1,
+            None => part.trim().parse::<u32>().map(|_| 1).ok()?,
+        };
+        Some(count + size)
+    })
+}
+
+/// Run options of `cmd` that its pod cannot apply.
+fn dropped_constraints(cmd: &DockerRunCommand<'_>) -> anyhow::Result<Vec<String>> {
+    let mut dropped = Vec::new();
+    if let Some(ulimit) = cmd.ulimit_spec() {
+        dropped.push(format!("ulimit {}", ulimit));
+    }
+    if let Some(cpuset) = cmd.cpuset()
+        && exclusive_cpus(cmd)?.is_none()
+    {
+        dropped.push(format!("cpuset_cpus {}", cpuset));
+    }
+    Ok(dropped)
+}
+
+pub fn pod_name(name: &str) -> String {
+    let name: String = name
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    name[..name.len().min(63)].trim_end_matches('-').to_string()
+}
+
+/// Config map data for the text files under `dir`, with the `items` that lay them out again
+/// under the mount path. Keys are indices since config map keys cannot contain `/`.
+fn config_map_files(dir: &Path) -> anyhow::Result<(BTreeMap<String, String>, Vec<Value>)> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(dir, &mut files).with_context(|| format!("Failed to read {}", dir.display()))?;
+    files.sort();
+
+    let mut data = BTreeMap::new();
+    let mut items = Vec::new();
+    for (index, file) in files.iter().enumerate() {
+        let key = format!("f{}", index);
+        let content = std::fs::read_to_string(file)
+            .with_context(|| format!("Only text files can be mounted: {}", file.display()))?;
+        let path = file.strip_prefix(dir)?.to_string_lossy().to_string();
+        data.insert(key.clone(), content);
+        items.push(json!({ "key": key, "path": path }));
+    }
+    Ok((data, items))
+}
+
+/// Readiness of a pod as a container health: ready is healthy, a failed pod or a container
+/// stuck on an image pull or crash loop is unhealthy.
+fn pod_health(pod: &Value) -> ContainerHealth {
+    let status = &pod["status"];
+    let waiting = &status["containerStatuses"][0]["state"]["waiting"];
+    let last_log = waiting["reason"]
+        .as_str()
+        .map(|reason| match waiting["message"].as_str() {
+            Some(message) => format!("{}: {}", reason, message),
+            None => reason.to_string(),
+        });
+
+    let ready = status["conditions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|c| c["type"] == "Ready" && c["status"] == "True");
+    let fatal = waiting["reason"]
+        .as_str()
+        .is_some_and(|reason| FATAL_WAITING_REASONS.contains(&reason));
+
+    let status = if status["phase"] == "Failed" || fatal {
+        HealthStatus::Unhealthy
+    } else if ready {
+        HealthStatus::Healthy
+    } else {
+        HealthStatus::Starting
+    };
+    ContainerHealth { status, last_log }
+}
+
+fn terminated_exit_code(pod: &Value) -> Option<i64> {
+    pod["status"]["containerStatuses"][0]["state"]["terminated"]["exitCode"].as_i64()
+}
+
+/// The pod's entry in a kubelet stats summary: working set memory, which is what
+/// `docker stats` reports, and CPU as a percentage of one core.
+fn summary_sample(summary: &Value, namespace: &str, pod: &str) -> Option<ContainerStats> {
+    let stats = summary["pods"].as_array()?.iter().find(|stats| {
+        stats["podRef"]["name"] == pod && stats["podRef"]["namespace"] == namespace
+    })?;
+    Some(ContainerStats {
+        memory_bytes: stats["memory"]["workingSetBytes"].as_u64()?,
+        cpu_percent: stats["cpu"]["usageNanoCores"].as_f64()? / 1e7,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KubeTarget, dropped_constraints, pod_health, pod_name, summary_sample};
+    use crate::docker::HealthStatus;
+    use crate::docker::command::{ContainerCli, DockerRunCommand};
+    use serde_json::json;
+    use wfb_storage::KubernetesEnvironment;
+
+    #[test]
+    fn run_command_becomes_a_pinned_pod_with_a_service() {
+        let env: KubernetesEnvironment = serde_json::from_value(json!({
+            "name": "kind",
+            "title": "kind",
+            "registry": "localhost:5001/",
+            "nodes": { "app": { "wfb.io/role": "app" } },
+        }))
+        .unwrap();
+        let target = KubeTarget::new(&env, &env.nodes.app);
+        let cmd = DockerRunCommand::new(ContainerCli::default(), "spring_boot", "spring_boot")
+            .port(54320, 8080)
+            .env("PORT", "8080")
+            .cpus(2.0)
+            .memory("1g")
+            .ulimit("nofile=1000000:1000000");
+
+        let manifest = target.manifest(&cmd).unwrap();
+        let pod = &manifest["items"][0];
+        assert_eq!(pod["metadata"]["name"], "spring-boot");
+        assert_eq!(pod["spec"]["nodeSelector"]["wfb.io/role"], "app");
+        let container = &pod["spec"]["containers"][0];
+        assert_eq!(container["image"], "localhost:5001/spring_boot:latest");
+        assert_eq!(container["resources"]["limits"]["memory"], "1073741824");
+        assert_eq!(container["readinessProbe"]["tcpSocket"]["port"], 8080);
+
+        let service = &manifest["items"][1];
+        assert_eq!(service["metadata"]["name"], "wfb-54320");
+        assert_eq!(service["spec"]["selector"]["wfb.io/pod"], "spring-boot");
+        assert_eq!(service["spec"]["ports"][0]["port"], 54320);
+        assert_eq!(target.image_ref("nogcio/wrkr"), "nogcio/wrkr:latest");
+        assert_eq!(pod_name("Wrkr_Runner-"), "wrkr-runner");
+
+        let pulling = json!({ "status": { "phase": "Pending", "containerStatuses": [
+            { "state": { "waiting": { "reason": "ImagePullBackOff" } } }
+        ] } });
+        assert_eq!(pod_health(&pulling).status, HealthStatus::Unhealthy);
+        let ready = json!({ "status": { "phase": "Running", "conditions": [
+            { "type": "Ready", "status": "True" }
+        ] } });
+        assert_eq!(pod_health(&ready).status, HealthStatus::Healthy);
+
+        let summary = json!({ "pods": [{
+            "podRef": { "name": "spring-boot", "namespace": "wfb" },
+            "cpu": { "usageNanoCores": 2_500_000_000u64 },
+            "memory": { "workingSetBytes": 1024 },
+        }] });
+        let sample = summary_sample(&summary, "wfb", "spring-boot").unwrap();
+        assert_eq!(sample.cpu_percent, 250.0);
+        assert_eq!(sample.memory_bytes, 1024);
+    }
+
+    #[test]
+    fn cpusets_become_guaranteed_cpu_requests_and_ulimits_are_dropped() {
+        let env: KubernetesEnvironment = serde_json::from_value(json!({
+            "name": "kind",
+            "title": "kind",
+            "registry": "localhost:5001",
+        }))
+        .unwrap();
+        let target = KubeTarget::new(&env, &env.nodes.app);
+        let pinned = DockerRunCommand::new(ContainerCli::default(), "axum", "axum")
+            .cpuset_cpus("0-2,5")
+            .memory("1g");
+
+        let manifest = target.manifest(&pinned).unwrap();
+        let resources = &manifest["items"][0]["spec"]["containers"][0]["resources"];
+        assert_eq!(resources["limits"]["cpu"], "4");
+        assert_eq!(resources["requests"], resources["limits"]);
+        assert!(dropped_constraints(&pinned).unwrap().is_empty());
+
+        // Without a memory limit the pod cannot be Guaranteed, so the cpuset is dropped.
+        let unpinned = DockerRunCommand::new(ContainerCli::default(), "axum", "axum")
+            .cpuset_cpus("0-3")
+            .ulimit("nofile=1000000:1000000");
+        assert_eq!(
+            dropped_constraints(&unpinned).unwrap(),
+            ["ulimit nofile=1000000:1000000", "cpuset_cpus 0-3"]
+        );
+    }
+}
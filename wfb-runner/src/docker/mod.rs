@@ -1,23 +1,109 @@
 #![allow(dead_code)]
+pub mod api;
 pub mod command;
+pub mod kube;
 
+use self::api::DockerApi;
 use self::command::{
-    DockerBuildCommand, DockerInspectCommand, DockerLoadCommand, DockerLogsCommand,
-    DockerPullCommand, DockerRmCommand, DockerRunCommand, DockerSaveCommand, DockerStatsCommand,
-    DockerStopCommand,
+    ContainerCli, DockerBuildCommand, DockerImageInspectCommand, DockerImageTagsCommand,
+    DockerInspectCommand, DockerLoadCommand, DockerLogsCommand, DockerPullCommand, DockerRmCommand,
+    DockerRmiCommand, DockerRunCommand, DockerSaveCommand, DockerStatsCommand, DockerStopCommand,
+    DockerTagCommand,
 };
+use self::kube::KubeTarget;
 use crate::exec::Executor;
 use indicatif::ProgressBar;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 
+/// Health check state of a container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Starting,
+    Healthy,
+    Unhealthy,
+    /// The container has no health check.
+    None,
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            HealthStatus::Starting => "starting",
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Unhealthy => "unhealthy",
+            HealthStatus::None => "none",
+        };
+        f.write_str(status)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerHealth {
+    pub status: HealthStatus,
+    /// Output of the most recent health check, as `[exit code] output`.
+    pub last_log: Option<String>,
+}
+
+/// One resource usage sample of a container.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContainerStats {
+    pub memory_bytes: u64,
+    /// Percentage of one core, so a container busy on 4 cores reports 400.
+    pub cpu_percent: f64,
+}
+
+/// How a `DockerManager` reaches its daemon.
+#[derive(Clone)]
+enum Backend {
+    /// `docker` CLI commands run through the executor.
+    Cli,
+    Api(DockerApi),
+    /// Pods in a Kubernetes cluster, through `kubectl` run by the executor.
+    Kubernetes(KubeTarget),
+}
+
+/// Container operations on one host, through the `docker` CLI or the Engine API, or on
+/// pods of a Kubernetes cluster.
+///
+/// Image builds and transfers (`build`, `save`, `load`, `pull`) always use the CLI, since
+/// they work on files next to the executor.
 #[derive(Clone)]
 pub struct DockerManager<E: Executor> {
     executor: E,
-    sudo: bool,
+    cli: ContainerCli,
+    backend: Backend,
 }
 
 impl<E: Executor> DockerManager<E> {
-    pub fn new(executor: E, sudo: bool) -> Self {
-        Self { executor, sudo }
+    pub fn new(executor: E, cli: ContainerCli) -> Self {
+        Self {
+            executor,
+            cli,
+            backend: Backend::Cli,
+        }
+    }
+
+    /// Switches container operations to the Engine API.
+    pub fn with_api(mut self, api: DockerApi) -> Self {
+        self.backend = Backend::Api(api);
+        self
+    }
+
+    /// Runs containers as pods of a Kubernetes cluster; built images are pushed to its
+    /// registry.
+    pub fn with_kubernetes(mut self, target: KubeTarget) -> Self {
+        self.backend = Backend::Kubernetes(target);
+        self
+    }
+
+    /// Run options the backend could not apply since the last call; only pods drop any.
+    pub fn take_dropped_constraints(&self) -> std::collections::BTreeSet<String> {
+        match &self.backend {
+            Backend::Kubernetes(target) => target.take_dropped_constraints(),
+            Backend::Cli | Backend::Api(_) => Default::default(),
+        }
     }
 
     pub async fn build(
@@ -25,10 +111,84 @@ pub async fn build(
         docker_file: Option<&str>,
         image_name: &str,
         context_path: &str,
+        build_args: &HashMap<String, String>,
         pb: &ProgressBar,
     ) -> anyhow::Result<()> {
-        let cmd = DockerBuildCommand::new(self.sudo, docker_file, image_name, context_path);
-        self.executor.execute(cmd, pb).await.map(|_| ())
+        let mut cmd = DockerBuildCommand::new(self.cli, docker_file, image_name, context_path);
+        for (key, value) in build_args {
+            cmd = cmd.with_build_arg(key, value);
+        }
+        self.executor.execute(cmd, pb).await?;
+        if let Backend::Kubernetes(target) = &self.backend {
+            target
+                .push(&self.executor, self.cli, image_name, pb)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Builds `image_name` unless an image of the same context hash was built before, in
+    /// which case that image is tagged as the latest instead. Returns whether it built.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn build_cached(
+        &self,
+        docker_file: Option<&str>,
+        image_name: &str,
+        context_path: &str,
+        build_args: &HashMap<String, String>,
+        context_hash: &str,
+        pb: &ProgressBar,
+    ) -> anyhow::Result<bool> {
+        let cached = format!("{}:ctx-{}", image_name, context_hash);
+        let inspect = DockerImageInspectCommand::new(self.cli, &cached);
+        if self
+            .executor
+            .execute(inspect, &ProgressBar::hidden())
+            .await
+            .is_err()
+        {
+            self.build(docker_file, image_name, context_path, build_args, pb)
+                .await?;
+            let tag = DockerTagCommand::new(self.cli, image_name, &cached);
+            self.executor.execute(tag, pb).await?;
+            self.remove_stale_context_tags(image_name, &cached, pb)
+                .await;
+            return Ok(true);
+        }
+
+        let tag = DockerTagCommand::new(self.cli, &cached, image_name);
+        self.executor.execute(tag, pb).await?;
+        if let Backend::Kubernetes(target) = &self.backend {
+            target
+                .push(&self.executor, self.cli, image_name, pb)
+                .await?;
+        }
+        Ok(false)
+    }
+
+    /// Untags the images built from earlier contexts of `image_name`, so every change of
+    /// a context doesn't leave another image behind on the build host. Best effort: an
+    /// image still used by a container stays.
+    async fn remove_stale_context_tags(&self, image_name: &str, current: &str, pb: &ProgressBar) {
+        let tags = DockerImageTagsCommand::new(self.cli, image_name);
+        let Ok(tags) = self.executor.execute(tags, &ProgressBar::hidden()).await else {
+            return;
+        };
+        for tag in tags.lines().map(str::trim) {
+            let image = format!("{}:{}", image_name, tag);
+            if !tag.starts_with("ctx-") || image == current {
+                continue;
+            }
+            let rmi = DockerRmiCommand::new(self.cli, &image);
+            if let Err(e) = self.executor.execute(rmi, &ProgressBar::hidden()).await {
+                pb.println(format!(
+                    "   {} Failed to remove {}: {:#}",
+                    console::style("!").yellow(),
+                    image,
+                    e
+                ));
+            }
+        }
     }
 
     pub async fn build_with_platform_and_output(
@@ -40,7 +200,7 @@ pub async fn build_with_platform_and_output(
         output: &str,
         pb: &ProgressBar,
     ) -> anyhow::Result<()> {
-        let cmd = DockerBuildCommand::new(self.sudo, docker_file, image_name, context_path)
+        let cmd = DockerBuildCommand::new(self.cli, docker_file, image_name, context_path)
             .with_platform(platform)
             .with_output(output);
         self.executor.execute(cmd, pb).await.map(|_| ())
@@ -52,40 +212,60 @@ pub async fn save(
         output_path: &str,
         pb: &ProgressBar,
     ) -> anyhow::Result<()> {
-        let cmd = DockerSaveCommand::new(self.sudo, image_name, output_path);
+        let cmd = DockerSaveCommand::new(self.cli, image_name, output_path);
         self.executor.execute(cmd, pb).await.map(|_| ())
     }
 
     pub async fn load(&self, input_path: &str, pb: &ProgressBar) -> anyhow::Result<()> {
-        let cmd = DockerLoadCommand::new(self.sudo, input_path);
+        let cmd = DockerLoadCommand::new(self.cli, input_path);
         self.executor.execute(cmd, pb).await.map(|_| ())
     }
 
     pub async fn pull(&self, image: &str, tag: &str, pb: &ProgressBar) -> anyhow::Result<()> {
-        let cmd = DockerPullCommand::new(self.sudo, image, tag);
+        // The cluster's nodes pull images themselves when the pod starts.
+        if let Backend::Kubernetes(_) = &self.backend {
+            return Ok(());
+        }
+        let cmd = DockerPullCommand::new(self.cli, image, tag);
         self.executor.execute(cmd, pb).await.map(|_| ())
     }
 
     pub async fn stop_and_remove(&self, container_name: &str, pb: &ProgressBar) {
-        let stop_cmd = DockerStopCommand::new(self.sudo, container_name);
+        match &self.backend {
+            Backend::Api(api) => return api.stop_and_remove(container_name).await,
+            Backend::Kubernetes(target) => {
+                return target
+                    .stop_and_remove(&self.executor, container_name, pb)
+                    .await;
+            }
+            Backend::Cli => {}
+        }
+
+        let stop_cmd = DockerStopCommand::new(self.cli, container_name);
         let _ = self.executor.execute(stop_cmd, pb).await;
 
-        let rm_cmd = DockerRmCommand::new(self.sudo, container_name);
+        let rm_cmd = DockerRmCommand::new(self.cli, container_name);
         let _ = self.executor.execute(rm_cmd, pb).await;
     }
 
     pub async fn stop_all_containers(&self, pb: &ProgressBar) {
-        let docker = if self.sudo { "sudo docker" } else { "docker" };
+        match &self.backend {
+            Backend::Api(api) => return api.remove_all_containers().await,
+            Backend::Kubernetes(target) => return target.remove_all(&self.executor, pb).await,
+            Backend::Cli => {}
+        }
+
+        let cli = self.cli;
         // We use || true to ignore errors if no containers exist
-        let cmd = format!("{} stop $({} ps -aq) || true", docker, docker);
+        let cmd = format!("{} stop $({} ps -aq) || true", cli, cli);
         let _ = self.executor.execute(cmd, pb).await;
 
-        let cmd = format!("{} rm $({} ps -aq) || true", docker, docker);
+        let cmd = format!("{} rm $({} ps -aq) || true", cli, cli);
         let _ = self.executor.execute(cmd, pb).await;
     }
 
     pub fn run_command<'a>(&'a self, image: &'a str, name: &'a str) -> DockerRunCommand<'a> {
-        DockerRunCommand::new(self.sudo, image, name)
+        DockerRunCommand::new(self.cli, image, name)
     }
 
     pub async fn execute_run(
@@ -93,7 +273,11 @@ pub async fn execute_run(
         cmd: DockerRunCommand<'_>,
         pb: &ProgressBar,
     ) -> anyhow::Result<String> {
-        self.executor.execute(cmd, pb).await
+        match &self.backend {
+            Backend::Cli => self.executor.execute(cmd, pb).await,
+            Backend::Api(api) => api.run(&cmd, |_| {}).await,
+            Backend::Kubernetes(target) => target.run(&self.executor, &cmd, |_| {}, pb).await,
+        }
     }
 
     pub async fn execute_run_with_std_out(
@@ -102,23 +286,99 @@ pub async fn execute_run_with_std_out(
         on_stdout: impl Fn(&str) + Send + Sync + 'static,
         pb: &ProgressBar,
     ) -> anyhow::Result<String> {
-        self.executor.execute_with_std_out(cmd, on_stdout, pb).await
+        match &self.backend {
+            Backend::Cli => self.executor.execute_with_std_out(cmd, on_stdout, pb).await,
+            Backend::Api(api) => api.run(&cmd, on_stdout).await,
+            Backend::Kubernetes(target) => target.run(&self.executor, &cmd, on_stdout, pb).await,
+        }
     }
 
-    pub async fn inspect(&self, container_name: &str, format: &str) -> anyhow::Result<String> {
-        let cmd = DockerInspectCommand::new(self.sudo, container_name, format);
-        let pb = ProgressBar::hidden();
-        self.executor.execute(cmd, &pb).await
+    pub async fn health(&self, container_name: &str) -> anyhow::Result<ContainerHealth> {
+        match &self.backend {
+            Backend::Api(api) => return api.health(container_name).await,
+            Backend::Kubernetes(target) => {
+                return target.health(&self.executor, container_name).await;
+            }
+            Backend::Cli => {}
+        }
+
+        let format = "{{if .State.Health}}{{.State.Health.Status}}|{{range .State.Health.Log}}[{{.ExitCode}}] {{.Output}}__SEP__{{end}}{{else}}none{{end}}";
+        let cmd = DockerInspectCommand::new(self.cli, container_name, format);
+        let output = self.executor.execute(cmd, &ProgressBar::hidden()).await?;
+        let (status, health_log) = match output.split_once('|') {
+            Some((s, l)) => (s.trim(), l),
+            None => (output.trim(), ""),
+        };
+
+        let status = match status {
+            "starting" => HealthStatus::Starting,
+            "healthy" => HealthStatus::Healthy,
+            "unhealthy" => HealthStatus::Unhealthy,
+            _ => HealthStatus::None,
+        };
+        let last_log = health_log
+            .split("__SEP__")
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .last()
+            .map(str::to_string);
+        Ok(ContainerHealth { status, last_log })
     }
 
-    pub async fn stats(&self, container_name: &str, format: &str) -> anyhow::Result<String> {
-        let cmd = DockerStatsCommand::new(self.sudo, container_name, format);
+    /// Calls `on_sample` with the container's usage about once a second until the task is
+    /// aborted.
+    pub async fn monitor_stats(&self, container_name: &str, on_sample: impl Fn(ContainerStats)) {
+        match &self.backend {
+            Backend::Api(api) => return api.monitor_stats(container_name, on_sample).await,
+            Backend::Kubernetes(target) => {
+                return target
+                    .monitor_stats(&self.executor, container_name, on_sample)
+                    .await;
+            }
+            Backend::Cli => {}
+        }
+
+        loop {
+            let cmd =
+                DockerStatsCommand::new(self.cli, container_name, "{{.MemUsage}}::{{.CPUPerc}}");
+            if let Ok(stats) = self.executor.execute(cmd, &ProgressBar::hidden()).await {
+                // stats output might be "10MiB / 1GiB::0.05%"
+                if let Some((mem, cpu)) = stats.split_once("::") {
+                    let mem = mem.trim().split('/').next().unwrap_or("0B").trim();
+                    on_sample(ContainerStats {
+                        memory_bytes: parse_docker_memory(mem),
+                        cpu_percent: parse_docker_cpu(cpu),
+                    });
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    pub async fn logs(&self, container_name: &str) -> anyhow::Result<String> {
+        match &self.backend {
+            Backend::Api(api) => return api.logs(container_name, true, true).await,
+            Backend::Kubernetes(target) => {
+                return target.logs(&self.executor, container_name).await;
+            }
+            Backend::Cli => {}
+        }
+
+        let cmd = DockerLogsCommand::new(self.cli, container_name);
         let pb = ProgressBar::hidden();
         self.executor.execute(cmd, &pb).await
     }
 
-    pub async fn logs(&self, container_name: &str) -> anyhow::Result<String> {
-        let cmd = DockerLogsCommand::new(self.sudo, container_name);
+    pub async fn stderr_logs(&self, container_name: &str) -> anyhow::Result<String> {
+        match &self.backend {
+            Backend::Api(api) => return api.logs(container_name, false, true).await,
+            Backend::Kubernetes(target) => {
+                return target.logs(&self.executor, container_name).await;
+            }
+            Backend::Cli => {}
+        }
+
+        let cmd = DockerLogsCommand::new(self.cli, container_name).stderr_only(true);
         let pb = ProgressBar::hidden();
         self.executor.execute(cmd, &pb).await
     }
@@ -128,10 +388,46 @@ pub async fn logs_follow(
         container_name: &str,
         on_stdout: impl Fn(&str) + Send + Sync + 'static,
     ) -> anyhow::Result<String> {
-        let cmd = DockerLogsCommand::new(self.sudo, container_name).follow(true);
+        match &self.backend {
+            Backend::Api(api) => return api.logs_follow(container_name, on_stdout).await,
+            Backend::Kubernetes(target) => {
+                return target
+                    .logs_follow(&self.executor, container_name, on_stdout)
+                    .await;
+            }
+            Backend::Cli => {}
+        }
+
+        let cmd = DockerLogsCommand::new(self.cli, container_name).follow(true);
         let pb = ProgressBar::hidden();
         self.executor
             .execute_with_std_out(cmd, on_stdout, &pb)
             .await
     }
 }
+
+fn parse_docker_memory(s: &str) -> u64 {
+    let s = s.trim();
+    let (num_str, multiplier) = if s.ends_with("GiB") {
+        (s.trim_end_matches("GiB"), 1024.0 * 1024.0 * 1024.0)
+    } else if s.ends_with("MiB") {
+        (s.trim_end_matches("MiB"), 1024.0 * 1024.0)
+    } else if s.ends_with("KiB") {
+        (s.trim_end_matches("KiB"), 1024.0)
+    } else if s.ends_with("B") {
+        (s.trim_end_matches("B"), 1.0)
+    } else {
+        return 0;
+    };
+
+    if let Ok(num) = num_str.trim().parse::<f64>() {
+        (num * multiplier) as u64
+    } else {
+        0
+    }
+}
+
+fn parse_docker_cpu(s: &str) -> f64 {
+    let s = s.trim().trim_end_matches('%');
+    s.parse::<f64>().unwrap_or(0.0)
+}
@@ -1,43 +1,198 @@
 use super::{Executor, OutputLogger};
+use crate::consts;
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use indicatif::ProgressBar;
-use ssh2::Session;
+use ssh2::{Channel, Session};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::net::TcpStream;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Runs commands on one host over SSH.
+///
+/// Clones share a pool of authenticated sessions, and commands run as channels multiplexed
+/// on those sessions instead of opening a new connection each time. Build one executor per
+/// host and clone it wherever that host is used (see `runner::factory`).
 #[derive(Clone)]
 pub struct SshExecutor {
-    host: String,
-    port: u16,
-    username: String,
-    private_key_path: PathBuf,
+    pool: Arc<SessionPool>,
 }
 
 impl SshExecutor {
     pub fn new(host: String, port: u16, username: String, private_key_path: PathBuf) -> Self {
         Self {
-            host,
-            port,
-            username,
-            private_key_path,
+            pool: Arc::new(SessionPool {
+                host,
+                port,
+                username,
+                private_key_path,
+                sessions: Mutex::new(Vec::new()),
+            }),
         }
     }
 
     pub fn from_config(config: &wfb_storage::SshConnection) -> Self {
         SshExecutor::new(
             config.ip.clone(),
-            22,
+            config.port,
             config.user.clone(),
             config.ssh_key_path.clone(),
         )
     }
 }
 
+/// Sessions to one host. Channels are opened on an existing session until it carries
+/// `SSH_MAX_CHANNELS_PER_SESSION` of them; only then is another session connected.
+struct SessionPool {
+    host: String,
+    port: u16,
+    username: String,
+    private_key_path: PathBuf,
+    sessions: Mutex<Vec<Arc<SharedSession>>>,
+}
+
+/// A non-blocking session shared by concurrent channels. Every libssh2 call on the session
+/// or one of its channels is made under `lock`, so channels interleave without any call
+/// waiting on the network while holding it.
+struct SharedSession {
+    session: Session,
+    lock: Mutex<()>,
+    channels: AtomicUsize,
+}
+
+impl SharedSession {
+    /// Runs `op` under the session lock until it stops reporting `EAGAIN`.
+    fn call<T>(&self, mut op: impl FnMut(&Session) -> Result<T, ssh2::Error>) -> Result<T> {
+        loop {
+            let result = {
+                let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+                op(&self.session)
+            };
+            match result {
+                Err(e) if would_block(&e) => std::thread::sleep(SSH_POLL_INTERVAL),
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    /// Runs one non-blocking read or write under the session lock.
+    fn io<T>(&self, op: impl FnOnce() -> std::io::Result<T>) -> std::io::Result<T> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        op()
+    }
+
+    /// Writes all of `data`, waiting out `WouldBlock` without holding the lock.
+    fn write_all(&self, writer: &mut impl Write, mut data: &[u8]) -> std::io::Result<()> {
+        while !data.is_empty() {
+            match self.io(|| writer.write(data)) {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+                Ok(n) => data = &data[n..],
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(SSH_POLL_INTERVAL)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+const SSH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// `LIBSSH2_ERROR_EAGAIN`: a non-blocking call has to be repeated.
+const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+
+fn would_block(e: &ssh2::Error) -> bool {
+    e.code() == ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN)
+}
+
+/// A channel and the session it counts against; releases its slot when dropped.
+struct PooledChannel {
+    channel: Channel,
+    session: Arc<SharedSession>,
+}
+
+impl Drop for PooledChannel {
+    fn drop(&mut self) {
+        self.session.channels.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A session slot taken without a channel, for SCP/SFTP transfers.
+struct SessionLease(Arc<SharedSession>);
+
+impl Drop for SessionLease {
+    fn drop(&mut self) {
+        self.0.channels.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl SessionPool {
+    fn connect(&self) -> Result<SharedSession> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port))
+            .context("Failed to connect to SSH host")?;
+        let mut sess = Session::new().context("Failed to create SSH session")?;
+        sess.set_tcp_stream(tcp);
+        sess.handshake().context("SSH handshake failed")?;
+        sess.userauth_pubkey_file(&self.username, None, &self.private_key_path, None)
+            .context("SSH authentication failed")?;
+        sess.set_keepalive(true, consts::SSH_KEEPALIVE_INTERVAL_SECS);
+        sess.set_blocking(false);
+        Ok(SharedSession {
+            session: sess,
+            lock: Mutex::new(()),
+            channels: AtomicUsize::new(0),
+        })
+    }
+
+    /// Takes a slot on the least busy session below the channel limit, connecting a new
+    /// session only when every live one is full. Also reports whether the session is new.
+    fn lease(&self) -> Result<(SessionLease, bool)> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        // A keepalive both keeps idle sessions open and finds the ones the host dropped.
+        sessions.retain(|s| s.call(|sess| sess.keepalive_send()).is_ok());
+
+        let (session, fresh) = match sessions
+            .iter()
+            .filter(|s| s.channels.load(Ordering::Relaxed) < consts::SSH_MAX_CHANNELS_PER_SESSION)
+            .min_by_key(|s| s.channels.load(Ordering::Relaxed))
+        {
+            Some(session) => (session.clone(), false),
+            None => {
+                let session = Arc::new(self.connect()?);
+                sessions.push(session.clone());
+                (session, true)
+            }
+        };
+        session.channels.fetch_add(1, Ordering::Relaxed);
+        Ok((SessionLease(session), fresh))
+    }
+
+    /// Opens a channel, dropping an existing session that can no longer open one.
+    fn channel(&self) -> Result<PooledChannel> {
+        loop {
+            let (lease, fresh) = self.lease()?;
+            let session = lease.0.clone();
+            match session.call(|sess| sess.channel_session()) {
+                Ok(channel) => {
+                    // The slot moves from the lease to the channel.
+                    std::mem::forget(lease);
+                    return Ok(PooledChannel { channel, session });
+                }
+                Err(_) if !fresh => self
+                    .sessions
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .retain(|s| !Arc::ptr_eq(s, &session)),
+                Err(e) => return Err(e).context("Failed to create SSH channel"),
+            }
+        }
+    }
+}
+
 struct LineBuffer {
     buf: Vec<u8>,
 }
@@ -88,7 +243,7 @@ fn get_dir_size_sync(path: &Path) -> Result<u64> {
 }
 
 fn upload_recursive_sync<F>(
-    sess: &Session,
+    sess: &SharedSession,
     src: &Path,
     dst: &Path,
     total_size: u64,
@@ -99,12 +254,9 @@ fn upload_recursive_sync<F>(
     F: Fn(&str, u64, u64) + Send + 'static,
 {
     if src.is_dir() {
-        let sftp = sess.sftp().context("Failed to init SFTP")?;
-        match sftp.stat(dst) {
-            Ok(_) => {} // Exists
-            Err(_) => {
-                let _ = sftp.mkdir(dst, 0o755);
-            }
+        let sftp = sess.call(|s| s.sftp()).context("Failed to init SFTP")?;
+        if sess.call(|_| sftp.stat(dst)).is_err() {
+            let _ = sess.call(|_| sftp.mkdir(dst, 0o755));
         }
 
         for entry in std::fs::read_dir(src)? {
@@ -126,7 +278,7 @@ fn upload_recursive_sync<F>(
         let file_size = metadata.len();
 
         let mut remote_file = sess
-            .scp_send(dst, 0o644, file_size, None)
+            .call(|s| s.scp_send(dst, 0o644, file_size, None))
             .context("Failed to start SCP send")?;
 
         let mut buffer = [0u8; 8192];
@@ -138,19 +290,20 @@ fn upload_recursive_sync<F>(
             if n == 0 {
                 break;
             }
-            remote_file
-                .write_all(&buffer[..n])
+            sess.write_all(&mut remote_file, &buffer[..n])
                 .context("Failed to write to remote SCP")?;
             let c = copied.fetch_add(n as u64, Ordering::Relaxed) + n as u64;
             on_progress(&src.to_string_lossy(), c, total_size);
         }
 
         // Close scp channel for this file
-        remote_file.send_eof().context("Failed to send EOF")?;
-        remote_file.wait_eof().context("Failed to wait for EOF")?;
-        remote_file.close().context("Failed to close channel")?;
-        remote_file
-            .wait_close()
+        sess.call(|_| remote_file.send_eof())
+            .context("Failed to send EOF")?;
+        sess.call(|_| remote_file.wait_eof())
+            .context("Failed to wait for EOF")?;
+        sess.call(|_| remote_file.close())
+            .context("Failed to close channel")?;
+        sess.call(|_| remote_file.wait_close())
             .context("Failed to wait for close")?;
     }
     Ok(())
@@ -176,32 +329,19 @@ async fn execute_with_std_out<S, F>(
         S: std::fmt::Display + Send + Sync,
     {
         let script = script.to_string();
+        let pool = self.pool.clone();
         let logger = Arc::new(OutputLogger::new(
             pb.clone(),
-            format!("ssh {}@{} {}", self.username, self.host, script),
+            format!("ssh {}@{} {}", pool.username, pool.host, script),
         ));
 
-        let host = self.host.clone();
-        let port = self.port;
-        let username = self.username.clone();
-        let private_key_path = self.private_key_path.clone();
-
         tokio::task::spawn_blocking(move || {
-            let tcp = TcpStream::connect((host.as_str(), port))
-                .context("Failed to connect to SSH host")?;
-            let mut sess = Session::new().context("Failed to create SSH session")?;
-            sess.set_tcp_stream(tcp);
-            sess.handshake().context("SSH handshake failed")?;
-
-            sess.userauth_pubkey_file(&username, None, &private_key_path, None)
-                .context("SSH authentication failed")?;
-
-            let mut channel = sess
-                .channel_session()
-                .context("Failed to create SSH channel")?;
-            channel.exec(&script).context("Failed to execute script")?;
-
-            sess.set_blocking(false);
+            let mut pooled = pool.channel()?;
+            let session = pooled.session.clone();
+            let channel = &mut pooled.channel;
+            session
+                .call(|_| channel.exec(&script))
+                .context("Failed to execute script")?;
 
             let mut stdout_buf = [0u8; 4096];
             let mut stderr_buf = [0u8; 4096];
@@ -216,7 +356,7 @@ async fn execute_with_std_out<S, F>(
                 let mut did_work = false;
 
                 if !stdout_closed {
-                    match channel.read(&mut stdout_buf) {
+                    match session.io(|| channel.read(&mut stdout_buf)) {
                         Ok(0) => stdout_closed = true,
                         Ok(n) => {
                             did_work = true;
@@ -234,7 +374,7 @@ async fn execute_with_std_out<S, F>(
                 }
 
                 if !stderr_closed {
-                    match channel.stderr().read(&mut stderr_buf) {
+                    match session.io(|| channel.stderr().read(&mut stderr_buf)) {
                         Ok(0) => stderr_closed = true,
                         Ok(n) => {
                             did_work = true;
@@ -249,7 +389,7 @@ async fn execute_with_std_out<S, F>(
                 }
 
                 if !did_work && (!stdout_closed || !stderr_closed) {
-                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    std::thread::sleep(SSH_POLL_INTERVAL);
                 }
             }
 
@@ -262,8 +402,8 @@ async fn execute_with_std_out<S, F>(
                 logger.on_stderr(&s);
             }
 
-            channel.wait_close()?;
-            let exit_status = channel.exit_status()?;
+            session.call(|_| channel.wait_close())?;
+            let exit_status = session.call(|_| channel.exit_status())?;
             if exit_status != 0 {
                 let stderr = logger.get_stderr();
                 let last_lines = logger.get_last_lines_plain();
@@ -304,10 +444,7 @@ async fn rm(&self, path: &str) -> Result<(), anyhow::Error> {
     }
 
     async fn cp(&self, src: &str, dst: &str, pb: &ProgressBar) -> Result<(), anyhow::Error> {
-        let host = self.host.clone();
-        let port = self.port;
-        let username = self.username.clone();
-        let private_key_path = self.private_key_path.clone();
+        let pool = self.pool.clone();
         let src = src.to_string();
         let dst = dst.to_string();
         let pb_clone = pb.clone();
@@ -325,13 +462,7 @@ async fn cp(&self, src: &str, dst: &str, pb: &ProgressBar) -> Result<(), anyhow:
         };
 
         tokio::task::spawn_blocking(move || {
-            let tcp = TcpStream::connect((host.as_str(), port))
-                .context("Failed to connect to SSH host")?;
-            let mut sess = Session::new().context("Failed to create SSH session")?;
-            sess.set_tcp_stream(tcp);
-            sess.handshake().context("SSH handshake failed")?;
-            sess.userauth_pubkey_file(&username, None, &private_key_path, None)
-                .context("SSH authentication failed")?;
+            let (lease, _) = pool.lease()?;
 
             let src_path = Path::new(&src);
             let dst_path = Path::new(&dst);
@@ -339,7 +470,15 @@ async fn cp(&self, src: &str, dst: &str, pb: &ProgressBar) -> Result<(), anyhow:
             let total_size = get_dir_size_sync(src_path).context("Failed to get size")?;
             let copied = AtomicU64::new(0);
 
-            upload_recursive_sync(&sess, src_path, dst_path, total_size, &copied, &on_progress)
+            upload_recursive_sync(
+                &lease.0,
+                src_path,
+                dst_path,
+                total_size,
+                &copied,
+                &on_progress,
+            )?;
+            Ok(())
         })
         .await?
     }
@@ -1,16 +1,17 @@
 mod benchmark_data;
 mod cli;
+mod compare;
 mod consts;
 mod db_config;
 mod docker;
 mod exec;
 mod runner;
+mod selection;
 
 use clap::Parser;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use std::{sync::Arc, time::Duration};
 use tokio::task::JoinSet;
-use wfb_storage::Environment;
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
@@ -24,26 +25,50 @@ async fn main() -> Result<(), anyhow::Error> {
             env,
             skip_wrkr_build,
             skip_db_build,
+            repeat,
+            arrival_rates,
+            retry_failed,
+            only_failed,
+            selection,
         } => {
-            let benchmarks = config.get_benchmarks();
+            let benchmarks =
+                selection.apply(config.get_benchmarks().into_iter().cloned().collect())?;
 
             let env_config = config
                 .get_environment(&env)
                 .ok_or_else(|| anyhow::anyhow!("Environment '{}' not found in config", env))?
                 .clone();
-            let storage = wfb_storage::Storage::new("data")?;
+            let storage = wfb_storage::Storage::open(args.storage, "data")?;
 
+            let failed: std::collections::HashSet<(String, String, String)> =
+                wfb_storage::latest_attempts(&storage.get_journal(&run_id, env_config.name()))
+                    .into_iter()
+                    .filter(|attempt| attempt.state == wfb_storage::AttemptState::Failed)
+                    .map(|attempt| (attempt.language, attempt.benchmark, attempt.test))
+                    .collect();
+
+            let mut skipped_failed = 0;
             let mut benchmarks_to_run = Vec::new();
-            for b in benchmarks {
+            for b in &benchmarks {
                 let lang = config
                     .get_lang(&b.language)
                     .ok_or_else(|| anyhow::anyhow!("Language '{}' not found", b.language))?;
 
                 let mut missing_tests = Vec::new();
                 for test in &b.tests {
-                    if !storage.has_test_result(&run_id, &env_config, lang, b, *test) {
-                        missing_tests.push(*test);
+                    if storage.has_test_result(&run_id, &env_config, lang, b, *test) {
+                        continue;
+                    }
+                    let key = (lang.name.clone(), b.name.clone(), test.to_string());
+                    let is_failed = failed.contains(&key);
+                    if only_failed && !is_failed {
+                        continue;
+                    }
+                    if is_failed && !retry_failed && !only_failed {
+                        skipped_failed += 1;
+                        continue;
                     }
+                    missing_tests.push(*test);
                 }
 
                 if !missing_tests.is_empty() {
@@ -53,6 +78,13 @@ async fn main() -> Result<(), anyhow::Error> {
                 }
             }
 
+            if skipped_failed > 0 {
+                println!(
+                    "Skipping {} previously failed tests (pass --retry-failed to run them)",
+                    skipped_failed
+                );
+            }
+
             let m = MultiProgress::new();
             let unique_dbs = benchmarks_to_run
                 .iter()
@@ -61,101 +93,29 @@ async fn main() -> Result<(), anyhow::Error> {
                 .into_iter()
                 .collect::<Vec<_>>();
 
-            let run_id_clone = run_id.clone();
-            let env_config_clone = env_config.clone();
-            let config_clone = config.clone();
-
-            let runner: Arc<dyn runner::BenchmarkRunner> = match env_config {
-                Environment::Local(ref _local_config) => {
-                    let executor = exec::local::LocalExecutor::new();
-                    let config = runner::RunnerConfig {
-                        db_host: "host.docker.internal".to_string(),
-                        db_port: consts::DB_PORT_EXTERNAL.to_string(),
-                        app_host_url: format!(
-                            "http://host.docker.internal:{}",
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        app_public_host_url: format!(
-                            "http://localhost:{}",
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        is_remote: false,
-                    };
-                    Arc::new(runner::Runner::new(
-                        executor.clone(),
-                        executor.clone(),
-                        executor,
-                        false,
-                        config,
-                        storage.clone(),
-                        run_id_clone,
-                        env_config_clone,
-                        config_clone,
-                    ))
-                }
-                Environment::Ssh(ref ssh_config) => {
-                    let app_config = ssh_config
-                        .app
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: app section missing"))?;
-                    let db_config = ssh_config
-                        .db
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: db section missing"))?;
-                    let wrkr_config = ssh_config
-                        .wrkr
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: wrkr section missing"))?;
-
-                    let app_executor = exec::ssh::SshExecutor::from_config(app_config);
-                    let db_executor = exec::ssh::SshExecutor::from_config(db_config);
-                    let wrkr_executor = exec::ssh::SshExecutor::from_config(wrkr_config);
-                    let config = runner::RunnerConfig {
-                        db_host: db_config.internal_ip.clone(),
-                        db_port: consts::DB_PORT_EXTERNAL.to_string(),
-                        app_host_url: format!(
-                            "http://{}:{}",
-                            app_config.internal_ip,
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        app_public_host_url: format!(
-                            "http://{}:{}",
-                            app_config.ip,
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        is_remote: true,
-                    };
-                    Arc::new(runner::Runner::new(
-                        app_executor,
-                        db_executor,
-                        wrkr_executor,
-                        true,
-                        config,
-                        storage.clone(),
-                        run_id_clone,
-                        env_config_clone,
-                        config_clone,
-                    ))
-                }
-            };
-
-            runner.prepare(&m).await?;
+            let runners =
+                runner::factory::build_runners(&env_config, &storage, &run_id, &config).await?;
 
             let mut start_actions = JoinSet::new();
-            if !skip_wrkr_build {
-                let runner_clone = runner.clone();
-                let m_clone = m.clone();
-                start_actions.spawn(async move { runner_clone.deploy_wrkr(&m_clone).await });
-            }
+            for runner in &runners {
+                runner.prepare(&m).await?;
 
-            if !skip_db_build {
-                let runner_clone = runner.clone();
-                let m_clone = m.clone();
-                start_actions.spawn(async move {
-                    runner_clone
-                        .build_database_images(unique_dbs, &m_clone)
-                        .await
-                });
+                if !skip_wrkr_build {
+                    let runner_clone = runner.clone();
+                    let m_clone = m.clone();
+                    start_actions.spawn(async move { runner_clone.deploy_wrkr(&m_clone).await });
+                }
+
+                if !skip_db_build {
+                    let runner_clone = runner.clone();
+                    let m_clone = m.clone();
+                    let unique_dbs = unique_dbs.clone();
+                    start_actions.spawn(async move {
+                        runner_clone
+                            .build_database_images(unique_dbs, &m_clone)
+                            .await
+                    });
+                }
             }
 
             while let Some(res) = start_actions.join_next().await {
@@ -173,66 +133,43 @@ async fn main() -> Result<(), anyhow::Error> {
             pb.enable_steady_tick(Duration::from_millis(100));
             pb.set_message("Running benchmarks...");
 
-            for b in benchmarks_to_run {
-                pb.set_message(format!("{} running", b.name));
-                let _ = runner.run_benchmark(&b, &m).await;
-                pb.inc(1);
-            }
-            pb.finish_with_message("Done");
-        }
-        cli::Commands::Verify {
-            env,
-            benchmark,
-            language,
-            testcase,
-        } => {
-            let mut benchmarks = config
-                .get_benchmarks()
-                .into_iter()
-                .cloned()
-                .collect::<Vec<_>>();
-
-            // Filter by benchmark name if specified
-            if let Some(ref benchmark_name) = benchmark {
-                benchmarks.retain(|b| b.name == *benchmark_name);
-                if benchmarks.is_empty() {
-                    return Err(anyhow::anyhow!(
-                        "No benchmark found with name: {}",
-                        benchmark_name
-                    ));
-                }
-            }
-
-            // Filter by language if specified
-            if let Some(ref lang) = language {
-                benchmarks.retain(|b| b.language == *lang);
-                if benchmarks.is_empty() {
-                    return Err(anyhow::anyhow!(
-                        "No benchmarks found for language: {}",
-                        lang
-                    ));
-                }
-            }
+            let options = runner::RunOptions {
+                repeat,
+                arrival_rates,
+            };
 
-            // Filter by test case if specified
-            if let Some(ref tc) = testcase {
-                let tc = match tc.to_lowercase().as_str() {
-                    "plaintext" => wfb_storage::BenchmarkTests::PlainText,
-                    "json_aggregate" => wfb_storage::BenchmarkTests::JsonAggregate,
-                    "static_files" => wfb_storage::BenchmarkTests::StaticFiles,
-                    "db_complex" => wfb_storage::BenchmarkTests::DbComplex,
-                    "grpc_aggregate" => wfb_storage::BenchmarkTests::GrpcAggregate,
-                    _ => {
-                        eprintln!("Unknown testcase: {}", tc);
-                        return Ok(());
+            // Every runner pulls the next benchmark from a shared queue, so host sets that
+            // finish early pick up the remaining work.
+            let queue = Arc::new(std::sync::Mutex::new(
+                benchmarks_to_run
+                    .into_iter()
+                    .collect::<std::collections::VecDeque<_>>(),
+            ));
+            let mut workers = JoinSet::new();
+            for runner in runners {
+                let queue = queue.clone();
+                let options = options.clone();
+                let m = m.clone();
+                let pb = pb.clone();
+                workers.spawn(async move {
+                    loop {
+                        let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                        let Some(b) = next else { break };
+                        pb.set_message(format!("{} running", b.name));
+                        let _ = runner.run_benchmark(&b, &options, &m).await;
+                        pb.inc(1);
                     }
-                };
-                for b in &mut benchmarks {
-                    b.tests.retain(|test| *test == tc);
-                }
+                });
             }
-
-            benchmarks.retain(|b| !b.tests.is_empty());
+            while let Some(res) = workers.join_next().await {
+                res?;
+            }
+            storage.finish_run(&run_id)?;
+            pb.finish_with_message("Done");
+        }
+        cli::Commands::Verify { env, selection } => {
+            let benchmarks =
+                selection.apply(config.get_benchmarks().into_iter().cloned().collect())?;
 
             let env_config = config
                 .get_environment(&env)
@@ -247,84 +184,9 @@ async fn main() -> Result<(), anyhow::Error> {
                 .into_iter()
                 .collect::<Vec<_>>();
 
-            let storage = wfb_storage::Storage::new("data")?;
-            let run_id_clone = "verify".to_string();
-            let env_config_clone = env_config.clone();
-            let config_clone = config.clone();
-
-            let runner: Arc<dyn runner::BenchmarkRunner> = match env_config {
-                Environment::Local(ref _local_config) => {
-                    let executor = exec::local::LocalExecutor::new();
-                    let config = runner::RunnerConfig {
-                        db_host: "host.docker.internal".to_string(),
-                        db_port: consts::DB_PORT_EXTERNAL.to_string(),
-                        app_host_url: format!(
-                            "http://host.docker.internal:{}",
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        app_public_host_url: format!(
-                            "http://localhost:{}",
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        is_remote: false,
-                    };
-                    Arc::new(runner::Runner::new(
-                        executor.clone(),
-                        executor.clone(),
-                        executor,
-                        false,
-                        config,
-                        storage.clone(),
-                        run_id_clone,
-                        env_config_clone,
-                        config_clone,
-                    ))
-                }
-                Environment::Ssh(ref ssh_config) => {
-                    let app_config = ssh_config
-                        .app
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: app section missing"))?;
-                    let db_config = ssh_config
-                        .db
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: db section missing"))?;
-                    let wrkr_config = ssh_config
-                        .wrkr
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: wrkr section missing"))?;
-
-                    let app_executor = exec::ssh::SshExecutor::from_config(app_config);
-                    let db_executor = exec::ssh::SshExecutor::from_config(db_config);
-                    let wrkr_executor = exec::ssh::SshExecutor::from_config(wrkr_config);
-                    let config = runner::RunnerConfig {
-                        db_host: db_config.internal_ip.clone(),
-                        db_port: consts::DB_PORT_EXTERNAL.to_string(),
-                        app_host_url: format!(
-                            "http://{}:{}",
-                            app_config.internal_ip,
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        app_public_host_url: format!(
-                            "http://{}:{}",
-                            app_config.ip,
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        is_remote: true,
-                    };
-                    Arc::new(runner::Runner::new(
-                        app_executor,
-                        db_executor,
-                        wrkr_executor,
-                        true,
-                        config,
-                        storage,
-                        run_id_clone,
-                        env_config_clone,
-                        config_clone,
-                    ))
-                }
-            };
+            let storage = wfb_storage::Storage::open(args.storage, "data")?;
+            let runner =
+                runner::factory::build_runner(&env_config, &storage, "verify", &config).await?;
 
             runner.prepare(&m).await?;
             runner.deploy_wrkr(&m).await?;
@@ -358,6 +220,51 @@ async fn main() -> Result<(), anyhow::Error> {
                 return Err(anyhow::anyhow!("Verification failed for some benchmarks"));
             }
         }
+        cli::Commands::Compare {
+            base,
+            head,
+            env,
+            format,
+            threshold,
+        } => {
+            if config.get_environment(&env).is_none() {
+                return Err(anyhow::anyhow!("Environment '{}' not found in config", env));
+            }
+            let storage = wfb_storage::Storage::open(args.storage, "data")?;
+            {
+                let runs = storage.runs();
+                for run_id in [&base, &head] {
+                    if !runs.contains_key(run_id) {
+                        return Err(anyhow::anyhow!("Run '{}' not found", run_id));
+                    }
+                }
+            }
+
+            let comparison =
+                wfb_storage::compare_runs(&storage.data_read(), &base, &head, &env, threshold);
+            println!("{}", compare::render(&comparison, format)?);
+
+            let regressions = comparison.regressions().count();
+            if regressions > 0 {
+                return Err(anyhow::anyhow!(
+                    "{} test(s) regressed (threshold {:.1}%)",
+                    regressions,
+                    threshold
+                ));
+            }
+        }
+        cli::Commands::Import => {
+            let source = wfb_storage::FsStore::new("data")?;
+            let target = wfb_storage::SqliteStore::open(
+                std::path::Path::new("data").join(wfb_storage::SqliteStore::FILE_NAME),
+            )?;
+            let imported = target.import_fs(&source)?;
+            println!(
+                "Imported {} test case(s) into data/{}",
+                imported,
+                wfb_storage::SqliteStore::FILE_NAME
+            );
+        }
         cli::Commands::Dev { name, env } => {
             let benchmark = config
                 .get_benchmarks()
@@ -372,84 +279,9 @@ async fn main() -> Result<(), anyhow::Error> {
                 .clone();
 
             let m = MultiProgress::new();
-            let storage = wfb_storage::Storage::new("data")?;
-            let run_id_clone = "dev".to_string();
-            let env_config_clone = env_config.clone();
-            let config_clone = config.clone();
-
-            let runner: Arc<dyn runner::BenchmarkRunner> = match env_config {
-                Environment::Local(ref _local_config) => {
-                    let executor = exec::local::LocalExecutor::new();
-                    let config = runner::RunnerConfig {
-                        db_host: "host.docker.internal".to_string(),
-                        db_port: consts::DB_PORT_EXTERNAL.to_string(),
-                        app_host_url: format!(
-                            "http://host.docker.internal:{}",
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        app_public_host_url: format!(
-                            "http://localhost:{}",
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        is_remote: false,
-                    };
-                    Arc::new(runner::Runner::new(
-                        executor.clone(),
-                        executor.clone(),
-                        executor,
-                        false,
-                        config,
-                        storage.clone(),
-                        run_id_clone,
-                        env_config_clone,
-                        config_clone,
-                    ))
-                }
-                Environment::Ssh(ref ssh_config) => {
-                    let app_config = ssh_config
-                        .app
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: app section missing"))?;
-                    let db_config = ssh_config
-                        .db
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: db section missing"))?;
-                    let wrkr_config = ssh_config
-                        .wrkr
-                        .as_ref()
-                        .ok_or_else(|| anyhow::anyhow!("SSH Config: wrkr section missing"))?;
-
-                    let app_executor = exec::ssh::SshExecutor::from_config(app_config);
-                    let db_executor = exec::ssh::SshExecutor::from_config(db_config);
-                    let wrkr_executor = exec::ssh::SshExecutor::from_config(wrkr_config);
-                    let config = runner::RunnerConfig {
-                        db_host: db_config.internal_ip.clone(),
-                        db_port: consts::DB_PORT_EXTERNAL.to_string(),
-                        app_host_url: format!(
-                            "http://{}:{}",
-                            app_config.ip,
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        app_public_host_url: format!(
-                            "http://{}:{}",
-                            app_config.ip,
-                            consts::APP_PORT_EXTERNAL
-                        ),
-                        is_remote: true,
-                    };
-                    Arc::new(runner::Runner::new(
-                        app_executor,
-                        db_executor,
-                        wrkr_executor,
-                        true,
-                        config,
-                        storage,
-                        run_id_clone,
-                        env_config_clone,
-                        config_clone,
-                    ))
-                }
-            };
+            let storage = wfb_storage::Storage::open(args.storage, "data")?;
+            let runner =
+                runner::factory::build_runner(&env_config, &storage, "dev", &config).await?;
 
             runner.prepare(&m).await?;
             #[allow(clippy::needless_borrow)]
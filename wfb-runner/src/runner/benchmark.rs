@@ -1,14 +1,17 @@
 use crate::consts;
 use crate::db_config::get_db_config;
 use crate::exec::Executor;
-use crate::runner::Runner;
+use crate::runner::{RunOptions, Runner};
 use anyhow::{Context, bail};
 use humanize_bytes::humanize_bytes_binary;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::time::Duration;
-use wfb_storage::{Benchmark, BenchmarkTests, DatabaseKind};
+use wfb_storage::{
+    Benchmark, BenchmarkTests, ContainerResources, DatabaseKind, JournalEntry, Lang, LoadExecutor,
+    LogKind, ResolvedLoadProfile,
+};
 
 impl<E: Executor + Clone + Send + 'static> Runner<E> {
     fn scripts_mount_host_path(&self) -> anyhow::Result<String> {
@@ -45,9 +48,7 @@ fn max_vus_for_test(test: BenchmarkTests) -> u64 {
         }
     }
 
-    fn warmup_vus_for_test(test: BenchmarkTests) -> u64 {
-        let max_vus = Self::max_vus_for_test(test);
-
+    fn warmup_vus_for(max_vus: u64) -> u64 {
         // Keep warmup much lighter than the real run:
         //  - scale down to ~1/16 of target VUs (rounding up)
         //  - cap to a small absolute maximum
@@ -56,13 +57,149 @@ fn warmup_vus_for_test(test: BenchmarkTests) -> u64 {
         capped.max(1)
     }
 
+    /// Resolves the load settings for `test`: built-in defaults overlaid with the `default`,
+    /// environment and benchmark load profiles from config.
+    fn load_profile_for_test(
+        &self,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+    ) -> anyhow::Result<ResolvedLoadProfile> {
+        let (profiles, settings) =
+            self.wfb_config
+                .load_settings_for(&self.environment, benchmark, test)?;
+
+        let max_vus = settings
+            .max_vus
+            .unwrap_or_else(|| Self::max_vus_for_test(test));
+        let stages = settings.stages.unwrap_or_default();
+        let duration_secs = if stages.is_empty() {
+            settings
+                .duration_secs
+                .unwrap_or(consts::BENCHMARK_DURATION_PER_TEST_SECS)
+        } else {
+            stages.iter().map(|s| s.duration_secs).sum()
+        };
+        let executor = settings.executor.unwrap_or_default();
+
+        if executor == LoadExecutor::ConstantArrivalRate && settings.rate.is_none() {
+            bail!(
+                "{}/{}: executor '{}' requires 'rate' in the load profile",
+                benchmark.name,
+                test,
+                executor
+            );
+        }
+
+        Ok(ResolvedLoadProfile {
+            profiles,
+            duration_secs,
+            warmup_duration_secs: settings
+                .warmup_duration_secs
+                .unwrap_or(consts::BENCHMARK_WARMUP_DURATION_SECS),
+            warmup_vus: settings
+                .warmup_vus
+                .unwrap_or_else(|| Self::warmup_vus_for(max_vus)),
+            max_vus,
+            executor,
+            stages,
+            rate: settings.rate,
+            rate_duration_secs: settings.rates.as_ref().map(|_| {
+                settings
+                    .rate_duration_secs
+                    .unwrap_or(consts::BENCHMARK_RATE_STEP_DURATION_SECS)
+            }),
+            rates: settings.rates.unwrap_or_default(),
+            slo_p99_ms: Some(settings.slo_p99_ms.unwrap_or(consts::BENCHMARK_SLO_P99_MS)),
+        })
+    }
+
+    /// Records in the run manifest the load settings `test` ran with, the resource limits
+    /// applied to `benchmark` and the run options the environment dropped for it.
+    fn record_run_manifest(
+        &self,
+        lang: &Lang,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+        profile: &ResolvedLoadProfile,
+    ) -> anyhow::Result<()> {
+        let dropped: Vec<String> = [
+            ("app", &self.app_docker),
+            ("db", &self.db_docker),
+            ("wrkr", &self.wrkr_docker),
+        ]
+        .into_iter()
+        .flat_map(|(role, docker)| {
+            docker
+                .take_dropped_constraints()
+                .into_iter()
+                .map(move |constraint| format!("{}: {}", role, constraint))
+        })
+        .collect();
+
+        let resources = self.resources_for(benchmark);
+        let key = format!("{}/{}", lang.name, benchmark.name);
+        self.storage
+            .update_run_manifest(&self.run_id, &self.environment, &|manifest| {
+                manifest
+                    .load_profiles
+                    .insert(format!("{}/{}", key, test), profile.clone());
+                manifest.resources.insert(key.clone(), resources.clone());
+                if !dropped.is_empty() {
+                    manifest
+                        .dropped_constraints
+                        .entry(key.clone())
+                        .or_default()
+                        .extend(dropped.iter().cloned());
+                }
+            })?;
+        Ok(())
+    }
+
+    /// Container resource limits of the environment, overridden by the benchmark's.
+    fn resources_for(&self, benchmark: &Benchmark) -> ContainerResources {
+        let mut resources = self.environment.resources().clone();
+        resources.merge(&benchmark.resources);
+        resources
+    }
+
+    fn script_for_test(test: BenchmarkTests) -> &'static str {
+        match test {
+            BenchmarkTests::PlainText => consts::SCRIPT_PLAINTEXT,
+            BenchmarkTests::JsonAggregate => consts::SCRIPT_JSON,
+            BenchmarkTests::StaticFiles => consts::SCRIPT_STATIC,
+            BenchmarkTests::DbComplex => consts::SCRIPT_DB_COMPLEX,
+            BenchmarkTests::GrpcAggregate => consts::SCRIPT_GRPC_AGGREGATE,
+        }
+    }
+
+    /// Extra wrkr environment describing the measured-phase executor (see `wfb.options` in
+    /// `scripts/lib/wfb.lua`).
+    fn wrkr_env_for_profile(profile: &ResolvedLoadProfile) -> Vec<(&'static str, String)> {
+        let mut envs = vec![("WFB_EXECUTOR", profile.executor.to_string())];
+        if !profile.stages.is_empty() {
+            let stages = profile
+                .stages
+                .iter()
+                .map(|s| format!("{}s:{}", s.duration_secs, s.target))
+                .collect::<Vec<_>>()
+                .join(",");
+            envs.push(("WFB_STAGES", stages));
+        }
+        if let Some(rate) = profile.rate {
+            envs.push(("WFB_RATE", rate.to_string()));
+        }
+        envs
+    }
+
     pub async fn run_app(&self, benchmark: &Benchmark, pb: &ProgressBar) -> anyhow::Result<()> {
+        let resources = self.resources_for(benchmark);
         let mut cmd = self
             .app_docker
             .run_command(&benchmark.name, &benchmark.name)
-            .port(consts::APP_PORT_EXTERNAL, consts::APP_PORT_INTERNAL)
+            .port(self.config.app_port, consts::APP_PORT_INTERNAL)
+            .limits(&resources.app)
             .ulimit("nofile=1000000:1000000")
-            .add_host("host.docker.internal:host-gateway");
+            .host_gateway();
 
         if let Some(db_kind) = &benchmark.database {
             let db_pass = if matches!(db_kind, DatabaseKind::Mssql) {
@@ -73,7 +210,7 @@ pub async fn run_app(&self, benchmark: &Benchmark, pb: &ProgressBar) -> anyhow::
 
             cmd = cmd
                 .env("DB_HOST", &self.config.db_host)
-                .env("DB_PORT", &self.config.db_port)
+                .env("DB_PORT", self.config.db_port.to_string())
                 .env("DB_USER", consts::DB_USER)
                 .env("DB_PASSWORD", db_pass)
                 .env("DB_NAME", consts::DB_NAME)
@@ -88,6 +225,10 @@ pub async fn run_app(&self, benchmark: &Benchmark, pb: &ProgressBar) -> anyhow::
             cmd = cmd.env(k, v);
         }
 
+        for arg in &benchmark.arguments {
+            cmd = cmd.arg(arg);
+        }
+
         self.app_docker.execute_run(cmd, pb).await?;
 
         Ok(())
@@ -267,7 +408,8 @@ pub async fn verify_benchmark_impl(
         let result = async {
             self.build_benchmark_image(benchmark, &pb).await?;
             if let Some(db_kind) = &benchmark.database {
-                self.setup_database(db_kind, &pb).await?;
+                self.setup_database(db_kind, &self.resources_for(benchmark).db, &pb)
+                    .await?;
                 self.wait_for_db_ready(db_kind, &pb).await?;
             }
             self.run_app(benchmark, &pb).await?;
@@ -278,11 +420,13 @@ pub async fn verify_benchmark_impl(
         }
         .await;
 
-        let logs = if result.is_err() {
-            self.app_docker.logs(&benchmark.name).await.ok()
-        } else {
-            None
-        };
+        if let Err(e) = &result {
+            let app_log_tail = self.app_log_tail(benchmark).await;
+            if let Err(journal_err) = self.journal_setup_failure(benchmark, e, app_log_tail) {
+                mb.println(format!("Failed to write run journal: {journal_err:#}"))
+                    .ok();
+            }
+        }
 
         self.cleanup(benchmark, &pb).await.ok();
 
@@ -308,9 +452,6 @@ pub async fn verify_benchmark_impl(
                     benchmark.name,
                     e
                 ));
-                if let Some(l) = logs {
-                    println!("Application Logs:\n{}", l);
-                }
                 Err(e)
             }
         }
@@ -319,6 +460,7 @@ pub async fn verify_benchmark_impl(
     pub async fn run_benchmark_impl(
         &self,
         benchmark: &Benchmark,
+        options: &RunOptions,
         mb: &MultiProgress,
     ) -> anyhow::Result<()> {
         let pb = mb.add(ProgressBar::new_spinner());
@@ -332,12 +474,13 @@ pub async fn run_benchmark_impl(
         pb.enable_steady_tick(Duration::from_millis(100));
         pb.set_message(format!("Running {}", benchmark.name));
 
-        let result = async {
+        let setup = async {
             // Build and deploy
             self.build_benchmark_image(benchmark, &pb).await?;
 
             if let Some(db_kind) = &benchmark.database {
-                self.setup_database(db_kind, &pb).await?;
+                self.setup_database(db_kind, &self.resources_for(benchmark).db, &pb)
+                    .await?;
                 self.wait_for_db_ready(db_kind, &pb).await?;
             }
             self.run_app(benchmark, &pb).await?;
@@ -349,15 +492,31 @@ pub async fn run_benchmark_impl(
             // Cleanup before running actual benchmarks to ensure clean state for each test
             self.cleanup(benchmark, &pb).await?;
 
-            pb.set_message("benchmarks running...");
-
-            // Run tests via wrkr in docker
-            self.run_tests_docker(benchmark, mb).await?;
-
             Ok::<(), anyhow::Error>(())
         }
         .await;
 
+        let result = match setup {
+            Ok(()) => {
+                pb.set_message("benchmarks running...");
+
+                // Run tests via wrkr in docker
+                self.run_tests_docker(benchmark, options, mb).await
+            }
+            Err(e) => {
+                let app_log_tail = self.app_log_tail(benchmark).await;
+                for test in &benchmark.tests {
+                    self.save_test_logs(benchmark, *test, None).await.ok();
+                }
+                self.cleanup(benchmark, &pb).await.ok();
+                if let Err(journal_err) = self.journal_setup_failure(benchmark, &e, app_log_tail) {
+                    mb.println(format!("Failed to write run journal: {journal_err:#}"))
+                        .ok();
+                }
+                Err(e)
+            }
+        };
+
         let style = match ProgressStyle::default_spinner().template("{msg}") {
             Ok(style) => style,
             Err(_) => ProgressStyle::default_spinner(),
@@ -385,6 +544,74 @@ pub async fn run_benchmark_impl(
         }
     }
 
+    /// Records every test of `benchmark` as failed when the image, database or app could not
+    /// be brought up or did not pass verification.
+    fn journal_setup_failure(
+        &self,
+        benchmark: &Benchmark,
+        error: &anyhow::Error,
+        app_log_tail: Option<String>,
+    ) -> anyhow::Result<()> {
+        for test in &benchmark.tests {
+            let attempt =
+                JournalEntry::running(&benchmark.language, &benchmark.name, &test.to_string())
+                    .failed(format!("{error:#}"), app_log_tail.clone());
+            self.storage
+                .append_journal(&self.run_id, &self.environment, &attempt)?;
+        }
+        Ok(())
+    }
+
+    /// Last [`consts::APP_LOG_TAIL_LINES`] lines of the app container's log, if it still exists.
+    async fn app_log_tail(&self, benchmark: &Benchmark) -> Option<String> {
+        let logs = self.app_docker.logs(&benchmark.name).await.ok()?;
+        let lines: Vec<&str> = logs.lines().collect();
+        let tail = lines[lines.len().saturating_sub(consts::APP_LOG_TAIL_LINES)..].join("\n");
+        (!tail.is_empty()).then_some(tail)
+    }
+
+    /// Stores the app and db container logs of `test`, plus the stderr of `wrkr_container`,
+    /// next to its results. Containers that are already gone are skipped.
+    async fn save_test_logs(
+        &self,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+        wrkr_container: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let lang = self
+            .wfb_config
+            .get_lang(&benchmark.language)
+            .ok_or_else(|| anyhow::anyhow!("Language '{}' not found", benchmark.language))?;
+
+        let mut logs = Vec::new();
+        if let Ok(log) = self.app_docker.logs(&benchmark.name).await {
+            logs.push((LogKind::App, log));
+        }
+        if let Some(db_kind) = &benchmark.database
+            && let Ok(log) = self.db_docker.logs(get_db_config(db_kind).image_name).await
+        {
+            logs.push((LogKind::Db, log));
+        }
+        if let Some(container) = wrkr_container
+            && let Ok(log) = self.wrkr_docker.stderr_logs(container).await
+        {
+            logs.push((LogKind::Wrkr, log));
+        }
+
+        for (kind, log) in logs {
+            self.storage.save_test_log(
+                &self.run_id,
+                &self.environment,
+                lang,
+                benchmark,
+                test,
+                kind,
+                &log,
+            )?;
+        }
+        Ok(())
+    }
+
     pub async fn dev_benchmark_impl(
         &self,
         benchmark: &Benchmark,
@@ -406,7 +633,8 @@ pub async fn dev_benchmark_impl(
 
         if let Some(db_kind) = &benchmark.database {
             self.build_database_image(db_kind, &pb).await?;
-            self.setup_database(db_kind, &pb).await?;
+            self.setup_database(db_kind, &self.resources_for(benchmark).db, &pb)
+                .await?;
             self.wait_for_db_ready(db_kind, &pb).await?;
         }
         self.run_app(benchmark, &pb).await?;
@@ -450,6 +678,7 @@ pub async fn dev_benchmark_impl(
     async fn run_tests_docker(
         &self,
         benchmark: &Benchmark,
+        options: &RunOptions,
         mb: &MultiProgress,
     ) -> anyhow::Result<()> {
         let lang = self
@@ -457,179 +686,375 @@ async fn run_tests_docker(
             .get_lang(&benchmark.language)
             .ok_or_else(|| anyhow::anyhow!("Language '{}' not found", benchmark.language))?;
 
+        let mut failed = 0;
         for test in &benchmark.tests {
-            let pb = mb.add(ProgressBar::new_spinner());
-            let style = match ProgressStyle::default_spinner()
-                .template("{spinner:.blue} {prefix} [{bar:40.cyan/blue}] {msg}")
-            {
-                Ok(style) => style.progress_chars("#>-"),
-                Err(_) => ProgressStyle::default_spinner().progress_chars("#>-"),
+            let attempt = JournalEntry::running(&lang.name, &benchmark.name, &test.to_string());
+            self.storage
+                .append_journal(&self.run_id, &self.environment, &attempt)?;
+
+            match self.run_test(benchmark, lang, *test, options, mb).await {
+                Ok(()) => {
+                    self.storage.append_journal(
+                        &self.run_id,
+                        &self.environment,
+                        &attempt.succeeded(),
+                    )?;
+                }
+                Err(e) => {
+                    // Keep going with the remaining tests; the failure is in the journal.
+                    failed += 1;
+                    let app_log_tail = self.app_log_tail(benchmark).await;
+                    self.save_test_logs(benchmark, *test, Some("wrkr-runner"))
+                        .await
+                        .ok();
+                    self.wrkr_docker
+                        .stop_and_remove("wrkr-runner", &ProgressBar::hidden())
+                        .await;
+                    self.cleanup(benchmark, &ProgressBar::hidden()).await.ok();
+                    mb.println(format!(
+                        "   {} {:?} failed: {}",
+                        console::style("✘").red(),
+                        test,
+                        e
+                    ))
+                    .ok();
+                    self.storage.append_journal(
+                        &self.run_id,
+                        &self.environment,
+                        &attempt.failed(format!("{e:#}"), app_log_tail),
+                    )?;
+                }
+            }
+        }
+
+        if failed > 0 {
+            bail!("{} of {} tests failed", failed, benchmark.tests.len());
+        }
+        Ok(())
+    }
+
+    /// Runs every iteration of `test` and stores the aggregated result.
+    async fn run_test(
+        &self,
+        benchmark: &Benchmark,
+        lang: &Lang,
+        test: BenchmarkTests,
+        options: &RunOptions,
+        mb: &MultiProgress,
+    ) -> anyhow::Result<()> {
+        let repeat = options.repeat.max(1);
+
+        let mut profile = self.load_profile_for_test(benchmark, test)?;
+        if !options.arrival_rates.is_empty() {
+            profile.rates = options.arrival_rates.clone();
+            profile
+                .rate_duration_secs
+                .get_or_insert(consts::BENCHMARK_RATE_STEP_DURATION_SECS);
+        }
+        profile.rates.sort_unstable();
+        profile.rates.dedup();
+
+        let mut iterations = Vec::new();
+        for iteration in 1..=repeat {
+            let Some(result) = self
+                .run_test_iteration(benchmark, test, &profile, iteration, repeat, mb)
+                .await?
+            else {
+                continue;
             };
-            pb.set_style(style);
-            pb.set_prefix(format!("[{}/{}]", benchmark.name, test));
-            pb.enable_steady_tick(Duration::from_millis(100));
-            pb.set_length(consts::BENCHMARK_DURATION_PER_TEST_SECS);
-            pb.set_position(0);
 
-            if let Some(db_kind) = &benchmark.database {
-                self.setup_database(db_kind, &pb).await?;
-                self.wait_for_db_ready(db_kind, &pb).await?;
+            if repeat > 1 {
+                self.storage.save_iteration_result(
+                    &self.run_id,
+                    &self.environment,
+                    lang,
+                    benchmark,
+                    test,
+                    iteration,
+                    &result.summary,
+                    &result.raw_data,
+                )?;
             }
-            self.run_app(benchmark, &pb).await?;
-            self.wait_for_app_ready(benchmark, &pb).await?;
+            iterations.push(result);
+        }
 
-            let script_path = match test {
-                BenchmarkTests::PlainText => consts::SCRIPT_PLAINTEXT,
-                BenchmarkTests::JsonAggregate => consts::SCRIPT_JSON,
-                BenchmarkTests::StaticFiles => consts::SCRIPT_STATIC,
-                BenchmarkTests::DbComplex => consts::SCRIPT_DB_COMPLEX,
-                BenchmarkTests::GrpcAggregate => consts::SCRIPT_GRPC_AGGREGATE,
-            };
+        let Some(IterationResult {
+            summary,
+            raw_data,
+            percentiles,
+        }) = aggregate_iterations(iterations)
+        else {
+            bail!("no results were collected for {:?}", test);
+        };
 
-            // --- WARMUP PHASE ---
-            {
-                let warmup_pb = mb.add(ProgressBar::new(consts::BENCHMARK_WARMUP_DURATION_SECS));
-                warmup_pb.set_style(
-                    match ProgressStyle::default_bar().template(
-                        "{spinner:.yellow} {prefix:.yellow} [{bar:40.yellow/white}] {msg:.yellow}",
-                    ) {
-                        Ok(style) => style.progress_chars("=>-"),
-                        Err(_) => ProgressStyle::default_bar().progress_chars("=>-"),
-                    },
-                );
-                warmup_pb.set_prefix(format!("[{}/{}/warmup]", benchmark.name, test));
-                warmup_pb.enable_steady_tick(Duration::from_millis(100));
-
-                let scripts_mount = self.scripts_mount_host_path()?;
-                let warmup_vus = Self::warmup_vus_for_test(*test);
-                let warmup_duration_str = format!("{}s", consts::BENCHMARK_WARMUP_DURATION_SECS);
-                let envs = self.wrkr_env_for_test(
-                    *test,
-                    "warmup",
-                    warmup_duration_str.as_str(),
-                    warmup_vus,
-                );
+        if let Some(percentiles) = &percentiles {
+            self.storage.save_latency_percentiles(
+                &self.run_id,
+                &self.environment,
+                lang,
+                benchmark,
+                test,
+                percentiles,
+            )?;
+        }
 
-                let mut cmd = self
-                    .wrkr_docker
-                    .run_command(consts::WRKR_IMAGE, "wrkr-warmup")
-                    .detach(false)
-                    .ulimit("nofile=1000000:1000000")
-                    .volume(scripts_mount.as_str(), "/scripts")
-                    .arg("run")
-                    .arg(script_path)
-                    .arg("--output")
-                    .arg("json");
-
-                for (k, v) in envs {
-                    cmd = cmd.env(k, v);
-                }
+        let manifest = wfb_storage::BenchmarkManifest {
+            language_version: benchmark.language_version.clone(),
+            framework_version: benchmark.framework_version.clone(),
+            tags: benchmark.tags.clone(),
+            database: benchmark.database,
+            path: benchmark.path.clone(),
+            arguments: benchmark.arguments.clone(),
+            build_args: benchmark.build_args.clone(),
+            resources: self.resources_for(benchmark),
+            load_profiles: HashMap::from([(test.to_string(), profile.clone())]),
+            hosts: self
+                .config
+                .host
+                .iter()
+                .map(|host| (test.to_string(), host.clone()))
+                .collect(),
+        };
 
-                let warmup_pb_clone = warmup_pb.clone();
-                let _ = self
-                    .wrkr_docker
-                    .execute_run_with_std_out(
-                        cmd,
-                        move |line| {
-                            let Ok(line) = serde_json::from_str::<WrkrJsonLine>(line) else {
-                                return;
-                            };
-                            let WrkrJsonLine::Progress(stats) = line else {
-                                return;
-                            };
-
-                            warmup_pb_clone.set_position(
-                                stats
-                                    .elapsed_secs
-                                    .min(consts::BENCHMARK_WARMUP_DURATION_SECS),
-                            );
-                            warmup_pb_clone.set_message(format!(
-                                "RPS: {:.0} | Latency: {} | Errors: {}",
-                                stats.requests_per_sec,
-                                format_latency(stats.latency_p99),
-                                stats.checks_failed_total
-                            ));
-                        },
-                        &ProgressBar::hidden(),
-                    )
-                    .await;
-                self.wrkr_docker
-                    .stop_and_remove("wrkr-warmup", &ProgressBar::hidden())
-                    .await;
-                warmup_pb.finish_and_clear();
-            }
-            // --- END WARMUP PHASE ---
+        self.storage.save_benchmark_result(
+            &self.run_id,
+            &self.environment,
+            lang,
+            benchmark,
+            test,
+            &manifest,
+            &summary,
+            &raw_data,
+        )?;
+        self.record_run_manifest(lang, benchmark, test, &profile)?;
+
+        if let Some(stats) = &summary.stats {
+            mb.println(format!(
+                "   {} {:?} x{} - RPS: {:.0} ± {:.0} (95% CI {:.0}..{:.0}) | P99: {} (median)",
+                console::style("Σ").cyan(),
+                test,
+                stats.iterations,
+                stats.requests_per_sec.mean,
+                stats.requests_per_sec.stdev,
+                stats.requests_per_sec.ci95_low,
+                stats.requests_per_sec.ci95_high,
+                format_latency(stats.latency_p99.median.round() as u64),
+            ))
+            .ok();
+        }
 
-            let run_pb = mb.add(ProgressBar::new(100));
-            let style = match ProgressStyle::default_bar().template("{msg}") {
-                Ok(style) => style,
-                Err(_) => ProgressStyle::default_bar(),
+        // The main measurement is already saved; a failed sweep only loses the curve.
+        if !profile.rates.is_empty() {
+            let curve = match self.run_rate_sweep(benchmark, test, &profile, mb).await {
+                Ok(curve) => curve,
+                Err(e) => {
+                    mb.println(format!(
+                        "   {} {:?} latency sweep failed: {:#}",
+                        console::style("!").yellow(),
+                        test,
+                        e
+                    ))
+                    .ok();
+                    return Ok(());
+                }
             };
-            run_pb.set_style(style);
+            self.storage.save_latency_curve(
+                &self.run_id,
+                &self.environment,
+                lang,
+                benchmark,
+                test,
+                &curve,
+            )?;
+        }
+        Ok(())
+    }
 
-            let duration = format!("{}", consts::BENCHMARK_DURATION_PER_TEST_SECS);
-            let duration_str = format!("{}s", duration);
+    /// Drives a light load against the freshly started app so the measured phase doesn't
+    /// include JIT/connection-pool startup.
+    async fn run_warmup(
+        &self,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+        profile: &ResolvedLoadProfile,
+        mb: &MultiProgress,
+    ) -> anyhow::Result<()> {
+        let script_path = Self::script_for_test(test);
+        let warmup_pb = mb.add(ProgressBar::new(profile.warmup_duration_secs));
+        warmup_pb.set_style(
+            match ProgressStyle::default_bar().template(
+                "{spinner:.yellow} {prefix:.yellow} [{bar:40.yellow/white}] {msg:.yellow}",
+            ) {
+                Ok(style) => style.progress_chars("=>-"),
+                Err(_) => ProgressStyle::default_bar().progress_chars("=>-"),
+            },
+        );
+        warmup_pb.set_prefix(format!("[{}/{}/warmup]", benchmark.name, test));
+        warmup_pb.enable_steady_tick(Duration::from_millis(100));
 
-            let resource_usage = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
-            let resource_usage_clone = resource_usage.clone();
-            let app_docker = self.app_docker.clone();
-            let container_name = benchmark.name.clone();
+        let scripts_mount = self.scripts_mount_host_path()?;
+        let warmup_vus = profile.warmup_vus;
+        let warmup_duration_secs = profile.warmup_duration_secs;
+        let warmup_duration_str = format!("{}s", warmup_duration_secs);
+        let envs = self.wrkr_env_for_test(test, "warmup", warmup_duration_str.as_str(), warmup_vus);
 
-            let monitor_handle = tokio::spawn(async move {
-                loop {
-                    if let Ok(stats) = app_docker
-                        .stats(&container_name, "{{.MemUsage}}::{{.CPUPerc}}")
-                        .await
-                    {
-                        // stats output might be "10MiB / 1GiB::0.05%"
-                        let parts: Vec<&str> = stats.split("::").collect();
-                        if parts.len() == 2 {
-                            let mem_str = parts[0].trim().split('/').next().unwrap_or("0B").trim();
-                            let cpu_str = parts[1].trim();
-
-                            let bytes = parse_docker_memory(mem_str);
-                            let cpu = parse_docker_cpu(cpu_str);
-
-                            if let Ok(mut guard) = resource_usage_clone.lock() {
-                                guard.push((bytes, cpu));
-                            }
-                        }
-                    }
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-                }
-            });
+        let mut cmd = self
+            .wrkr_docker
+            .run_command(consts::WRKR_IMAGE, "wrkr-warmup")
+            .detach(false)
+            .ulimit("nofile=1000000:1000000")
+            .volume(scripts_mount.as_str(), "/scripts")
+            .arg("run")
+            .arg(script_path)
+            .arg("--output")
+            .arg("json");
 
-            let scripts_mount = self.scripts_mount_host_path()?;
+        for (k, v) in envs {
+            cmd = cmd.env(k, v);
+        }
 
-            let mut cmd = self
-                .wrkr_docker
-                .run_command(consts::WRKR_IMAGE, "wrkr-runner")
-                .detach(false)
-                .ulimit("nofile=1000000:1000000")
-                .volume(scripts_mount.as_str(), "/scripts")
-                .arg("run")
-                .arg(script_path)
-                .arg("--output")
-                .arg("json");
+        let warmup_pb_clone = warmup_pb.clone();
+        let _ = self
+            .wrkr_docker
+            .execute_run_with_std_out(
+                cmd,
+                move |line| {
+                    let Ok(line) = serde_json::from_str::<WrkrJsonLine>(line) else {
+                        return;
+                    };
+                    let WrkrJsonLine::Progress(stats) = line else {
+                        return;
+                    };
 
-            let max_vus = Self::max_vus_for_test(*test);
-            let envs = self.wrkr_env_for_test(*test, "run", &duration_str, max_vus);
-            for (k, v) in envs {
-                cmd = cmd.env(k, v);
-            }
+                    warmup_pb_clone.set_position(stats.elapsed_secs.min(warmup_duration_secs));
+                    warmup_pb_clone.set_message(format!(
+                        "RPS: {:.0} | Latency: {} | Errors: {}",
+                        stats.requests_per_sec,
+                        format_latency(stats.latency_p99),
+                        stats.checks_failed_total
+                    ));
+                },
+                &ProgressBar::hidden(),
+            )
+            .await;
+        self.wrkr_docker
+            .stop_and_remove("wrkr-warmup", &ProgressBar::hidden())
+            .await;
+        warmup_pb.finish_and_clear();
+        Ok(())
+    }
 
-            let raw_data_collection = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
-            let raw_data_collection_clone = raw_data_collection.clone();
+    /// Runs one measured iteration of `test`: fresh app/db containers, warmup, then the
+    /// load phase. Returns `None` if nothing was collected.
+    async fn run_test_iteration(
+        &self,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+        profile: &ResolvedLoadProfile,
+        iteration: u32,
+        repeat: u32,
+        mb: &MultiProgress,
+    ) -> anyhow::Result<Option<IterationResult>> {
+        let test = &test;
+        let pb = mb.add(ProgressBar::new_spinner());
+        let style = match ProgressStyle::default_spinner()
+            .template("{spinner:.blue} {prefix} [{bar:40.cyan/blue}] {msg}")
+        {
+            Ok(style) => style.progress_chars("#>-"),
+            Err(_) => ProgressStyle::default_spinner().progress_chars("#>-"),
+        };
+        pb.set_style(style);
+        if repeat > 1 {
+            pb.set_prefix(format!(
+                "[{}/{} #{}/{}]",
+                benchmark.name, test, iteration, repeat
+            ));
+        } else {
+            pb.set_prefix(format!("[{}/{}]", benchmark.name, test));
+        }
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb.set_length(profile.duration_secs);
+        pb.set_position(0);
 
-            let pb_clone = pb.clone();
-            let resource_usage_read = resource_usage.clone();
-            let _output = self.wrkr_docker.execute_run_with_std_out(cmd, move |line| {
-                let Ok(line) = serde_json::from_str::<WrkrJsonLine>(line) else {
-                    return;
-                };
-                let WrkrJsonLine::Progress(stats) = line else {
-                    return;
-                };
+        if let Some(db_kind) = &benchmark.database {
+            self.setup_database(db_kind, &self.resources_for(benchmark).db, &pb)
+                .await?;
+            self.wait_for_db_ready(db_kind, &pb).await?;
+        }
+        self.run_app(benchmark, &pb).await?;
+        self.wait_for_app_ready(benchmark, &pb).await?;
+
+        let script_path = Self::script_for_test(*test);
+        self.run_warmup(benchmark, *test, profile, mb).await?;
+
+        let run_pb = mb.add(ProgressBar::new(100));
+        let style = match ProgressStyle::default_bar().template("{msg}") {
+            Ok(style) => style,
+            Err(_) => ProgressStyle::default_bar(),
+        };
+        run_pb.set_style(style);
+
+        let duration_secs = profile.duration_secs;
+        let duration_str = format!("{}s", duration_secs);
+
+        let resource_usage = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let resource_usage_clone = resource_usage.clone();
+        let app_docker = self.app_docker.clone();
+        let container_name = benchmark.name.clone();
+
+        let monitor_handle = tokio::spawn(async move {
+            app_docker
+                .monitor_stats(&container_name, move |stats| {
+                    if let Ok(mut guard) = resource_usage_clone.lock() {
+                        guard.push((stats.memory_bytes, stats.cpu_percent));
+                    }
+                })
+                .await
+        });
+
+        let scripts_mount = self.scripts_mount_host_path()?;
+
+        let mut cmd = self
+            .wrkr_docker
+            .run_command(consts::WRKR_IMAGE, "wrkr-runner")
+            .detach(false)
+            .ulimit("nofile=1000000:1000000")
+            .volume(scripts_mount.as_str(), "/scripts")
+            .arg("run")
+            .arg(script_path)
+            .arg("--output")
+            .arg("json");
+
+        let mut envs = self.wrkr_env_for_test(*test, "run", &duration_str, profile.max_vus);
+        envs.extend(Self::wrkr_env_for_profile(profile));
+        for (k, v) in envs {
+            cmd = cmd.env(k, v);
+        }
+
+        let raw_data_collection = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let raw_data_collection_clone = raw_data_collection.clone();
+        let percentiles = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let percentiles_clone = percentiles.clone();
+
+        let pb_clone = pb.clone();
+        let resource_usage_read = resource_usage.clone();
+        let _output = self
+            .wrkr_docker
+            .execute_run_with_std_out(
+                cmd,
+                move |line| {
+                    let Ok(line) = serde_json::from_str::<WrkrJsonLine>(line) else {
+                        return;
+                    };
+                    let stats = match line {
+                        WrkrJsonLine::Progress(stats) => stats,
+                        WrkrJsonLine::Summary(summary) => {
+                            if let Ok(mut guard) = percentiles_clone.lock() {
+                                *guard = summary.latency_percentiles();
+                            }
+                            return;
+                        }
+                    };
                     let total_errors = stats.checks_failed.values().copied().sum();
 
                     let (mem_bytes, cpu_usage) = if let Ok(guard) = resource_usage_read.lock() {
@@ -676,106 +1101,350 @@ async fn run_tests_docker(
                         guard.push(raw_item);
                     }
 
-                    pb_clone.set_position(stats.elapsed_secs.min(consts::BENCHMARK_DURATION_PER_TEST_SECS));
+                    pb_clone.set_position(stats.elapsed_secs.min(duration_secs));
                     pb_clone.set_message(format!(
-                        "[{}] RPS: {:.0} | TPS: {} | Latency: {} | Errors: {} | Mem: {} | CPU: {:.2}%",
-                        stats.connections,
-                        stats.requests_per_sec,
-                        humanize_bytes_binary!(stats.bytes_received_per_sec + stats.bytes_sent_per_sec),
-                        format_latency(latency_p99_us),
-                        total_errors,
-                        humanize_bytes_binary!(mem_bytes),
-                        cpu_usage
-                    ));
-            }, &run_pb).await?;
+                    "[{}] RPS: {:.0} | TPS: {} | Latency: {} | Errors: {} | Mem: {} | CPU: {:.2}%",
+                    stats.connections,
+                    stats.requests_per_sec,
+                    humanize_bytes_binary!(stats.bytes_received_per_sec + stats.bytes_sent_per_sec),
+                    format_latency(latency_p99_us),
+                    total_errors,
+                    humanize_bytes_binary!(mem_bytes),
+                    cpu_usage
+                ));
+                },
+                &run_pb,
+            )
+            .await?;
+
+        monitor_handle.abort();
+
+        let raw_data = raw_data_collection
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+
+        if raw_data.is_empty() {
+            bail!(
+                "wrkr produced no JSON progress lines for {:?}; check that --output json is supported and the script is valid",
+                test
+            );
+        }
 
-            monitor_handle.abort();
+        let final_resource_usage = resource_usage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .cloned()
+            .unwrap_or((0, 0.0));
+        let final_memory_usage = final_resource_usage.0;
+        let final_cpu_usage = final_resource_usage.1;
+
+        let mut summary =
+            find_max_stable_performance(&raw_data, final_memory_usage, final_cpu_usage);
+        let percentiles: Option<wfb_storage::LatencyPercentiles> =
+            percentiles.lock().unwrap_or_else(|e| e.into_inner()).take();
+
+        if let Some(summary) = &mut summary {
+            if let Some(percentiles) = &percentiles {
+                summary.latency_distribution = DISTRIBUTION_PERCENTILES
+                    .iter()
+                    .filter_map(|p| Some((*p, percentiles.value_at(*p as f64)?)))
+                    .collect();
+            }
+            let slo_p99_ms = profile.slo_p99_ms.unwrap_or(consts::BENCHMARK_SLO_P99_MS);
+            summary.slo = Some(find_slo_throughput(
+                &raw_data,
+                (slo_p99_ms * 1000.0).round() as u64,
+            ));
+        }
 
-            let raw_data = raw_data_collection
-                .lock()
-                .unwrap_or_else(|e| e.into_inner())
-                .clone();
+        // With --repeat, the logs of the last iteration are the ones kept.
+        self.save_test_logs(benchmark, *test, Some("wrkr-runner"))
+            .await?;
 
-            if raw_data.is_empty() {
-                bail!(
-                    "wrkr produced no JSON progress lines for {:?}; check that --output json is supported and the script is valid",
-                    test
-                );
+        self.wrkr_docker
+            .stop_and_remove("wrkr-runner", &run_pb)
+            .await;
+
+        self.cleanup(benchmark, &pb).await?;
+
+        run_pb.finish_and_clear();
+        mb.remove(&run_pb);
+
+        let style = match ProgressStyle::default_spinner().template("{msg}") {
+            Ok(style) => style,
+            Err(_) => ProgressStyle::default_spinner(),
+        };
+        pb.set_style(style);
+
+        if let Some(summary) = &summary {
+            pb.finish_with_message(format!(
+                "   {} {:?} - RPS: {:.0} | RPS @ SLO: {:.0} | TPS: {} | Latency: {} | Errors: {} | Mem: {}",
+                console::style("✔").green(),
+                test,
+                summary.requests_per_sec,
+                summary.slo.as_ref().map_or(0.0, |slo| slo.requests_per_sec),
+                humanize_bytes_binary!(summary.bytes_per_sec),
+                format_latency(summary.latency_p99),
+                summary.total_errors,
+                humanize_bytes_binary!(summary.memory_usage_bytes),
+            ));
+        } else {
+            pb.finish_with_message(format!(
+                "   {} {:?} - No stats collected",
+                console::style("✘").red(),
+                test
+            ));
+        }
+
+        Ok(summary.map(|summary| IterationResult {
+            summary,
+            raw_data,
+            percentiles,
+        }))
+    }
+
+    /// Drives `test` at each of `profile.rates` with the constant-arrival-rate executor,
+    /// reusing one app/db instance for the whole sweep.
+    async fn run_rate_sweep(
+        &self,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+        profile: &ResolvedLoadProfile,
+        mb: &MultiProgress,
+    ) -> anyhow::Result<wfb_storage::LatencyCurve> {
+        let step_secs = profile
+            .rate_duration_secs
+            .unwrap_or(consts::BENCHMARK_RATE_STEP_DURATION_SECS);
+
+        let pb = mb.add(ProgressBar::new_spinner());
+        let style = match ProgressStyle::default_spinner()
+            .template("{spinner:.blue} {prefix} [{bar:40.cyan/blue}] {msg}")
+        {
+            Ok(style) => style.progress_chars("#>-"),
+            Err(_) => ProgressStyle::default_spinner().progress_chars("#>-"),
+        };
+        pb.set_style(style);
+        pb.set_prefix(format!("[{}/{}/rates]", benchmark.name, test));
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb.set_length(step_secs * profile.rates.len() as u64);
+        pb.set_position(0);
+
+        if let Some(db_kind) = &benchmark.database {
+            self.setup_database(db_kind, &self.resources_for(benchmark).db, &pb)
+                .await?;
+            self.wait_for_db_ready(db_kind, &pb).await?;
+        }
+        self.run_app(benchmark, &pb).await?;
+        self.wait_for_app_ready(benchmark, &pb).await?;
+        self.run_warmup(benchmark, test, profile, mb).await?;
+
+        let scripts_mount = self.scripts_mount_host_path()?;
+        let script_path = Self::script_for_test(test);
+        let duration_str = format!("{}s", step_secs);
+
+        let mut curve = wfb_storage::LatencyCurve::default();
+        for (step, rate) in profile.rates.iter().copied().enumerate() {
+            let mut cmd = self
+                .wrkr_docker
+                .run_command(consts::WRKR_IMAGE, "wrkr-runner")
+                .detach(false)
+                .ulimit("nofile=1000000:1000000")
+                .volume(scripts_mount.as_str(), "/scripts")
+                .arg("run")
+                .arg(script_path)
+                .arg("--output")
+                .arg("json");
+
+            let mut envs = self.wrkr_env_for_test(test, "run", &duration_str, profile.max_vus);
+            envs.push((
+                "WFB_EXECUTOR",
+                LoadExecutor::ConstantArrivalRate.to_string(),
+            ));
+            envs.push(("WFB_RATE", rate.to_string()));
+            for (k, v) in envs {
+                cmd = cmd.env(k, v);
             }
 
-            let final_resource_usage = resource_usage
+            let last_progress = std::sync::Arc::new(std::sync::Mutex::new(None));
+            let last_progress_clone = last_progress.clone();
+            let summary = std::sync::Arc::new(std::sync::Mutex::new(None));
+            let summary_clone = summary.clone();
+            let pb_clone = pb.clone();
+            let offset = step as u64 * step_secs;
+            let result = self
+                .wrkr_docker
+                .execute_run_with_std_out(
+                    cmd,
+                    move |line| {
+                        let Ok(line) = serde_json::from_str::<WrkrJsonLine>(line) else {
+                            return;
+                        };
+                        let stats = match line {
+                            WrkrJsonLine::Progress(stats) => stats,
+                            WrkrJsonLine::Summary(line) => {
+                                if let Ok(mut guard) = summary_clone.lock() {
+                                    *guard = Some(line);
+                                }
+                                return;
+                            }
+                        };
+
+                        pb_clone.set_position(offset + stats.elapsed_secs.min(step_secs));
+                        pb_clone.set_message(format!(
+                            "{} req/s - RPS: {:.0} | P99: {} | Dropped: {}",
+                            rate,
+                            stats.requests_per_sec,
+                            format_latency(stats.latency_p99),
+                            stats.dropped_iterations_total
+                        ));
+
+                        if let Ok(mut guard) = last_progress_clone.lock() {
+                            *guard = Some(stats);
+                        }
+                    },
+                    &pb,
+                )
+                .await;
+            self.wrkr_docker.stop_and_remove("wrkr-runner", &pb).await;
+
+            if let Err(e) = result {
+                self.cleanup(benchmark, &pb).await?;
+                pb.finish_and_clear();
+                bail!("wrkr run at {} req/s failed: {}", rate, e);
+            }
+
+            let summary = summary.lock().unwrap_or_else(|e| e.into_inner()).take();
+            let last_progress = last_progress
                 .lock()
                 .unwrap_or_else(|e| e.into_inner())
-                .iter()
-                .max_by(|a, b| a.0.cmp(&b.0))
-                .cloned()
-                .unwrap_or((0, 0.0));
-            let final_memory_usage = final_resource_usage.0;
-            let final_cpu_usage = final_resource_usage.1;
-
-            let summary =
-                find_max_stable_performance(&raw_data, final_memory_usage, final_cpu_usage);
-
-            if let Some(summary) = &summary {
-                let manifest = wfb_storage::BenchmarkManifest {
-                    language_version: benchmark.language_version.clone(),
-                    framework_version: benchmark.framework_version.clone(),
-                    tags: benchmark.tags.clone(),
-                    database: benchmark.database,
-                    path: benchmark.path.clone(),
-                };
+                .take();
+            let point = summary.and_then(|summary| {
+                latency_curve_point(rate, step_secs, &summary, last_progress.as_ref())
+            });
+            let Some(point) = point else {
+                self.cleanup(benchmark, &pb).await?;
+                pb.finish_and_clear();
+                bail!("wrkr reported no latency summary at {} req/s", rate);
+            };
+            curve.points.push(point);
+        }
 
-                self.storage.save_benchmark_result(
-                    &self.run_id,
-                    &self.environment,
-                    lang,
-                    benchmark,
-                    *test,
-                    &manifest,
-                    summary,
-                    &raw_data,
-                )?;
-            }
+        self.cleanup(benchmark, &pb).await?;
 
-            self.wrkr_docker
-                .stop_and_remove("wrkr-runner", &run_pb)
-                .await;
+        let style = match ProgressStyle::default_spinner().template("{msg}") {
+            Ok(style) => style,
+            Err(_) => ProgressStyle::default_spinner(),
+        };
+        pb.set_style(style);
+        let steps = curve
+            .points
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}: {} ({} dropped)",
+                    p.target_rate,
+                    format_latency(p.latency_p99),
+                    p.dropped_iterations
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        pb.finish_with_message(format!(
+            "   {} {:?} rates - P99 {}",
+            console::style("✔").green(),
+            test,
+            steps
+        ));
 
-            self.cleanup(benchmark, &pb).await?;
+        Ok(curve)
+    }
+}
 
-            run_pb.finish_and_clear();
-            mb.remove(&run_pb);
+/// Builds the curve point of one sweep step from wrkr's end-of-step summary.
+///
+/// Percentiles come from the busiest scenario's whole-step distribution; dropped
+/// iterations are only reported on progress lines, so they are taken from the last one.
+fn latency_curve_point(
+    target_rate: u64,
+    duration_secs: u64,
+    summary: &WrkrJsonSummaryLine,
+    last_progress: Option<&WrkrJsonProgressLine>,
+) -> Option<wfb_storage::LatencyCurvePoint> {
+    let latency = summary
+        .scenarios
+        .iter()
+        .filter(|s| s.latency.is_some())
+        .max_by_key(|s| s.requests_total)?
+        .latency
+        .as_ref()?;
+
+    Some(wfb_storage::LatencyCurvePoint {
+        target_rate,
+        achieved_rps: summary.totals.requests_total as f64 / duration_secs.max(1) as f64,
+        latency_p50: latency.p50,
+        latency_p90: latency.p90,
+        latency_p99: latency.p99,
+        latency_max: latency.max,
+        dropped_iterations: last_progress.map_or(0, |l| l.dropped_iterations_total),
+        total_requests: summary.totals.requests_total,
+        total_errors: summary.totals.checks_failed_total,
+        duration_secs,
+    })
+}
 
-            let style = match ProgressStyle::default_spinner().template("{msg}") {
-                Ok(style) => style,
-                Err(_) => ProgressStyle::default_spinner(),
-            };
-            pb.set_style(style);
+/// Percentiles copied from the whole-run percentile summary into
+/// `TestCaseSummary::latency_distribution`. Only the ones wrkr reported are copied; a
+/// missing percentile is left out rather than filled with a neighbouring value.
+const DISTRIBUTION_PERCENTILES: [u8; 5] = [50, 75, 90, 99, 100];
+
+/// Measurements of one iteration of a test.
+struct IterationResult {
+    summary: wfb_storage::TestCaseSummary,
+    raw_data: Vec<wfb_storage::TestCaseRaw>,
+    /// Latency percentile summary from wrkr's summary line, if it reported one.
+    percentiles: Option<wfb_storage::LatencyPercentiles>,
+}
 
-            if let Some(summary) = &summary {
-                pb.finish_with_message(format!(
-                    "   {} {:?} - RPS: {:.0} | TPS: {} | Latency: {} | Errors: {} | Mem: {}",
-                    console::style("✔").green(),
-                    test,
-                    summary.requests_per_sec,
-                    humanize_bytes_binary!(summary.bytes_per_sec),
-                    format_latency(summary.latency_p99),
-                    summary.total_errors,
-                    humanize_bytes_binary!(summary.memory_usage_bytes),
-                ));
-            } else {
-                pb.finish_with_message(format!(
-                    "   {} {:?} - No stats collected",
-                    console::style("✘").red(),
-                    test
-                ));
-            }
-        }
-        Ok(())
+/// Collapses repeated iterations of a test into one stored result.
+///
+/// A single iteration is returned unchanged. For several iterations the iteration with
+/// the median RPS is kept as the representative (summary, raw time-series and percentiles),
+/// and its summary is annotated with run-to-run statistics for RPS and P99 latency.
+fn aggregate_iterations(mut iterations: Vec<IterationResult>) -> Option<IterationResult> {
+    if iterations.len() <= 1 {
+        return iterations.pop();
     }
+
+    let rps: Vec<f64> = iterations
+        .iter()
+        .map(|i| i.summary.requests_per_sec)
+        .collect();
+    let p99: Vec<f64> = iterations
+        .iter()
+        .map(|i| i.summary.latency_p99 as f64)
+        .collect();
+
+    let stats = wfb_storage::TestCaseStats {
+        iterations: iterations.len() as u32,
+        requests_per_sec: wfb_storage::MetricStats::from_samples(&rps)?,
+        latency_p99: wfb_storage::MetricStats::from_samples(&p99)?,
+    };
+
+    iterations.sort_by(|a, b| {
+        a.summary
+            .requests_per_sec
+            .total_cmp(&b.summary.requests_per_sec)
+    });
+    let mut representative = iterations.swap_remove((iterations.len() - 1) / 2);
+    representative.summary.stats = Some(stats);
+
+    Some(representative)
 }
 
-fn format_latency(micros: u64) -> String {
+pub(crate) fn format_latency(micros: u64) -> String {
     if micros >= 1_000_000 {
         format!("{:.2}s", micros as f64 / 1_000_000.0)
     } else if micros >= 1_000 {
@@ -859,6 +1528,10 @@ struct WrkrJsonProgressLine {
     pub req_per_sec_stdev: f64,
     pub req_per_sec_max: f64,
     pub req_per_sec_stdev_pct: f64,
+
+    // Iterations the arrival-rate executors could not start (cumulative).
+    #[serde(default)]
+    pub dropped_iterations_total: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -867,12 +1540,22 @@ struct WrkrJsonSummaryLine {
     pub totals: WrkrJsonTotals,
 }
 
+impl WrkrJsonSummaryLine {
+    /// Percentile summary of the busiest scenario that reported one.
+    fn latency_percentiles(&self) -> Option<wfb_storage::LatencyPercentiles> {
+        self.scenarios
+            .iter()
+            .filter(|s| s.latency_percentiles.is_some())
+            .max_by_key(|s| s.requests_total)
+            .and_then(|s| s.latency_percentiles.clone())
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 struct WrkrJsonScenarioSummary {
     #[allow(dead_code)]
     pub scenario: String,
 
-    #[allow(dead_code)]
     pub requests_total: u64,
     #[allow(dead_code)]
     pub failed_requests_total: u64,
@@ -886,11 +1569,24 @@ struct WrkrJsonScenarioSummary {
     #[allow(dead_code)]
     pub checks_failed_total: u64,
     pub checks_failed: HashMap<String, u64>,
+
+    #[serde(default)]
+    pub latency_percentiles: Option<wfb_storage::LatencyPercentiles>,
+    /// Whole-run percentiles in microseconds.
+    #[serde(default)]
+    pub latency: Option<WrkrJsonLatencySummary>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+struct WrkrJsonLatencySummary {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 struct WrkrJsonTotals {
-    #[allow(dead_code)]
     pub requests_total: u64,
     #[allow(dead_code)]
     pub failed_requests_total: u64,
@@ -942,7 +1638,6 @@ struct WrkrNdjsonV1ExecutorProgress {
     pub vus_active: u64,
     #[allow(dead_code)]
     pub vus_max: Option<u64>,
-    #[allow(dead_code)]
     pub dropped_iterations_total: Option<u64>,
 }
 
@@ -1017,6 +1712,7 @@ fn into_legacy_progress(self) -> WrkrJsonProgressLine {
             req_per_sec_stdev: self.metrics.req_per_sec_stdev,
             req_per_sec_max: self.metrics.req_per_sec_max,
             req_per_sec_stdev_pct: self.metrics.req_per_sec_stdev_pct,
+            dropped_iterations_total: self.executor.dropped_iterations_total.unwrap_or(0),
         }
     }
 }
@@ -1052,7 +1748,8 @@ struct WrkrNdjsonV1ScenarioSummary {
 
     pub checks: Option<WrkrNdjsonV1ChecksSummary>,
 
-    #[allow(dead_code)]
+    // Kept opaque so an unexpected latency layout never fails the whole summary line;
+    // see `latency_percentiles_from_summary`.
     pub latency_seconds: Option<serde_json::Value>,
 }
 
@@ -1112,6 +1809,12 @@ fn into_legacy_summary(self) -> WrkrJsonSummaryLine {
                     (0, HashMap::new())
                 };
 
+                let latency_percentiles = s
+                    .latency_seconds
+                    .as_ref()
+                    .and_then(|l| latency_percentiles_from_summary(l, s.requests_total));
+                let latency = s.latency_seconds.as_ref().and_then(latency_from_summary);
+
                 WrkrJsonScenarioSummary {
                     scenario: s.scenario,
                     requests_total: s.requests_total,
@@ -1121,6 +1824,8 @@ fn into_legacy_summary(self) -> WrkrJsonSummaryLine {
                     iterations_total: s.iterations_total,
                     checks_failed_total,
                     checks_failed,
+                    latency_percentiles,
+                    latency,
                 }
             })
             .collect();
@@ -1139,6 +1844,53 @@ fn into_legacy_summary(self) -> WrkrJsonSummaryLine {
     }
 }
 
+/// Keys of wrkr's `latencySeconds` summary that hold a percentile, with the percentile
+/// they hold. Other keys (`mean`, `stdev`, ...) are not percentiles.
+const SUMMARY_PERCENTILE_KEYS: [(&str, f64); 9] = [
+    ("p50", 50.0),
+    ("p75", 75.0),
+    ("p90", 90.0),
+    ("p95", 95.0),
+    ("p99", 99.0),
+    ("p99_9", 99.9),
+    ("p99_99", 99.99),
+    ("p99_999", 99.999),
+    ("max", 100.0),
+];
+
+/// Collects the percentiles of a scenario's `latencySeconds` summary listed in
+/// [`SUMMARY_PERCENTILE_KEYS`].
+fn latency_percentiles_from_summary(
+    latency: &serde_json::Value,
+    requests_total: u64,
+) -> Option<wfb_storage::LatencyPercentiles> {
+    let percentiles: Vec<(f64, u64)> = SUMMARY_PERCENTILE_KEYS
+        .iter()
+        .filter_map(|(key, percentile)| {
+            let value = latency.get(key)?.as_f64()?;
+            Some((*percentile, secs_f64_to_micros_u64(value)))
+        })
+        .collect();
+    if percentiles.is_empty() {
+        return None;
+    }
+    Some(wfb_storage::LatencyPercentiles::from_percentiles(
+        requests_total,
+        percentiles,
+    ))
+}
+
+/// Reads p50/p90/p99/max from a scenario's `latencySeconds` summary, in microseconds.
+fn latency_from_summary(latency: &serde_json::Value) -> Option<WrkrJsonLatencySummary> {
+    let micros = |key: &str| Some(secs_f64_to_micros_u64(latency.get(key)?.as_f64()?));
+    Some(WrkrJsonLatencySummary {
+        p50: micros("p50")?,
+        p90: micros("p90")?,
+        p99: micros("p99")?,
+        max: micros("max")?,
+    })
+}
+
 fn checks_series_to_map(series: &[WrkrNdjsonV1CheckSeries]) -> HashMap<String, u64> {
     let mut out = HashMap::new();
     for s in series {
@@ -1185,27 +1937,6 @@ fn secs_f64_to_micros_f64(secs: f64) -> f64 {
     secs * 1_000_000.0
 }
 
-fn parse_docker_memory(s: &str) -> u64 {
-    let s = s.trim();
-    let (num_str, multiplier) = if s.ends_with("GiB") {
-        (s.trim_end_matches("GiB"), 1024.0 * 1024.0 * 1024.0)
-    } else if s.ends_with("MiB") {
-        (s.trim_end_matches("MiB"), 1024.0 * 1024.0)
-    } else if s.ends_with("KiB") {
-        (s.trim_end_matches("KiB"), 1024.0)
-    } else if s.ends_with("B") {
-        (s.trim_end_matches("B"), 1.0)
-    } else {
-        return 0;
-    };
-
-    if let Ok(num) = num_str.trim().parse::<f64>() {
-        (num * multiplier) as u64
-    } else {
-        0
-    }
-}
-
 fn find_max_stable_performance(
     data: &[wfb_storage::TestCaseRaw],
     final_memory_usage: u64,
@@ -1330,17 +2061,58 @@ fn raw_to_summary(
         req_per_sec_stdev: raw.req_per_sec_stdev,
         req_per_sec_max: raw.req_per_sec_max,
         req_per_sec_stdev_pct: raw.req_per_sec_stdev_pct,
+        stats: None,
+        slo: None,
     }
 }
 
-fn parse_docker_cpu(s: &str) -> f64 {
-    let s = s.trim().trim_end_matches('%');
-    s.parse::<f64>().unwrap_or(0.0)
+/// Finds the best 5-second window (or the whole run, if shorter) in which every sample kept
+/// p99 latency within `latency_p99_target` (microseconds) and no new errors occurred.
+fn find_slo_throughput(
+    data: &[wfb_storage::TestCaseRaw],
+    latency_p99_target: u64,
+) -> wfb_storage::SloThroughput {
+    let window_size = data.len().clamp(1, 5);
+
+    let mut best = wfb_storage::SloThroughput {
+        latency_p99_target,
+        requests_per_sec: 0.0,
+        latency_p99: 0,
+    };
+
+    for end in window_size..=data.len() {
+        let start = end - window_size;
+        let window = &data[start..end];
+
+        // total_errors is cumulative, so compare against the sample before the window.
+        let errors_before = if start > 0 {
+            data[start - 1].total_errors
+        } else {
+            0
+        };
+        let window_errors = window[window_size - 1]
+            .total_errors
+            .saturating_sub(errors_before);
+        let worst_p99 = window.iter().map(|x| x.latency_p99).max().unwrap_or(0);
+        if window_errors > 0 || worst_p99 > latency_p99_target {
+            continue;
+        }
+
+        let avg_rps = window.iter().map(|x| x.requests_per_sec).sum::<f64>() / window_size as f64;
+        if avg_rps > best.requests_per_sec {
+            best.requests_per_sec = avg_rps;
+            best.latency_p99 = worst_p99;
+        }
+    }
+
+    best
 }
 
 #[cfg(test)]
 mod tests {
-    use super::WrkrJsonLine;
+    use super::{
+        WrkrJsonLine, find_slo_throughput, latency_curve_point, latency_percentiles_from_summary,
+    };
 
     #[test]
     fn wrkr_progress_line_v1_seconds_is_converted_to_us() {
@@ -1441,4 +2213,111 @@ fn wrkr_summary_line_v1_parses_and_extracts_failed_checks() {
             WrkrJsonLine::Progress(_) => panic!("expected summary"),
         }
     }
+
+    #[test]
+    fn latency_curve_point_takes_percentiles_from_the_step_summary() {
+        let summary = r#"{
+            "schema": "wrkr.ndjson.v1", "kind": "summary",
+            "scenarios": [
+                {
+                    "scenario": "Default", "exec": "Default", "executor": null,
+                    "requestsTotal": 4000, "failedRequestsTotal": 0, "bytesReceivedTotal": 0,
+                    "bytesSentTotal": 0, "iterationsTotal": 4000,
+                    "checks": {"total": 4000, "passed": 3999, "failed": 1, "bySeries": []},
+                    "latencySeconds": {"mean": 0.002, "stdev": 0.0, "max": 0.009, "p50": 0.001, "p75": 0.002, "p90": 0.003, "p99": 0.005}
+                }
+            ],
+            "totals": {
+                "requestsTotal": 4000, "failedRequestsTotal": 0, "bytesReceivedTotal": 0,
+                "bytesSentTotal": 0, "iterationsTotal": 4000, "checksFailedTotal": 1
+            },
+            "thresholds": {"violations": []}
+        }"#;
+        let progress = r#"{
+            "schema": "wrkr.ndjson.v1", "kind": "progress", "tick": 2,
+            "elapsedSeconds": 2.0, "intervalSeconds": 1.0, "scenario": "Default", "exec": "Default",
+            "executor": {"kind": "constant-arrival-rate", "vusActive": 8, "vusMax": 64, "droppedIterationsTotal": 7},
+            "metrics": {
+                "requestsPerSec": 1000, "bytesReceivedPerSec": 0, "bytesSentPerSec": 0,
+                "totalRequests": 4000, "totalFailedRequests": 0, "totalIterations": 4000,
+                "totalBytesReceived": 0, "totalBytesSent": 0, "checksFailedTotal": 1,
+                "latencySeconds": {"mean": 0.001, "stdev": 0.0, "max": 0.001, "p50": 0.001, "p75": 0.001, "p90": 0.001, "p99": 0.001, "stdevPct": 0.0},
+                "reqPerSecAvg": 0.0, "reqPerSecStdev": 0.0, "reqPerSecMax": 0.0, "reqPerSecStdevPct": 0.0
+            }
+        }"#;
+        let WrkrJsonLine::Summary(summary) = serde_json::from_str(summary).expect("parse") else {
+            panic!("expected summary");
+        };
+        let WrkrJsonLine::Progress(progress) = serde_json::from_str(progress).expect("parse")
+        else {
+            panic!("expected progress");
+        };
+
+        let point = latency_curve_point(2000, 2, &summary, Some(&progress)).expect("point");
+        assert_eq!(point.target_rate, 2000);
+        assert!((point.achieved_rps - 2000.0).abs() < 1e-9);
+        // Whole-step percentiles, not the last interval's.
+        assert_eq!(point.latency_p50, 1000);
+        assert_eq!(point.latency_p90, 3000);
+        assert_eq!(point.latency_p99, 5000);
+        assert_eq!(point.latency_max, 9000);
+        assert_eq!(point.dropped_iterations, 7);
+        assert_eq!(point.total_requests, 4000);
+        assert_eq!(point.total_errors, 1);
+
+        let mut without_latency = summary.clone();
+        without_latency.scenarios[0].latency = None;
+        assert!(latency_curve_point(2000, 2, &without_latency, None).is_none());
+    }
+
+    #[test]
+    fn slo_throughput_skips_windows_over_latency_or_with_errors() {
+        // (rps, p99 us, cumulative errors)
+        let samples = [
+            (1000.0, 2_000, 0),
+            (1000.0, 2_000, 0),
+            (1000.0, 2_000, 0),
+            (1000.0, 2_000, 0),
+            (1000.0, 2_000, 0),
+            (5000.0, 9_000, 0),
+            (9000.0, 8_000, 2),
+            (9000.0, 25_000, 2),
+        ];
+        let data: Vec<wfb_storage::TestCaseRaw> = samples
+            .iter()
+            .enumerate()
+            .map(|(i, (rps, p99, errors))| wfb_storage::TestCaseRaw {
+                elapsed_secs: i as u64,
+                requests_per_sec: *rps,
+                total_errors: *errors,
+                latency_p99: *p99,
+                ..Default::default()
+            })
+            .collect();
+
+        let slo = find_slo_throughput(&data, 10_000);
+        // [2..=6] has errors and [3..=7] breaks the SLO; [1..=5] beats [0..=4].
+        assert!((slo.requests_per_sec - 1800.0).abs() < 1e-9);
+        assert_eq!(slo.latency_p99, 9_000);
+
+        let unmet = find_slo_throughput(&data, 1_000);
+        assert_eq!(unmet.requests_per_sec, 0.0);
+        assert_eq!(unmet.latency_p99_target, 1_000);
+    }
+
+    #[test]
+    fn latency_percentiles_are_read_from_summary() {
+        let latency = serde_json::json!({
+            "mean": 0.001, "p50": 0.001, "p99": 0.004, "p99_9": 0.02, "p999": 0.03, "max": 0.05
+        });
+        let percentiles = latency_percentiles_from_summary(&latency, 1000).expect("percentiles");
+        assert_eq!(percentiles.total_count, 1000);
+        // `mean` is not a percentile and `p999` is not a key wrkr emits.
+        assert_eq!(percentiles.points.len(), 4);
+        assert_eq!(percentiles.value_at(99.9), Some(20_000));
+        assert_eq!(percentiles.value_at(100.0), Some(50_000));
+        assert_eq!(percentiles.value_at(75.0), None);
+
+        assert!(latency_percentiles_from_summary(&serde_json::Value::Null, 0).is_none());
+    }
 }
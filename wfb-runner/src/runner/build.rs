@@ -1,8 +1,11 @@
 use crate::consts;
 use crate::db_config::get_db_config;
+use crate::docker::command::shell_quote;
 use crate::exec::Executor;
 use crate::runner::Runner;
+use crate::runner::context::ContextManifest;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::collections::{BTreeSet, HashMap};
 use std::time::Duration;
 use wfb_storage::{Benchmark, DatabaseKind};
 
@@ -127,8 +130,9 @@ pub async fn build_database_image(
             &self.db_docker,
             config.image_name,
             &temp_dir,
+            &HashMap::new(),
+            &[(config.build_path, "")],
             pb,
-            || async { self.db_executor.cp(config.build_path, &temp_dir, pb).await },
         )
         .await
     }
@@ -145,35 +149,29 @@ pub async fn build_benchmark_image(
             &self.app_docker,
             &benchmark.name,
             &temp_dir,
+            &benchmark.build_args,
+            &[
+                (benchmark.path.as_str(), ""),
+                (consts::BENCHMARK_DATA, consts::BENCHMARK_DATA),
+            ],
             pb,
-            || async {
-                let temp_dir_benchmarks_data = format!("{}/benchmarks_data", temp_dir);
-                self.executor.mkdir(&temp_dir_benchmarks_data).await?;
-                self.executor.cp(&benchmark.path, &temp_dir, pb).await?;
-                pb.set_position(0);
-                self.executor
-                    .cp(consts::BENCHMARK_DATA, &temp_dir_benchmarks_data, pb)
-                    .await?;
-                Ok(())
-            },
         )
         .await
     }
 
-    async fn build_image_with_progress<F, Fut>(
+    /// Syncs the build context of `image_name` from `sources` (`(local dir, path in the
+    /// context)`) into `temp_dir` and builds it, unless the same context was built before.
+    #[allow(clippy::too_many_arguments)]
+    async fn build_image_with_progress(
         &self,
         executor: &E,
         docker: &crate::docker::DockerManager<E>,
         image_name: &str,
         temp_dir: &str,
+        build_args: &HashMap<String, String>,
+        sources: &[(&str, &str)],
         pb: &ProgressBar,
-        prepare_context: F,
-    ) -> anyhow::Result<()>
-    where
-        F: FnOnce() -> Fut,
-        Fut: std::future::Future<Output = anyhow::Result<()>>,
-    {
-        executor.mkdir(temp_dir).await?;
+    ) -> anyhow::Result<()> {
         let original_style = pb.style().clone();
         pb.set_style(
             match ProgressStyle::default_bar()
@@ -185,11 +183,111 @@ async fn build_image_with_progress<F, Fut>(
         );
         pb.set_length(100);
 
-        prepare_context().await?;
+        let manifest = ContextManifest::scan(sources)?;
+        Self::sync_context(executor, temp_dir, sources, &manifest, pb).await?;
 
         pb.set_style(original_style);
         pb.set_position(0);
 
-        docker.build(None, image_name, temp_dir, pb).await
+        let built = docker
+            .build_cached(
+                None,
+                image_name,
+                temp_dir,
+                build_args,
+                &manifest.hash(build_args),
+                pb,
+            )
+            .await?;
+        if !built {
+            pb.set_message(format!("{} is up to date", image_name));
+        }
+        Ok(())
+    }
+
+    /// Brings `temp_dir` in line with `manifest`: a full copy the first time, afterwards
+    /// only the files whose hash differs from the manifest left by the previous sync.
+    async fn sync_context(
+        executor: &E,
+        temp_dir: &str,
+        sources: &[(&str, &str)],
+        manifest: &ContextManifest,
+        pb: &ProgressBar,
+    ) -> anyhow::Result<()> {
+        let manifest_path = format!("{}.context.json", temp_dir);
+        let remote = executor
+            .execute(
+                format!("cat {} 2>/dev/null || true", manifest_path),
+                &ProgressBar::hidden(),
+            )
+            .await?;
+        // Until the new manifest is written, an interrupted sync falls back to a full copy.
+        executor.rm(&manifest_path).await?;
+
+        match serde_json::from_str::<ContextManifest>(remote.trim()) {
+            Ok(remote) => {
+                let diff = manifest.diff(&remote);
+                if !diff.remove.is_empty() {
+                    let paths =
+                        Self::context_paths(temp_dir, diff.remove.iter().map(String::as_str));
+                    executor
+                        .execute(format!("rm -f {}", paths), &ProgressBar::hidden())
+                        .await?;
+                }
+                let dirs: BTreeSet<&str> = diff
+                    .upload
+                    .iter()
+                    .filter_map(|path| path.rsplit_once('/').map(|(dir, _)| dir))
+                    .collect();
+                if !dirs.is_empty() {
+                    let paths = Self::context_paths(temp_dir, dirs.into_iter());
+                    executor
+                        .execute(format!("mkdir -p {}", paths), &ProgressBar::hidden())
+                        .await?;
+                }
+                for path in &diff.upload {
+                    let local = manifest.local[path].to_string_lossy();
+                    executor
+                        .cp(&local, &format!("{}/{}", temp_dir, path), pb)
+                        .await?;
+                }
+            }
+            // No manifest (or an unreadable one): the directory can't be trusted.
+            Err(_) => {
+                executor.rm(temp_dir).await?;
+                for (dir, prefix) in sources {
+                    let dst = if prefix.is_empty() {
+                        temp_dir.to_string()
+                    } else {
+                        format!("{}/{}", temp_dir, prefix)
+                    };
+                    executor.mkdir(&dst).await?;
+                    pb.set_position(0);
+                    executor.cp(dir, &dst, pb).await?;
+                }
+            }
+        }
+
+        // Runners of other host sets sync the same image concurrently; each writes its own
+        // copy, removed again when it is dropped.
+        let local_manifest = tempfile::Builder::new()
+            .prefix("wfb-")
+            .suffix(".context.json")
+            .tempfile()?;
+        tokio::fs::write(local_manifest.path(), serde_json::to_vec(manifest)?).await?;
+        executor
+            .cp(
+                &local_manifest.path().to_string_lossy(),
+                &manifest_path,
+                &ProgressBar::hidden(),
+            )
+            .await
+    }
+
+    fn context_paths<'p>(temp_dir: &str, paths: impl Iterator<Item = &'p str>) -> String {
+        paths
+            .map(|path| shell_quote(&format!("{}/{}", temp_dir, path)))
+            .collect::<Vec<_>>()
+            .join(" ")
     }
 }
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+/// Content hashes of the files in an image build context, keyed by their path inside it.
+///
+/// Uploaded next to the context on the build host, so the next build only transfers the
+/// files whose hash changed and skips the build when nothing did.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ContextManifest {
+    pub files: BTreeMap<String, String>,
+    /// Where each file of a scanned context lives on this machine.
+    #[serde(skip)]
+    pub local: BTreeMap<String, PathBuf>,
+}
+
+/// What a sync has to do to turn the remote context into the local one.
+#[derive(Debug, Default, PartialEq)]
+pub struct ContextDiff {
+    pub upload: Vec<String>,
+    pub remove: Vec<String>,
+}
+
+impl ContextManifest {
+    /// Hashes every file under each `(local dir, context prefix)` source.
+    pub fn scan(sources: &[(&str, &str)]) -> anyhow::Result<Self> {
+        let mut manifest = Self::default();
+        for (dir, prefix) in sources {
+            manifest
+                .scan_dir(Path::new(dir), prefix)
+                .with_context(|| format!("Failed to hash build context {}", dir))?;
+        }
+        Ok(manifest)
+    }
+
+    /// Hash of the whole context together with the build args, used to tag built images.
+    pub fn hash(&self, build_args: &HashMap<String, String>) -> String {
+        let mut hasher = blake3::Hasher::new();
+        for (path, hash) in &self.files {
+            hasher.update(path.as_bytes());
+            hasher.update(b"\0");
+            hasher.update(hash.as_bytes());
+            hasher.update(b"\n");
+        }
+        let mut build_args: Vec<_> = build_args.iter().collect();
+        build_args.sort();
+        for (key, value) in build_args {
+            hasher.update(format!("--build-arg {}={}\n", key, value).as_bytes());
+        }
+        hasher.finalize().to_hex()[..16].to_string()
+    }
+
+    fn scan_dir(&mut self, dir: &Path, prefix: &str) -> anyhow::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let key = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if path.is_dir() {
+                self.scan_dir(&path, &key)?;
+            } else {
+                let content = std::fs::read(&path)?;
+                self.files
+                    .insert(key.clone(), blake3::hash(&content).to_hex().to_string());
+                self.local.insert(key, path);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn diff(&self, remote: &ContextManifest) -> ContextDiff {
+        ContextDiff {
+            upload: self
+                .files
+                .iter()
+                .filter(|(path, hash)| remote.files.get(*path) != Some(hash))
+                .map(|(path, _)| path.clone())
+                .collect(),
+            remove: remote
+                .files
+                .keys()
+                .filter(|path| !self.files.contains_key(*path))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContextManifest;
+    use std::collections::HashMap;
+
+    #[test]
+    fn diff_uploads_changed_files_and_removes_stale_ones() {
+        let manifest = |files: &[(&str, &str)]| ContextManifest {
+            files: files
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+        let local = manifest(&[("Dockerfile", "a"), ("src/main.rs", "b"), ("new.txt", "c")]);
+        let remote = manifest(&[("Dockerfile", "a"), ("src/main.rs", "x"), ("old.txt", "d")]);
+
+        let diff = local.diff(&remote);
+        assert_eq!(diff.upload, ["new.txt", "src/main.rs"]);
+        assert_eq!(diff.remove, ["old.txt"]);
+        assert!(local.diff(&local).upload.is_empty());
+
+        let args = HashMap::from([("FEATURES".to_string(), "simd".to_string())]);
+        assert_eq!(local.hash(&args), local.clone().hash(&args));
+        assert_ne!(local.hash(&args), local.hash(&HashMap::new()));
+        assert_ne!(local.hash(&args), remote.hash(&args));
+    }
+}
@@ -1,14 +1,14 @@
-use crate::consts;
 use crate::db_config::get_db_config;
 use crate::exec::Executor;
 use crate::runner::Runner;
 use indicatif::ProgressBar;
-use wfb_storage::DatabaseKind;
+use wfb_storage::{DatabaseKind, ResourceLimits};
 
 impl<E: Executor + Clone + Send + 'static> Runner<E> {
     pub async fn setup_database(
         &self,
         db_kind: &DatabaseKind,
+        limits: &ResourceLimits,
         pb: &ProgressBar,
     ) -> anyhow::Result<()> {
         let config = get_db_config(db_kind);
@@ -16,7 +16,8 @@ pub async fn setup_database(
         let mut cmd = self
             .db_docker
             .run_command(config.image_name, config.image_name)
-            .port(consts::DB_PORT_EXTERNAL, config.port);
+            .port(self.config.db_port, config.port)
+            .limits(limits);
 
         for (k, v) in config.env_vars {
             cmd = cmd.env(k, v);
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::docker::api::DockerApi;
+use crate::docker::kube::KubeTarget;
+use crate::exec::local::LocalExecutor;
+use crate::exec::ssh::SshExecutor;
+use crate::runner::{BenchmarkRunner, Runner, RunnerConfig};
+use wfb_storage::{
+    Config, ContainerRuntime, DockerBackend, Environment, SshConnection, SshEnvironment,
+    SshHostSet, Storage,
+};
+
+/// Builds a runner for every host set of `environment`: one for a local or Kubernetes
+/// environment, one per entry of the host pool for an SSH environment.
+pub async fn build_runners(
+    environment: &Environment,
+    storage: &Storage,
+    run_id: &str,
+    config: &Config,
+) -> anyhow::Result<Vec<Arc<dyn BenchmarkRunner>>> {
+    let use_api = use_docker_api(environment)?;
+    match environment {
+        Environment::Local(local) => {
+            let executor = LocalExecutor::new();
+            let mut runner = Runner::new(
+                executor.clone(),
+                executor.clone(),
+                executor,
+                false,
+                RunnerConfig::local(local),
+                storage.clone(),
+                run_id.to_string(),
+                environment.clone(),
+                config.clone(),
+            );
+            if use_api {
+                let api = DockerApi::connect_local()?;
+                runner = runner.with_docker_apis(api.clone(), api.clone(), api);
+            }
+            Ok(vec![Arc::new(runner)])
+        }
+        Environment::Ssh(ssh) => {
+            let mut executors = SshExecutors::default();
+            let mut runners: Vec<Arc<dyn BenchmarkRunner>> = Vec::new();
+            for host in ssh.host_sets() {
+                let runner = build_ssh_runner(
+                    environment,
+                    ssh,
+                    &host,
+                    &mut executors,
+                    storage,
+                    run_id,
+                    config,
+                )
+                .await?;
+                runners.push(Arc::new(runner));
+            }
+            Ok(runners)
+        }
+        Environment::Kubernetes(kube) => {
+            // `kubectl` and the image builds run on this machine.
+            let executor = LocalExecutor::new();
+            let runner = Runner::new(
+                executor.clone(),
+                executor.clone(),
+                executor,
+                false,
+                RunnerConfig::kubernetes(kube),
+                storage.clone(),
+                run_id.to_string(),
+                environment.clone(),
+                config.clone(),
+            )
+            .with_kubernetes(
+                KubeTarget::new(kube, &kube.nodes.app),
+                KubeTarget::new(kube, &kube.nodes.db),
+                KubeTarget::new(kube, &kube.nodes.wrkr),
+            );
+            Ok(vec![Arc::new(runner)])
+        }
+    }
+}
+
+/// Whether the environment drives its containers through the Docker Engine API, failing
+/// for runtimes that don't serve it where the runner looks for it.
+fn use_docker_api(environment: &Environment) -> anyhow::Result<bool> {
+    let use_api = environment.docker_backend() == DockerBackend::Api;
+    match environment.container_runtime() {
+        ContainerRuntime::Nerdctl if use_api => anyhow::bail!(
+            "docker_backend: api needs a Docker-compatible daemon, which nerdctl does not provide"
+        ),
+        // The API backend talks to /var/run/docker.sock; rootless Podman serves its API
+        // on a per-user socket instead.
+        ContainerRuntime::Podman if use_api => anyhow::bail!(
+            "docker_backend: api talks to the Docker daemon socket; use docker_backend: cli with Podman"
+        ),
+        _ => Ok(use_api),
+    }
+}
+
+async fn build_ssh_runner(
+    environment: &Environment,
+    ssh: &SshEnvironment,
+    host: &SshHostSet,
+    executors: &mut SshExecutors,
+    storage: &Storage,
+    run_id: &str,
+    config: &Config,
+) -> anyhow::Result<Runner<SshExecutor>> {
+    let missing = |section: &str| {
+        anyhow::anyhow!(
+            "SSH Config: {} section missing for host set '{}'",
+            section,
+            host.name
+        )
+    };
+    let app = host.app.as_ref().ok_or_else(|| missing("app"))?;
+    let db = host.db.as_ref().ok_or_else(|| missing("db"))?;
+    let wrkr = host.wrkr.as_ref().ok_or_else(|| missing("wrkr"))?;
+
+    let mut runner = Runner::new(
+        executors.get(app),
+        executors.get(db),
+        executors.get(wrkr),
+        true,
+        RunnerConfig::ssh(ssh, &host.name, app, db),
+        storage.clone(),
+        run_id.to_string(),
+        environment.clone(),
+        config.clone(),
+    );
+    if use_docker_api(environment)? {
+        runner = runner.with_docker_apis(
+            DockerApi::connect_ssh(app).await?,
+            DockerApi::connect_ssh(db).await?,
+            DockerApi::connect_ssh(wrkr).await?,
+        );
+    }
+    Ok(runner)
+}
+
+/// One executor per SSH user, host and port, so roles and host sets on the same machine
+/// share its sessions.
+#[derive(Default)]
+struct SshExecutors(HashMap<(String, String, u16), SshExecutor>);
+
+impl SshExecutors {
+    fn get(&mut self, connection: &SshConnection) -> SshExecutor {
+        self.0
+            .entry((
+                connection.user.clone(),
+                connection.ip.clone(),
+                connection.port,
+            ))
+            .or_insert_with(|| SshExecutor::from_config(connection))
+            .clone()
+    }
+}
+
+/// Builds the runner of the environment's first host set, for commands that drive one host.
+pub async fn build_runner(
+    environment: &Environment,
+    storage: &Storage,
+    run_id: &str,
+    config: &Config,
+) -> anyhow::Result<Arc<dyn BenchmarkRunner>> {
+    match environment {
+        Environment::Ssh(ssh) => {
+            // `host_sets` always yields at least one set.
+            let host = ssh.host_sets().swap_remove(0);
+            let runner = build_ssh_runner(
+                environment,
+                ssh,
+                &host,
+                &mut SshExecutors::default(),
+                storage,
+                run_id,
+                config,
+            )
+            .await?;
+            Ok(Arc::new(runner))
+        }
+        // A single host set, so a single runner.
+        Environment::Local(_) | Environment::Kubernetes(_) => {
+            Ok(build_runners(environment, storage, run_id, config)
+                .await?
+                .swap_remove(0))
+        }
+    }
+}
@@ -1,15 +1,23 @@
 pub mod benchmark;
 pub mod build;
+pub mod context;
 pub mod database;
+pub mod factory;
 
 use crate::consts;
-use crate::docker::DockerManager;
+use crate::docker::api::DockerApi;
+use crate::docker::command::ContainerCli;
+use crate::docker::kube::KubeTarget;
+use crate::docker::{DockerManager, HealthStatus};
 use crate::exec::Executor;
 use async_trait::async_trait;
 use indicatif::{MultiProgress, ProgressBar};
 use std::time::Duration;
 use tokio::time::sleep;
-use wfb_storage::{Benchmark, Config, DatabaseKind, Environment, Storage};
+use wfb_storage::{
+    Benchmark, Config, DatabaseKind, Environment, KubernetesEnvironment, LocalEnvironment,
+    SshConnection, SshEnvironment, Storage,
+};
 
 #[async_trait]
 pub trait BenchmarkRunner: Send + Sync {
@@ -25,17 +33,105 @@ async fn verify_benchmark(
         benchmark: &Benchmark,
         mb: &MultiProgress,
     ) -> anyhow::Result<()>;
-    async fn run_benchmark(&self, benchmark: &Benchmark, mb: &MultiProgress) -> anyhow::Result<()>;
+    async fn run_benchmark(
+        &self,
+        benchmark: &Benchmark,
+        options: &RunOptions,
+        mb: &MultiProgress,
+    ) -> anyhow::Result<()>;
     async fn dev_benchmark(&self, benchmark: &Benchmark, mb: &MultiProgress) -> anyhow::Result<()>;
 }
 
+/// Options for `run` that apply to every benchmark in the session.
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    /// Number of measured iterations per test. Values above 1 store every iteration
+    /// and attach run-to-run statistics to the aggregated result.
+    pub repeat: u32,
+    /// Rates for the constant-arrival-rate sweep, overriding the load profile when non-empty.
+    pub arrival_rates: Vec<u64>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            repeat: 1,
+            arrival_rates: Vec::new(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RunnerConfig {
     pub db_host: String,
-    pub db_port: String,
+    pub db_port: u16,
+    pub app_port: u16,
     pub app_host_url: String,
     pub app_public_host_url: String,
     pub is_remote: bool,
+    /// Host set of the environment's pool this runner drives; recorded with its results.
+    pub host: Option<String>,
+}
+
+impl RunnerConfig {
+    /// Everything runs on this machine; containers reach each other through the Docker host.
+    pub fn local(env: &LocalEnvironment) -> Self {
+        let app_port = env.endpoints.app_port.unwrap_or(consts::APP_PORT_EXTERNAL);
+        let host = ContainerCli::new(env.container_runtime, false).host_alias();
+        Self {
+            db_host: env
+                .endpoints
+                .db_host
+                .clone()
+                .unwrap_or_else(|| host.to_string()),
+            db_port: env.endpoints.db_port.unwrap_or(consts::DB_PORT_EXTERNAL),
+            app_port,
+            app_host_url: format!("http://{}:{}", host, app_port),
+            app_public_host_url: format!("http://localhost:{}", app_port),
+            is_remote: false,
+            host: None,
+        }
+    }
+
+    /// One host set of an SSH environment: the app and wrkr reach their peers over the
+    /// internal network, the printed URL uses the app host's public address.
+    pub fn ssh(env: &SshEnvironment, host: &str, app: &SshConnection, db: &SshConnection) -> Self {
+        let app_port = env.endpoints.app_port.unwrap_or(consts::APP_PORT_EXTERNAL);
+        Self {
+            db_host: env
+                .endpoints
+                .db_host
+                .clone()
+                .unwrap_or_else(|| db.internal_ip.clone()),
+            db_port: env.endpoints.db_port.unwrap_or(consts::DB_PORT_EXTERNAL),
+            app_port,
+            app_host_url: format!("http://{}:{}", app.internal_ip, app_port),
+            app_public_host_url: format!("http://{}:{}", app.ip, app_port),
+            is_remote: true,
+            host: Some(host.to_string()),
+        }
+    }
+
+    /// Pods of a Kubernetes environment reach each other through the `wfb-<port>` services
+    /// in front of the published ports.
+    pub fn kubernetes(env: &KubernetesEnvironment) -> Self {
+        let app_port = env.endpoints.app_port.unwrap_or(consts::APP_PORT_EXTERNAL);
+        let db_port = env.endpoints.db_port.unwrap_or(consts::DB_PORT_EXTERNAL);
+        let app_host_url = format!("http://{}:{}", KubeTarget::service_name(app_port), app_port);
+        Self {
+            db_host: env
+                .endpoints
+                .db_host
+                .clone()
+                .unwrap_or_else(|| KubeTarget::service_name(db_port)),
+            db_port,
+            app_port,
+            app_public_host_url: app_host_url.clone(),
+            app_host_url,
+            is_remote: false,
+            host: None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -73,9 +169,7 @@ async fn prepare(&self, mb: &MultiProgress) -> anyhow::Result<()> {
         self.app_docker.stop_all_containers(&pb).await;
         self.db_docker.stop_all_containers(&pb).await;
 
-        self.executor.rm(consts::REMOTE_APP_PATH).await?;
-        self.executor.rm(consts::REMOTE_DB_PATH).await?;
-        self.db_executor.rm(consts::REMOTE_DB_PATH).await?;
+        // Build contexts are kept so the next build only transfers what changed.
         self.wrkr_executor.rm(consts::REMOTE_WRKR_PATH).await?;
 
         self.executor.mkdir(consts::REMOTE_APP_PATH).await?;
@@ -112,8 +206,13 @@ async fn verify_benchmark(
         self.verify_benchmark_impl(benchmark, mb).await
     }
 
-    async fn run_benchmark(&self, benchmark: &Benchmark, mb: &MultiProgress) -> anyhow::Result<()> {
-        self.run_benchmark_impl(benchmark, mb).await
+    async fn run_benchmark(
+        &self,
+        benchmark: &Benchmark,
+        options: &RunOptions,
+        mb: &MultiProgress,
+    ) -> anyhow::Result<()> {
+        self.run_benchmark_impl(benchmark, options, mb).await
     }
 
     async fn dev_benchmark(&self, benchmark: &Benchmark, mb: &MultiProgress) -> anyhow::Result<()> {
@@ -134,13 +233,14 @@ pub fn new(
         environment: Environment,
         wfb_config: Config,
     ) -> Self {
+        let cli = ContainerCli::new(environment.container_runtime(), sudo);
         Self {
             executor: app_executor.clone(),
             db_executor: db_executor.clone(),
             wrkr_executor: wrkr_executor.clone(),
-            app_docker: DockerManager::new(app_executor, sudo),
-            db_docker: DockerManager::new(db_executor, sudo),
-            wrkr_docker: DockerManager::new(wrkr_executor, sudo),
+            app_docker: DockerManager::new(app_executor, cli),
+            db_docker: DockerManager::new(db_executor, cli),
+            wrkr_docker: DockerManager::new(wrkr_executor, cli),
             config,
             storage,
             run_id,
@@ -149,6 +249,22 @@ pub fn new(
         }
     }
 
+    /// Runs containers on the app, database and wrkr hosts through the Docker Engine API.
+    pub fn with_docker_apis(mut self, app: DockerApi, db: DockerApi, wrkr: DockerApi) -> Self {
+        self.app_docker = self.app_docker.with_api(app);
+        self.db_docker = self.db_docker.with_api(db);
+        self.wrkr_docker = self.wrkr_docker.with_api(wrkr);
+        self
+    }
+
+    /// Runs the app, database and wrkr as pods of a Kubernetes cluster.
+    pub fn with_kubernetes(mut self, app: KubeTarget, db: KubeTarget, wrkr: KubeTarget) -> Self {
+        self.app_docker = self.app_docker.with_kubernetes(app);
+        self.db_docker = self.db_docker.with_kubernetes(db);
+        self.wrkr_docker = self.wrkr_docker.with_kubernetes(wrkr);
+        self
+    }
+
     async fn wait_for_container_ready(
         &self,
         docker: &DockerManager<E>,
@@ -159,28 +275,14 @@ async fn wait_for_container_ready(
         let mut retries = 0;
 
         loop {
-            let format = "{{if .State.Health}}{{.State.Health.Status}}|{{range .State.Health.Log}}[{{.ExitCode}}] {{.Output}}__SEP__{{end}}{{else}}none{{end}}";
-            let output = docker.inspect(container_name, format).await?;
-            let (status, health_log) = match output.split_once('|') {
-                Some((s, l)) => (s.trim(), l),
-                None => (output.trim(), ""),
-            };
+            let health = docker.health(container_name).await?;
+            let status = health.status;
+            let last_log = health.last_log.as_deref().unwrap_or("");
 
-            let last_log = if !health_log.is_empty() {
-                health_log
-                    .split("__SEP__")
-                    .map(|s| s.trim())
-                    .filter(|s| !s.is_empty())
-                    .last()
-                    .unwrap_or("")
-            } else {
-                ""
-            };
-
-            if status == "healthy" {
+            if status == HealthStatus::Healthy {
                 pb.set_message(format!("Container {} is healthy", container_name));
                 return Ok(());
-            } else if status == "unhealthy" {
+            } else if status == HealthStatus::Unhealthy {
                 anyhow::bail!(
                     "Container {} is unhealthy. Last health check: {}",
                     container_name,
@@ -214,3 +316,41 @@ async fn wait_for_container_ready(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RunnerConfig;
+    use crate::consts;
+    use wfb_storage::SshEnvironment;
+
+    #[test]
+    fn ssh_config_applies_endpoint_overrides() {
+        let connection = |ip: &str, internal_ip: &str| serde_json::json!({ "ip": ip, "internal_ip": internal_ip, "user": "wfb", "ssh_key_path": "k" });
+        let env: SshEnvironment = serde_json::from_value(serde_json::json!({
+            "name": "r640",
+            "title": "R640",
+            "spec": null,
+            "icon": null,
+            "endpoints": { "app_port": 9000 },
+            "app": connection("10.0.0.1", "192.168.0.1"),
+            "db": connection("10.0.0.2", "192.168.0.2"),
+        }))
+        .unwrap();
+        let (app, db) = (env.app.as_ref().unwrap(), env.db.as_ref().unwrap());
+
+        let config = RunnerConfig::ssh(&env, "r640", app, db);
+        assert_eq!(config.app_host_url, "http://192.168.0.1:9000");
+        assert_eq!(config.app_public_host_url, "http://10.0.0.1:9000");
+        assert_eq!(config.db_host, "192.168.0.2");
+        assert_eq!(config.db_port, consts::DB_PORT_EXTERNAL);
+
+        let mut env = env.clone();
+        env.endpoints.db_host = Some("db.internal".to_string());
+        env.endpoints.db_port = Some(5432);
+        let config = RunnerConfig::ssh(&env, "r640", app, db);
+        assert_eq!(
+            (config.db_host.as_str(), config.db_port),
+            ("db.internal", 5432)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use clap::Args;
+use glob::Pattern;
+use wfb_storage::{Benchmark, BenchmarkTests, DatabaseKind};
+
+/// Benchmark/test filters shared by `run` and `verify`.
+///
+/// Each flag can be repeated; a benchmark is selected when it matches any value of every
+/// flag that was given. Names are glob patterns (`spring-*`).
+#[derive(Args, Debug, Clone, Default)]
+pub struct Selection {
+    /// Only benchmarks whose name matches (glob, e.g. `spring-*`)
+    #[arg(short, long = "benchmark", value_name = "GLOB", value_parser = parse_pattern)]
+    pub benchmarks: Vec<Pattern>,
+
+    /// Only benchmarks of this language (glob)
+    #[arg(short, long = "language", value_name = "GLOB", value_parser = parse_pattern)]
+    pub languages: Vec<Pattern>,
+
+    /// Only these test cases, by their config name (plain_text, json_aggregate, static_files,
+    /// db_complex, grpc_aggregate)
+    #[arg(
+        short,
+        long = "testcase",
+        value_name = "TEST",
+        value_parser = parse_serde_name::<BenchmarkTests>
+    )]
+    pub testcases: Vec<BenchmarkTests>,
+
+    /// Only benchmarks with this tag, as `key=value` (value is a glob, e.g. `runtime=jvm`);
+    /// repeated tags must all match
+    #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+    pub tags: Vec<TagFilter>,
+
+    /// Only benchmarks using this database (postgres, mysql, mariadb, mssql, mongodb, or `none`)
+    #[arg(long = "db", value_name = "DB", value_parser = parse_database)]
+    pub databases: Vec<DatabaseFilter>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TagFilter {
+    pub key: String,
+    pub value: Pattern,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseFilter {
+    None,
+    Kind(DatabaseKind),
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.benchmarks.is_empty()
+            && self.languages.is_empty()
+            && self.testcases.is_empty()
+            && self.tags.is_empty()
+            && self.databases.is_empty()
+    }
+
+    pub fn matches(&self, benchmark: &Benchmark) -> bool {
+        let any = |patterns: &[Pattern], value: &str| {
+            patterns.is_empty() || patterns.iter().any(|p| p.matches(value))
+        };
+        let database = match benchmark.database {
+            Some(kind) => DatabaseFilter::Kind(kind),
+            None => DatabaseFilter::None,
+        };
+
+        any(&self.benchmarks, &benchmark.name)
+            && any(&self.languages, &benchmark.language)
+            && self.tags.iter().all(|tag| {
+                benchmark
+                    .tags
+                    .get(&tag.key)
+                    .is_some_and(|value| tag.value.matches(value))
+            })
+            && (self.databases.is_empty() || self.databases.contains(&database))
+    }
+
+    /// Keeps the selected benchmarks, narrowed to the selected tests.
+    pub fn apply(&self, benchmarks: Vec<Benchmark>) -> anyhow::Result<Vec<Benchmark>> {
+        let selected: Vec<Benchmark> = benchmarks
+            .into_iter()
+            .filter(|b| self.matches(b))
+            .filter_map(|mut b| {
+                if !self.testcases.is_empty() {
+                    b.tests.retain(|test| self.testcases.contains(test));
+                }
+                (!b.tests.is_empty()).then_some(b)
+            })
+            .collect();
+
+        if selected.is_empty() && !self.is_empty() {
+            anyhow::bail!("No benchmarks match the selection");
+        }
+        Ok(selected)
+    }
+}
+
+fn parse_pattern(s: &str) -> Result<Pattern, String> {
+    Pattern::new(s).map_err(|e| format!("invalid pattern '{}': {}", s, e))
+}
+
+/// Parses a unit enum variant by its serde name, as used in the config YAML.
+fn parse_serde_name<T: serde::de::DeserializeOwned>(s: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(s.to_lowercase()))
+        .map_err(|_| format!("unknown value '{}'", s))
+}
+
+fn parse_tag(s: &str) -> Result<TagFilter, String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("invalid tag filter '{}', expected key=value", s))?;
+    Ok(TagFilter {
+        key: key.to_string(),
+        value: parse_pattern(value)?,
+    })
+}
+
+fn parse_database(s: &str) -> Result<DatabaseFilter, String> {
+    if s.eq_ignore_ascii_case("none") {
+        return Ok(DatabaseFilter::None);
+    }
+    parse_serde_name(s).map(DatabaseFilter::Kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selection;
+    use crate::cli::{Args, Commands};
+    use clap::Parser;
+    use wfb_storage::{Benchmark, BenchmarkTests, DatabaseKind};
+
+    fn benchmark(name: &str, runtime: &str, database: Option<DatabaseKind>) -> Benchmark {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "language": "Java",
+            "language_version": "21",
+            "framework": name,
+            "framework_version": "1",
+            "path": name,
+            "tests": ["plain_text", "db_complex"],
+            "tags": { "runtime": runtime },
+            "database": database,
+        }))
+        .unwrap()
+    }
+
+    fn selection(args: &[&str]) -> Selection {
+        let args = Args::try_parse_from(["wfb-runner", "run", "r1"].iter().chain(args)).unwrap();
+        match args.command {
+            Commands::Run { selection, .. } => selection,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn selection_filters_by_glob_tag_database_and_test() {
+        let benchmarks = vec![
+            benchmark("spring-boot", "jvm", Some(DatabaseKind::Postgres)),
+            benchmark("spring-webflux", "jvm", None),
+            benchmark("quarkus-native", "native", None),
+        ];
+
+        let selected = selection(&["--tag", "runtime=jvm", "-t", "plain_text", "--db", "none"])
+            .apply(benchmarks.clone())
+            .unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "spring-webflux");
+        assert_eq!(selected[0].tests, [BenchmarkTests::PlainText]);
+
+        let selected = selection(&["-b", "spring-*", "--db", "postgres"])
+            .apply(benchmarks.clone())
+            .unwrap();
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].name, "spring-boot");
+
+        assert_eq!(selection(&[]).apply(benchmarks.clone()).unwrap().len(), 3);
+        assert!(selection(&["-b", "gin"]).apply(benchmarks).is_err());
+        assert!(Args::try_parse_from(["wfb-runner", "run", "r1", "-t", "plaintext"]).is_err());
+    }
+}
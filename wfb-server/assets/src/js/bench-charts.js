@@ -86,7 +86,8 @@
 
     try {
       const parsed = JSON.parse(el.textContent || "{}");
-      if (!parsed || !Array.isArray(parsed.x)) return null;
+      if (!parsed) return null;
+      if (!Array.isArray(parsed.x) && !Array.isArray(parsed.percentiles)) return null;
       return parsed;
     } catch {
       return null;
@@ -138,6 +139,15 @@
       return;
     }
 
+    if (container.dataset.wfbChartKind === "latency-spectrum") {
+      initSpectrum(container, root, data, width, height);
+      return;
+    }
+    if (container.dataset.wfbChartKind === "time-overlay") {
+      initTimeOverlay(container, root, data, width, height);
+      return;
+    }
+
     const gridStroke = cssVar("--border", "rgba(148,163,184,0.25)");
     const axisStroke = cssVar("--muted-foreground", "#94a3b8");
 
@@ -356,6 +366,233 @@
     }
   }
 
+  // Percentile p (0..100) maps to 1/(1-p) so each extra "nine" gets equal width on a log axis.
+  function percentileToX(p) {
+    return 1 / (1 - p / 100);
+  }
+
+  function formatPercentile(p) {
+    p = toFiniteNumber(p);
+    if (p === null) return "";
+    return "p" + String(Number(p.toFixed(3)));
+  }
+
+  function overlayPalette() {
+    return [1, 2, 3, 4, 5].map((i) =>
+      cssVar("--chart-" + i, ["#6366f1", "#22d3ee", "#34d399", "#f59e0b", "#f43f5e"][i - 1])
+    );
+  }
+
+  function positiveOrNull(v) {
+    const n = toFiniteNumber(v);
+    return n !== null && n > 0 ? n : null;
+  }
+
+  function initSpectrum(container, root, data, width, height) {
+    const percentiles = data.percentiles || [];
+    const xs = percentiles.map(percentileToX);
+    const formatX = (x) => formatPercentile(100 * (1 - 1 / x));
+
+    initOverlay(container, root, width, height, {
+      xs,
+      series: (data.series || []).map((s) => ({
+        label: s.label,
+        values: (s.latency_ms || []).map(positiveOrNull),
+      })),
+      xScale: { time: false, distr: 3, log: 10 },
+      xAxis: { splits: () => xs, values: (u, ticks) => ticks.map(formatX) },
+      formatX,
+      yScale: { distr: 3, log: 10 },
+      yLabel: "Latency",
+      formatY: formatMs,
+      points: true,
+    });
+  }
+
+  const OVERLAY_UNITS = {
+    rps: { label: "RPS", format: (v) => (isFinite(v) ? formatNumberCompact(v) + " req/s" : ""), axis: formatNumberCompact },
+    ms: { label: "P99", format: formatMs, axis: (v) => formatMs(v).replace(/\s.*$/, "") },
+    mb: { label: "Mem", format: formatBytesMb, axis: (v) => formatBytesMb(v).replace(/\s.*$/, "") },
+  };
+
+  // Same metric for several series over elapsed time (compare page) or, when `x_labels`
+  // is present, over labelled points such as runs (history page).
+  function initTimeOverlay(container, root, data, width, height) {
+    const unit = OVERLAY_UNITS[data.unit] || OVERLAY_UNITS.rps;
+    const xLabels = Array.isArray(data.x_labels) ? data.x_labels : null;
+    const formatX = xLabels
+      ? (x) => xLabels[Math.round(x)] || ""
+      : (x) => (isFinite(x) ? "t=" + Number(x).toFixed(0) + "s" : "");
+    const xAxis = xLabels
+      ? { splits: () => data.x, values: (u, ticks) => ticks.map(formatX) }
+      : {
+          values: (u, ticks) =>
+            ticks.map((v) => {
+              const n = toFiniteNumber(v);
+              return n === null ? "" : n.toFixed(0) + "s";
+            }),
+        };
+
+    initOverlay(container, root, width, height, {
+      xs: data.x,
+      series: (data.labels || []).map((label, i) => ({
+        label,
+        values: ((data.values || [])[i] || []).map(toFiniteNumber),
+      })),
+      xScale: { time: false },
+      xAxis,
+      formatX,
+      yScale: { auto: true },
+      yLabel: unit.label,
+      formatY: unit.format,
+      formatAxisY: unit.axis,
+      points: !!xLabels,
+    });
+  }
+
+  // One series per framework on a shared x axis, with a cursor tooltip and legend.
+  function initOverlay(container, root, width, height, cfg) {
+    const gridStroke = cssVar("--border", "rgba(148,163,184,0.25)");
+    const axisStroke = cssVar("--muted-foreground", "#94a3b8");
+    const palette = overlayPalette();
+
+    const series = cfg.series.map((s, i) => ({ ...s, color: palette[i % palette.length] }));
+    const formatAxisY = cfg.formatAxisY || cfg.formatY;
+
+    const opts = {
+      width,
+      height,
+      padding: [12, 14, 10, 10],
+      legend: { show: false },
+      cursor: { drag: { setScale: false } },
+      scales: {
+        x: cfg.xScale,
+        y: cfg.yScale,
+      },
+      axes: [
+        {
+          scale: "x",
+          stroke: axisStroke,
+          grid: { stroke: gridStroke, width: 1 },
+          ...cfg.xAxis,
+        },
+        {
+          scale: "y",
+          label: cfg.yLabel,
+          stroke: axisStroke,
+          grid: { stroke: gridStroke, width: 1 },
+          values: (u, ticks) => ticks.map(formatAxisY),
+        },
+      ],
+      series: [
+        { label: "x" },
+        ...series.map((s) => ({
+          label: s.label,
+          scale: "y",
+          stroke: s.color,
+          width: 2,
+          spanGaps: true,
+          points: { show: cfg.points, size: 5 },
+        })),
+      ],
+    };
+
+    const tooltip = ensureTooltip(root);
+    let rafId = 0;
+    let pending = null;
+    function scheduleTooltipUpdate(u, idx) {
+      pending = idx;
+      if (rafId) return;
+      rafId = requestAnimationFrame(() => {
+        rafId = 0;
+        const i = pending;
+        if (i == null || i < 0) {
+          tooltip.style.display = "none";
+          return;
+        }
+
+        const rows = series
+          .map((s) => ({ s, val: cfg.formatY(s.values[i]) }))
+          .filter((r) => r.val);
+        if (rows.length === 0) {
+          tooltip.style.display = "none";
+          return;
+        }
+
+        // Labels are framework names; build rows with textContent rather than HTML.
+        tooltip.replaceChildren();
+        const title = document.createElement("div");
+        title.className = "wfb-chart-tooltip-title";
+        title.textContent = cfg.formatX(u.data[0][i]);
+        tooltip.appendChild(title);
+        for (const { s, val } of rows) {
+          const row = document.createElement("div");
+          row.className = "wfb-chart-tooltip-row";
+          const swatch = document.createElement("span");
+          swatch.className = "wfb-chart-tooltip-swatch";
+          swatch.style.background = s.color;
+          const label = document.createElement("span");
+          label.className = "wfb-chart-tooltip-label";
+          label.textContent = s.label + ":";
+          const value = document.createElement("span");
+          value.className = "wfb-chart-tooltip-value";
+          value.textContent = val;
+          row.append(swatch, label, value);
+          tooltip.appendChild(row);
+        }
+        tooltip.style.display = "block";
+
+        const left = Number.isFinite(u.cursor.left) ? u.cursor.left : 0;
+        const top = Number.isFinite(u.cursor.top) ? u.cursor.top : 0;
+        const rect = root.getBoundingClientRect();
+        const tipRect = tooltip.getBoundingClientRect();
+        const edgePad = 10;
+
+        let xPx = left + 16;
+        if (xPx + tipRect.width > rect.width - edgePad) xPx = left - tipRect.width - 16;
+        tooltip.style.left = Math.max(edgePad, xPx) + "px";
+        tooltip.style.top = Math.max(edgePad, top + 22) + "px";
+      });
+    }
+
+    const plot = new uPlot(
+      {
+        ...opts,
+        hooks: {
+          setCursor: [
+            (u) => scheduleTooltipUpdate(u, u.cursor && Number.isFinite(u.cursor.idx) ? u.cursor.idx : null),
+          ],
+        },
+      },
+      [cfg.xs, ...series.map((s) => s.values)],
+      root
+    );
+
+    root.addEventListener("mouseleave", function () {
+      tooltip.style.display = "none";
+    });
+
+    setLegendItems(
+      container.querySelector("[data-wfb-chart-legend]"),
+      series.map((s) => ({ label: s.label, color: s.color }))
+    );
+
+    container.dataset.wfbChartInit = "1";
+    container.__wfbPlot = plot;
+
+    if (typeof ResizeObserver !== "undefined") {
+      const ro = new ResizeObserver(() => {
+        const rect = root.getBoundingClientRect();
+        const w = Math.floor(rect.width || 0);
+        const h = Math.floor(rect.height || 0);
+        if (w < 120 || h < 120) return;
+        plot.setSize({ width: w, height: h });
+      });
+      ro.observe(root);
+      container.__wfbChartRO = ro;
+    }
+  }
+
   function initAll(scope) {
     const root = scope && scope.querySelectorAll ? scope : document;
     const charts = root.querySelectorAll("[data-wfb-bench-chart]");
@@ -42,6 +42,7 @@ pub struct BenchmarkInfo {
     pub disabled: bool,
     pub only: bool,
     pub arguments: Vec<String>,
+    pub build_args: HashMap<String, String>,
     pub env: HashMap<String, String>,
 }
 
@@ -64,6 +65,40 @@ pub struct TranscriptParams {
     pub lang: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct HistoryParams {
+    pub env: String,
+    pub test: String,
+    pub lang: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RegressionsParams {
+    pub env: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LogsParams {
+    pub lang: Option<String>,
+    /// `app`, `db` or `wrkr` to get that log as plain text instead of all of them as JSON.
+    pub kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct JournalParams {
+    /// Only attempts in this state (`running`, `succeeded`, `failed`).
+    pub state: Option<wfb_storage::AttemptState>,
+    /// Return every attempt instead of the latest one per test.
+    #[serde(default)]
+    pub all: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CompareParams {
+    /// Comma-separated frameworks, e.g. `axum,actix-web,fiber`.
+    pub f: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RunSummary {
@@ -78,6 +113,19 @@ fn serialize_duration_as_nanos<S>(duration: &Duration, serializer: S) -> Result<
     serializer.serialize_u128(duration.as_nanos())
 }
 
+fn serialize_optional_duration_as_nanos<S>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match duration {
+        Some(duration) => serializer.serialize_some(&duration.as_nanos()),
+        None => serializer.serialize_none(),
+    }
+}
+
 fn serialize_latency_distribution<S>(
     dist: &Vec<(u8, Duration)>,
     serializer: S,
@@ -102,8 +150,14 @@ pub struct RunResult {
     pub framework: String,
     pub framework_version: String,
     pub database: Option<String>,
+    /// Host set of the environment the test ran on; absent for local runs and older results.
+    pub host: Option<String>,
     pub path: Option<String>,
     pub rps: f64,
+    /// Best RPS sustained with P99 within `latency99Slo`; absent for older results.
+    pub rps_at_slo: Option<f64>,
+    #[serde(serialize_with = "serialize_optional_duration_as_nanos")]
+    pub latency99_slo: Option<Duration>,
     pub tps: u64,
     #[serde(serialize_with = "serialize_duration_as_nanos")]
     pub latency_avg: Duration,
@@ -131,6 +185,163 @@ pub struct RunResult {
     pub tags: HashMap<String, String>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRegressionsApi {
+    pub environment: String,
+    /// Previous run of the environment the results are compared against.
+    pub base_run: String,
+    pub head_run: String,
+    pub threshold_pct: f64,
+    pub regressions: Vec<TestChangeApi>,
+    pub improvements: Vec<TestChangeApi>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestChangeApi {
+    /// Run this test was compared against, the latest earlier run that included it.
+    pub base_run: String,
+    pub language: String,
+    pub framework: String,
+    pub test: String,
+    pub significance: wfb_storage::Significance,
+    pub rps: MetricDeltaApi,
+    pub latency99: MetricDeltaApi,
+    pub memory_usage: MetricDeltaApi,
+    pub errors: MetricDeltaApi,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDeltaApi {
+    pub base: f64,
+    pub head: f64,
+    pub change_pct: Option<f64>,
+}
+
+impl From<wfb_storage::MetricDelta> for MetricDeltaApi {
+    fn from(d: wfb_storage::MetricDelta) -> Self {
+        Self {
+            base: d.base,
+            head: d.head,
+            change_pct: d.change_pct,
+        }
+    }
+}
+
+impl From<&wfb_storage::TestCaseComparison> for TestChangeApi {
+    fn from(t: &wfb_storage::TestCaseComparison) -> Self {
+        Self {
+            base_run: t.base_run.clone(),
+            language: t.language.clone(),
+            framework: t.benchmark.clone(),
+            test: t.test.clone(),
+            significance: t.significance,
+            rps: t.requests_per_sec.into(),
+            latency99: t.latency_p99.into(),
+            memory_usage: t.memory_usage_bytes.into(),
+            errors: t.total_errors.into(),
+        }
+    }
+}
+
+impl From<&wfb_storage::RunComparison> for RunRegressionsApi {
+    fn from(c: &wfb_storage::RunComparison) -> Self {
+        let with_verdict = |verdict| {
+            c.tests
+                .iter()
+                .filter(|t| t.verdict == verdict)
+                .map(Into::into)
+                .collect()
+        };
+        Self {
+            environment: c.environment.clone(),
+            base_run: c.base_run.clone(),
+            head_run: c.head_run.clone(),
+            threshold_pct: c.threshold_pct,
+            regressions: with_verdict(wfb_storage::Verdict::Regressed),
+            improvements: with_verdict(wfb_storage::Verdict::Improved),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestLogsApi {
+    pub app: Option<String>,
+    pub db: Option<String>,
+    pub wrkr: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntryApi {
+    pub language: String,
+    pub framework: String,
+    pub test: String,
+    pub state: wfb_storage::AttemptState,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub error: Option<String>,
+    pub app_log_tail: Option<String>,
+}
+
+impl From<wfb_storage::JournalEntry> for JournalEntryApi {
+    fn from(e: wfb_storage::JournalEntry) -> Self {
+        Self {
+            language: e.language,
+            framework: e.benchmark,
+            test: e.test,
+            state: e.state,
+            started_at: e.started_at,
+            finished_at: e.finished_at,
+            error: e.error,
+            app_log_tail: e.app_log_tail,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPointApi {
+    pub run_id: String,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub language: String,
+    pub framework_version: String,
+    /// Set on the first run measured with a new framework version.
+    pub previous_framework_version: Option<String>,
+    pub rps: f64,
+    #[serde(serialize_with = "serialize_duration_as_nanos")]
+    pub latency99: Duration,
+    pub memory_usage: u64,
+    pub errors: u64,
+}
+
+impl From<wfb_storage::HistoryPoint> for HistoryPointApi {
+    fn from(p: wfb_storage::HistoryPoint) -> Self {
+        Self {
+            run_id: p.run_id,
+            created_at: p.created_at,
+            language: p.language,
+            framework_version: p.framework_version,
+            previous_framework_version: p.previous_framework_version,
+            rps: p.requests_per_sec,
+            latency99: Duration::from_micros(p.latency_p99),
+            memory_usage: p.memory_usage_bytes,
+            errors: p.total_errors,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameworkComparisonApi {
+    #[serde(flatten)]
+    pub result: RunResult,
+    pub raw: Vec<TestCaseRawApi>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestCaseRawApi {
@@ -158,8 +369,8 @@ pub struct TestCaseRawApi {
     pub req_per_sec_stdev_pct: f64,
 }
 
-impl From<wfb_storage::TestCaseRaw> for TestCaseRawApi {
-    fn from(r: wfb_storage::TestCaseRaw) -> Self {
+impl From<&wfb_storage::TestCaseRaw> for TestCaseRawApi {
+    fn from(r: &wfb_storage::TestCaseRaw) -> Self {
         Self {
             elapsed_secs: r.elapsed_secs,
             connections: r.connections,
@@ -176,7 +387,7 @@ fn from(r: wfb_storage::TestCaseRaw) -> Self {
             latency_p90: r.latency_p90,
             latency_p99: r.latency_p99,
             latency_stdev_pct: r.latency_stdev_pct,
-            errors: r.errors,
+            errors: r.errors.clone(),
             memory_usage_bytes: r.memory_usage_bytes,
             cpu_usage_percent: r.cpu_usage_percent,
             req_per_sec_avg: r.req_per_sec_avg,
@@ -186,3 +397,67 @@ fn from(r: wfb_storage::TestCaseRaw) -> Self {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyCurvePointApi {
+    pub target_rate: u64,
+    pub achieved_rps: f64,
+    pub latency_p50: u64,
+    pub latency_p90: u64,
+    pub latency_p99: u64,
+    pub latency_max: u64,
+    pub dropped_iterations: u64,
+    pub total_requests: u64,
+    pub total_errors: u64,
+    pub duration_secs: u64,
+}
+
+impl From<wfb_storage::LatencyCurvePoint> for LatencyCurvePointApi {
+    fn from(p: wfb_storage::LatencyCurvePoint) -> Self {
+        Self {
+            target_rate: p.target_rate,
+            achieved_rps: p.achieved_rps,
+            latency_p50: p.latency_p50,
+            latency_p90: p.latency_p90,
+            latency_p99: p.latency_p99,
+            latency_max: p.latency_max,
+            dropped_iterations: p.dropped_iterations,
+            total_requests: p.total_requests,
+            total_errors: p.total_errors,
+            duration_secs: p.duration_secs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyPercentilesApi {
+    pub total_count: u64,
+    pub points: Vec<LatencyPercentileApi>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyPercentileApi {
+    /// Percentile in `0..=100`; `100` is the maximum.
+    pub percentile: f64,
+    /// Latency in microseconds.
+    pub value: u64,
+}
+
+impl From<wfb_storage::LatencyPercentiles> for LatencyPercentilesApi {
+    fn from(h: wfb_storage::LatencyPercentiles) -> Self {
+        Self {
+            total_count: h.total_count,
+            points: h
+                .points
+                .into_iter()
+                .map(|p| LatencyPercentileApi {
+                    percentile: p.percentile,
+                    value: p.value,
+                })
+                .collect(),
+        }
+    }
+}
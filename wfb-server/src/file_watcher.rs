@@ -1,12 +1,66 @@
 use anyhow::Result;
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::Path;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::sync::mpsc;
+use tokio::time::{Instant, timeout_at};
 use tracing::{error, info, warn};
+use wfb_storage::SqliteStore;
+
+/// A batch is flushed once no event has arrived for this long...
+const DEBOUNCE_QUIET: Duration = Duration::from_millis(500);
+/// ...or when it has been collecting for this long, whichever comes first.
+const DEBOUNCE_MAX_WAIT: Duration = Duration::from_secs(5);
+
+/// Run journal file name in the filesystem store (`<run>/<env>/journal.jsonl`).
+const JOURNAL_FILE: &str = "journal.jsonl";
 
 pub enum FileChangeEvent {
     ConfigChanged,
-    DataChanged,
+    /// Path relative to the data directory.
+    DataChanged(PathBuf),
+}
+
+/// File changes collected over one debounce window.
+#[derive(Default)]
+pub struct FileChangeBatch {
+    pub config_changed: bool,
+    pub data_paths: BTreeSet<PathBuf>,
+    /// Run journals are appended on every test attempt, so they only refresh the journal
+    /// cache instead of reloading results.
+    pub journal_paths: BTreeSet<PathBuf>,
+}
+
+impl FileChangeBatch {
+    fn push(&mut self, event: FileChangeEvent) {
+        match event {
+            FileChangeEvent::ConfigChanged => self.config_changed = true,
+            FileChangeEvent::DataChanged(path) => {
+                if path.file_name() == Some(JOURNAL_FILE.as_ref()) {
+                    self.journal_paths.insert(path);
+                } else {
+                    self.data_paths.insert(path);
+                }
+            }
+        }
+    }
+}
+
+/// Waits for the next change, then keeps collecting until events go quiet.
+/// Returns `None` once the watcher has shut down.
+pub async fn next_batch(rx: &mut mpsc::Receiver<FileChangeEvent>) -> Option<FileChangeBatch> {
+    let mut batch = FileChangeBatch::default();
+    batch.push(rx.recv().await?);
+
+    let deadline = Instant::now() + DEBOUNCE_MAX_WAIT;
+    loop {
+        let quiet_until = (Instant::now() + DEBOUNCE_QUIET).min(deadline);
+        match timeout_at(quiet_until, rx.recv()).await {
+            Ok(Some(event)) => batch.push(event),
+            Ok(None) | Err(_) => return Some(batch),
+        }
+    }
 }
 
 pub struct FileWatcherService {
@@ -38,8 +92,15 @@ pub fn new<P1, P2>(
 
                         let change_event = if path.starts_with(&config_path) {
                             Some(FileChangeEvent::ConfigChanged)
-                        } else if path.starts_with(&data_path) {
-                            Some(FileChangeEvent::DataChanged)
+                        } else if let Ok(relative) = path.strip_prefix(&data_path) {
+                            // Writes to the SQLite database come in throughout a run; the
+                            // store is reloaded when the runner marks the run finished.
+                            let is_database = relative
+                                .file_name()
+                                .and_then(|name| name.to_str())
+                                .is_some_and(SqliteStore::is_database_file);
+                            (!is_database)
+                                .then(|| FileChangeEvent::DataChanged(relative.to_path_buf()))
                         } else {
                             None
                         };
@@ -1,7 +1,7 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json, Response},
 };
 use std::sync::Arc;
 use std::time::Duration;
@@ -86,6 +86,28 @@ pub async fn get_frameworks(State(state): State<Arc<AppState>>) -> Json<Vec<Fram
     Json(frameworks)
 }
 
+/// One framework's results for `?env=&test=` across all runs, oldest first, with framework
+/// version changes marked.
+pub async fn get_framework_history(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiFrameworkHistoryPath,
+    Query(query_params): Query<HistoryParams>,
+) -> Result<Json<Vec<HistoryPointApi>>, StatusCode> {
+    let data = state.storage.data_read();
+    let runs_manifests = state.storage.runs();
+    // A name shared by benchmarks in several languages needs `?lang=`.
+    let history = wfb_storage::benchmark_history(
+        &data,
+        &runs_manifests,
+        &query_params.env,
+        query_params.lang.as_deref(),
+        &params.name,
+        &query_params.test,
+    )
+    .map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(Json(history.into_iter().map(Into::into).collect()))
+}
+
 pub async fn get_benchmarks(State(state): State<Arc<AppState>>) -> Json<Vec<BenchmarkInfo>> {
     let config = state.config_read();
     let benchmarks = config
@@ -107,6 +129,7 @@ pub async fn get_benchmarks(State(state): State<Arc<AppState>>) -> Json<Vec<Benc
             disabled: b.disabled,
             only: b.only,
             arguments: b.arguments.clone(),
+            build_args: b.build_args.clone(),
             env: b.env.clone(),
         })
         .collect();
@@ -115,10 +138,61 @@ pub async fn get_benchmarks(State(state): State<Arc<AppState>>) -> Json<Vec<Benc
 
 pub async fn get_runs(State(state): State<Arc<AppState>>) -> Json<Vec<RunSummary>> {
     let data = state.storage.data_read();
-    let runs_manifests = state.storage.runs_read();
+    let runs_manifests = state.storage.runs();
     Json(common::get_all_runs(&data, &runs_manifests))
 }
 
+/// Tests of a run that regressed or improved against the previous run of each environment
+/// (optionally only `?env=`). Latency values are in microseconds.
+pub async fn get_run_regressions(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiRunRegressionsPath,
+    Query(query_params): Query<RegressionsParams>,
+) -> Result<Json<Vec<RunRegressionsApi>>, StatusCode> {
+    if !state.storage.data_read().contains_key(&params.run_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let regressions = state.regressions_read();
+    let mut results: Vec<RunRegressionsApi> = regressions
+        .get(&params.run_id)
+        .into_iter()
+        .flat_map(|envs| envs.values())
+        .filter(|c| {
+            query_params
+                .env
+                .as_deref()
+                .is_none_or(|env| env == c.environment)
+        })
+        .map(Into::into)
+        .collect();
+    results.sort_by(|a, b| a.environment.cmp(&b.environment));
+
+    Ok(Json(results))
+}
+
+/// Test attempts recorded by the runner for a run and environment: the latest attempt of
+/// every test, or the whole journal with `?all=true`. `?state=failed` lists the failures.
+pub async fn get_run_journal(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiRunJournalPath,
+    Query(query_params): Query<JournalParams>,
+) -> Json<Vec<JournalEntryApi>> {
+    let journal = state.journal(&params.run_id, &params.env);
+    let entries = if query_params.all {
+        journal.as_ref().clone()
+    } else {
+        wfb_storage::latest_attempts(&journal)
+    };
+    Json(
+        entries
+            .into_iter()
+            .filter(|e| query_params.state.is_none_or(|state| state == e.state))
+            .map(Into::into)
+            .collect(),
+    )
+}
+
 pub async fn get_run_results(
     State(state): State<Arc<AppState>>,
     params: routes::ApiRunResultsPath,
@@ -130,45 +204,13 @@ pub async fn get_run_results(
         for (lang, lang_data) in env_data {
             for (bench_name, bench_result) in lang_data {
                 if let Some(test_summary) = bench_result.test_cases.get(&test) {
-                    results.push(RunResult {
-                        name: bench_name.clone(),
-                        language: lang.clone(),
-                        language_version: bench_result.manifest.language_version.clone(),
-                        framework: bench_name.clone(),
-                        framework_version: bench_result.manifest.framework_version.clone(),
-                        database: bench_result
-                            .manifest
-                            .database
-                            .as_ref()
-                            .map(|d| format!("{:?}", d).to_lowercase()),
-                        path: Some(bench_result.manifest.path.clone()),
-                        rps: test_summary.requests_per_sec,
-                        tps: test_summary.bytes_per_sec,
-                        latency_avg: Duration::from_secs_f64(
-                            test_summary.latency_mean / 1_000_000.0,
-                        ),
-                        latency_stdev: Duration::from_secs_f64(
-                            test_summary.latency_stdev / 1_000_000.0,
-                        ),
-                        latency_max: Duration::from_micros(test_summary.latency_max),
-                        latency50: Duration::from_micros(test_summary.latency_p50),
-                        latency75: Duration::from_micros(test_summary.latency_p75),
-                        latency90: Duration::from_micros(test_summary.latency_p90),
-                        latency99: Duration::from_micros(test_summary.latency_p99),
-                        latency_stdev_pct: test_summary.latency_stdev_pct,
-                        latency_distribution: test_summary
-                            .latency_distribution
-                            .iter()
-                            .map(|(p, l)| (*p, Duration::from_micros(*l)))
-                            .collect(),
-                        req_per_sec_avg: test_summary.req_per_sec_avg,
-                        req_per_sec_stdev: test_summary.req_per_sec_stdev,
-                        req_per_sec_max: test_summary.req_per_sec_max,
-                        req_per_sec_stdev_pct: test_summary.req_per_sec_stdev_pct,
-                        errors: test_summary.total_errors,
-                        memory_usage: test_summary.memory_usage_bytes,
-                        tags: bench_result.manifest.tags.clone(),
-                    });
+                    results.push(run_result(
+                        lang,
+                        bench_name,
+                        &test,
+                        bench_result,
+                        test_summary,
+                    ));
                 }
             }
         }
@@ -176,6 +218,95 @@ pub async fn get_run_results(
     Json(results)
 }
 
+fn run_result(
+    lang: &str,
+    bench_name: &str,
+    test: &str,
+    bench_result: &wfb_storage::BenchmarkResult,
+    test_summary: &wfb_storage::TestCaseSummary,
+) -> RunResult {
+    RunResult {
+        name: bench_name.to_string(),
+        language: lang.to_string(),
+        language_version: bench_result.manifest.language_version.clone(),
+        framework: bench_name.to_string(),
+        framework_version: bench_result.manifest.framework_version.clone(),
+        database: bench_result
+            .manifest
+            .database
+            .as_ref()
+            .map(|d| format!("{:?}", d).to_lowercase()),
+        host: bench_result.manifest.hosts.get(test).cloned(),
+        path: Some(bench_result.manifest.path.clone()),
+        rps: test_summary.requests_per_sec,
+        rps_at_slo: test_summary.slo.as_ref().map(|slo| slo.requests_per_sec),
+        latency99_slo: test_summary
+            .slo
+            .as_ref()
+            .map(|slo| Duration::from_micros(slo.latency_p99_target)),
+        tps: test_summary.bytes_per_sec,
+        latency_avg: Duration::from_secs_f64(test_summary.latency_mean / 1_000_000.0),
+        latency_stdev: Duration::from_secs_f64(test_summary.latency_stdev / 1_000_000.0),
+        latency_max: Duration::from_micros(test_summary.latency_max),
+        latency50: Duration::from_micros(test_summary.latency_p50),
+        latency75: Duration::from_micros(test_summary.latency_p75),
+        latency90: Duration::from_micros(test_summary.latency_p90),
+        latency99: Duration::from_micros(test_summary.latency_p99),
+        latency_stdev_pct: test_summary.latency_stdev_pct,
+        latency_distribution: test_summary
+            .latency_distribution
+            .iter()
+            .map(|(p, l)| (*p, Duration::from_micros(*l)))
+            .collect(),
+        req_per_sec_avg: test_summary.req_per_sec_avg,
+        req_per_sec_stdev: test_summary.req_per_sec_stdev,
+        req_per_sec_max: test_summary.req_per_sec_max,
+        req_per_sec_stdev_pct: test_summary.req_per_sec_stdev_pct,
+        errors: test_summary.total_errors,
+        memory_usage: test_summary.memory_usage_bytes,
+        tags: bench_result.manifest.tags.clone(),
+    }
+}
+
+/// Summaries and raw time series of the frameworks listed in `?f=`, in the requested order.
+/// Frameworks without results for the test are left out.
+pub async fn get_run_comparison(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiRunComparePath,
+    Query(query_params): Query<CompareParams>,
+) -> Result<Json<Vec<FrameworkComparisonApi>>, StatusCode> {
+    let routes::ApiRunComparePath { run_id, env, test } = params;
+    let frameworks = common::parse_framework_list(query_params.f.as_deref());
+    if frameworks.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let data = state.storage.data_read();
+    let mut results = Vec::new();
+    for framework in &frameworks {
+        let Some((lang, bench_result)) =
+            common::find_framework_result(&data, &run_id, &env, &test, framework)
+        else {
+            continue;
+        };
+        let Some(test_summary) = bench_result.test_cases.get(&test) else {
+            continue;
+        };
+
+        let raw = state
+            .storage
+            .get_raw_data(&run_id, &env, lang, framework, &test)
+            .unwrap_or_default();
+
+        results.push(FrameworkComparisonApi {
+            result: run_result(lang, framework, &test, bench_result, test_summary),
+            raw: raw.iter().map(Into::into).collect(),
+        });
+    }
+
+    Ok(Json(results))
+}
+
 pub async fn get_run_raw_data(
     State(state): State<Arc<AppState>>,
     params: routes::ApiRunRawPath,
@@ -187,29 +318,130 @@ pub async fn get_run_raw_data(
         test,
         framework,
     } = params;
-    let lang = if let Some(l) = query_params.lang {
-        l
-    } else {
-        // Try to find language
-        let data = state.storage.data_read();
-        let mut found_lang = None;
-        if let Some(env_data) = data.get(&run_id).and_then(|run_data| run_data.get(&env)) {
-            for (l, lang_data) in env_data {
-                if lang_data.contains_key(&framework) {
-                    found_lang = Some(l.clone());
-                    break;
-                }
-            }
-        }
-        found_lang.ok_or(StatusCode::NOT_FOUND)?
-    };
+    let lang = resolve_lang(&state, &run_id, &env, &framework, &test, query_params.lang)?;
 
     let raw_data = state
         .storage
         .get_raw_data(&run_id, &env, &lang, &framework, &test)
         .unwrap_or_default();
 
-    let api_data: Vec<TestCaseRawApi> = raw_data.into_iter().map(Into::into).collect();
+    let api_data: Vec<TestCaseRawApi> = raw_data.iter().map(Into::into).collect();
 
     Ok(Json(api_data))
 }
+
+/// Latency percentile summary of a test, as reported by wrkr at the end of the run.
+pub async fn get_run_latency_percentiles(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiRunPercentilesPath,
+    Query(query_params): Query<TranscriptParams>,
+) -> Result<Json<LatencyPercentilesApi>, StatusCode> {
+    let routes::ApiRunPercentilesPath {
+        run_id,
+        env,
+        test,
+        framework,
+    } = params;
+    let lang = resolve_lang(&state, &run_id, &env, &framework, &test, query_params.lang)?;
+
+    let percentiles = state
+        .storage
+        .get_latency_percentiles(&run_id, &env, &lang, &framework, &test)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(percentiles.into()))
+}
+
+/// Latency-vs-throughput curve recorded by a constant-arrival-rate sweep; empty when the
+/// test was not swept.
+pub async fn get_run_latency_curve(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiRunCurvePath,
+    Query(query_params): Query<TranscriptParams>,
+) -> Result<Json<Vec<LatencyCurvePointApi>>, StatusCode> {
+    let routes::ApiRunCurvePath {
+        run_id,
+        env,
+        test,
+        framework,
+    } = params;
+    let lang = resolve_lang(&state, &run_id, &env, &framework, &test, query_params.lang)?;
+
+    let points = state
+        .storage
+        .get_latency_curve(&run_id, &env, &lang, &framework, &test)
+        .map(|curve| curve.points)
+        .unwrap_or_default();
+
+    Ok(Json(points.into_iter().map(Into::into).collect()))
+}
+
+/// Container logs saved by the runner for a test: app and db output and wrkr's stderr.
+pub async fn get_run_logs(
+    State(state): State<Arc<AppState>>,
+    params: routes::ApiRunLogsPath,
+    Query(query_params): Query<LogsParams>,
+) -> Result<Response, StatusCode> {
+    let routes::ApiRunLogsPath {
+        run_id,
+        env,
+        test,
+        framework,
+    } = params;
+    let lang = resolve_lang(&state, &run_id, &env, &framework, &test, query_params.lang)?;
+    let get_log = |kind| {
+        state
+            .storage
+            .get_test_log(&run_id, &env, &lang, &framework, &test, kind)
+    };
+
+    if let Some(kind) = query_params.kind {
+        let kind: wfb_storage::LogKind = kind.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+        let log = get_log(kind).ok_or(StatusCode::NOT_FOUND)?;
+        return Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], log).into_response());
+    }
+
+    let logs = TestLogsApi {
+        app: get_log(wfb_storage::LogKind::App),
+        db: get_log(wfb_storage::LogKind::Db),
+        wrkr: get_log(wfb_storage::LogKind::Wrkr),
+    };
+    if logs.app.is_none() && logs.db.is_none() && logs.wrkr.is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(logs).into_response())
+}
+
+/// Uses the `lang` query parameter, or looks up the language whose `framework` recorded
+/// `test` in the run; like history, more than one such language is a bad request.
+fn resolve_lang(
+    state: &AppState,
+    run_id: &str,
+    env: &str,
+    framework: &str,
+    test: &str,
+    lang: Option<String>,
+) -> Result<String, StatusCode> {
+    if let Some(l) = lang {
+        return Ok(l);
+    }
+
+    let data = state.storage.data_read();
+    let mut languages = data
+        .get(run_id)
+        .and_then(|run_data| run_data.get(env))
+        .into_iter()
+        .flatten()
+        .filter(|(_, lang_data)| {
+            lang_data
+                .get(framework)
+                .is_some_and(|result| result.test_cases.contains_key(test))
+        })
+        .map(|(l, _)| l.clone());
+
+    match (languages.next(), languages.next()) {
+        (Some(l), None) => Ok(l),
+        (Some(_), Some(_)) => Err(StatusCode::BAD_REQUEST),
+        (None, _) => Err(StatusCode::NOT_FOUND),
+    }
+}
@@ -87,3 +87,34 @@ pub fn get_run_environments(
         })
         .collect()
 }
+
+/// Parses a comma-separated framework list (`?f=axum,actix-web`), keeping the first
+/// occurrence of each name in order.
+pub fn parse_framework_list(list: Option<&str>) -> Vec<String> {
+    let mut frameworks: Vec<String> = Vec::new();
+    for name in list.unwrap_or_default().split(',').map(str::trim) {
+        if !name.is_empty() && !frameworks.iter().any(|f| f == name) {
+            frameworks.push(name.to_string());
+        }
+    }
+    frameworks
+}
+
+/// Finds a framework's language and result for a test within one run environment.
+pub fn find_framework_result<'a>(
+    data: &'a StorageData,
+    run_id: &str,
+    env: &str,
+    test: &str,
+    framework: &str,
+) -> Option<(&'a String, &'a wfb_storage::BenchmarkResult)> {
+    data.get(run_id)?
+        .get(env)?
+        .iter()
+        .find_map(|(lang, lang_data)| {
+            lang_data
+                .get(framework)
+                .filter(|bench_result| bench_result.test_cases.contains_key(test))
+                .map(|bench_result| (lang, bench_result))
+        })
+}
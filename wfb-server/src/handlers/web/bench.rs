@@ -41,7 +41,7 @@ async fn bench_render(
     let render_started = Instant::now();
     let github_stars = github_stars_value_string().await;
     let data = state.storage.data_read();
-    let runs_manifests = state.storage.runs_read();
+    let runs_manifests = state.storage.runs();
     let config = state.config_read();
 
     let selection_query = super::types::IndexQuery {
@@ -92,6 +92,30 @@ async fn bench_render(
                 .collect();
             tags.sort_by(|a, b| a.0.cmp(&b.0));
 
+            let logs_url = Routes.run_logs_api_url(
+                &selection.active_run_id,
+                &selection.active_env,
+                &selection.active_test,
+                bench_name,
+            );
+            let logs = wfb_storage::LogKind::ALL
+                .into_iter()
+                .filter(|kind| {
+                    state
+                        .storage
+                        .get_test_log(
+                            &selection.active_run_id,
+                            &selection.active_env,
+                            lang,
+                            bench_name,
+                            &selection.active_test,
+                            *kind,
+                        )
+                        .is_some()
+                })
+                .map(|kind| (kind.to_string(), format!("{logs_url}?kind={kind}")))
+                .collect();
+
             bench_detail = Some(BenchDetailView {
                 run_id: selection.active_run_id.clone(),
                 env: selection.active_env.clone(),
@@ -101,6 +125,7 @@ async fn bench_render(
                 framework_version: manifest.framework_version.clone(),
                 language_version: manifest.language_version.clone(),
                 database,
+                host: manifest.hosts.get(&selection.active_test).cloned(),
                 repo_url: benchmark_repo_url(&manifest.path),
                 path: manifest.path.clone(),
                 tags,
@@ -108,6 +133,7 @@ async fn bench_render(
                 tps: test_summary.bytes_per_sec,
                 latency_p99: test_summary.latency_p99,
                 errors: test_summary.total_errors,
+                logs,
             });
         }
     }
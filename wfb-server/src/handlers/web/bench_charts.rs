@@ -1,9 +1,10 @@
 use super::render::HtmlTemplate;
 use askama::Template;
 use axum::extract::Extension;
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::response::IntoResponse;
-use serde::Serialize;
+use axum_extra::routing::TypedPath;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::middleware::CspNonce;
@@ -57,6 +58,266 @@ pub async fn bench_charts_partials_path_handler(
     })
 }
 
+/// Percentiles plotted on the latency spectrum chart.
+const SPECTRUM_PERCENTILES: [f64; 7] = [50.0, 75.0, 90.0, 99.0, 99.9, 99.99, 99.999];
+
+/// The spectrum only adds to the summary cards past p99, so it is hidden without these.
+const SPECTRUM_TAIL_FROM: f64 = 99.9;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySpectrumSeries {
+    pub label: String,
+    /// Latency in milliseconds for each entry of `percentiles`.
+    pub latency_ms: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySpectrumClientData {
+    pub percentiles: Vec<f64>,
+    pub series: Vec<LatencySpectrumSeries>,
+}
+
+/// A framework that can be overlaid on the spectrum chart.
+pub struct LatencySpectrumOption {
+    pub label: String,
+    pub selected: bool,
+    pub toggle_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LatencySpectrumQuery {
+    /// Comma-separated frameworks to overlay on the current one.
+    pub compare: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "partials/bench/latency-spectrum.rs.j2")]
+struct BenchLatencySpectrumPartialTemplate {
+    chart_data: LatencySpectrumClientData,
+    options: Vec<LatencySpectrumOption>,
+    has_tail: bool,
+    csp_nonce: String,
+}
+
+pub async fn bench_latency_spectrum_partials_path_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(CspNonce(csp_nonce)): Extension<CspNonce>,
+    params: routes::BenchLatencySpectrumPartialsViewPath,
+    Query(query): Query<LatencySpectrumQuery>,
+) -> impl IntoResponse {
+    let compare: Vec<String> = query
+        .compare
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|f| !f.is_empty() && *f != params.framework)
+        .map(str::to_string)
+        .collect();
+
+    let candidates = frameworks_for_test(&state, &params.run, &params.env, &params.test);
+
+    let mut series = Vec::new();
+    let mut options = Vec::new();
+    let mut has_tail = false;
+    for (lang, framework) in &candidates {
+        let is_primary = *framework == params.framework;
+        let selected = is_primary || compare.contains(framework);
+
+        if selected
+            && let Some(percentiles) = state.storage.get_latency_percentiles(
+                &params.run,
+                &params.env,
+                lang,
+                framework,
+                &params.test,
+            )
+        {
+            let entry = LatencySpectrumSeries {
+                label: format!("{lang} / {framework}"),
+                latency_ms: spectrum_latencies_ms(&percentiles),
+            };
+            if is_primary {
+                // Keyed on the current framework so toggling overlays never hides the chart.
+                has_tail = SPECTRUM_PERCENTILES
+                    .iter()
+                    .zip(&entry.latency_ms)
+                    .any(|(p, latency)| *p >= SPECTRUM_TAIL_FROM && latency.is_some());
+                series.insert(0, entry);
+            } else {
+                series.push(entry);
+            }
+        }
+
+        if !is_primary {
+            let toggled: Vec<&str> = if selected {
+                compare
+                    .iter()
+                    .filter(|f| *f != framework)
+                    .map(String::as_str)
+                    .collect()
+            } else {
+                compare
+                    .iter()
+                    .map(String::as_str)
+                    .chain([framework.as_str()])
+                    .collect()
+            };
+            options.push(LatencySpectrumOption {
+                label: framework.clone(),
+                selected,
+                toggle_url: spectrum_url(&params, &toggled),
+            });
+        }
+    }
+
+    HtmlTemplate(BenchLatencySpectrumPartialTemplate {
+        chart_data: LatencySpectrumClientData {
+            percentiles: SPECTRUM_PERCENTILES.to_vec(),
+            series,
+        },
+        options,
+        has_tail,
+        csp_nonce,
+    })
+}
+
+fn spectrum_latencies_ms(percentiles: &wfb_storage::LatencyPercentiles) -> Vec<Option<f64>> {
+    SPECTRUM_PERCENTILES
+        .iter()
+        .map(|p| percentiles.value_at(*p).map(to_ms))
+        .collect()
+}
+
+fn spectrum_url(params: &routes::BenchLatencySpectrumPartialsViewPath, compare: &[&str]) -> String {
+    let base = params.to_uri().to_string();
+    if compare.is_empty() {
+        base
+    } else {
+        let compare: Vec<_> = compare
+            .iter()
+            .map(|name| urlencoding::encode(name))
+            .collect();
+        format!("{base}?compare={}", compare.join(","))
+    }
+}
+
+/// One metric of several series on a shared x axis; `values[i]` holds the series of
+/// `labels[i]`, with gaps where that series has no sample.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlayChartClientData {
+    /// Elapsed seconds, or point indexes when `x_labels` is set.
+    pub x: Vec<f64>,
+    /// Category labels for each `x` (e.g. run ids); empty for time series.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub x_labels: Vec<String>,
+    pub labels: Vec<String>,
+    pub values: Vec<Vec<Option<f64>>>,
+    /// `rps`, `ms` or `mb`; selects the client-side formatting.
+    pub unit: &'static str,
+}
+
+/// Builds RPS, p99 and memory overlays for the given `(label, raw samples)` series.
+pub(super) fn build_overlay_chart_data(
+    series: &[(String, Arc<Vec<wfb_storage::TestCaseRaw>>)],
+    max_points: usize,
+) -> [OverlayChartClientData; 3] {
+    let charts: Vec<BenchChartClientData> = series
+        .iter()
+        .map(|(_, raw)| build_chart_data(raw, max_points))
+        .collect();
+
+    let mut x: Vec<f64> = charts.iter().flat_map(|c| c.x.iter().copied()).collect();
+    x.sort_by(f64::total_cmp);
+    x.dedup();
+
+    let labels: Vec<String> = series.iter().map(|(label, _)| label.clone()).collect();
+    let align = |pick: fn(&BenchChartClientData) -> &Vec<f64>| -> Vec<Vec<Option<f64>>> {
+        charts
+            .iter()
+            .map(|c| {
+                let values = pick(c);
+                let mut aligned = vec![None; x.len()];
+                for (t, v) in c.x.iter().zip(values) {
+                    if let Ok(i) = x.binary_search_by(|probe| probe.total_cmp(t)) {
+                        aligned[i] = Some(*v);
+                    }
+                }
+                aligned
+            })
+            .collect()
+    };
+
+    [
+        OverlayChartClientData {
+            x: x.clone(),
+            x_labels: Vec::new(),
+            labels: labels.clone(),
+            values: align(|c| &c.rps),
+            unit: "rps",
+        },
+        OverlayChartClientData {
+            x: x.clone(),
+            x_labels: Vec::new(),
+            labels: labels.clone(),
+            values: align(|c| &c.p99_ms),
+            unit: "ms",
+        },
+        OverlayChartClientData {
+            x: x.clone(),
+            x_labels: Vec::new(),
+            labels,
+            values: align(|c| &c.mem_mb),
+            unit: "mb",
+        },
+    ]
+}
+
+/// Builds RPS, p99 and memory charts over runs; runs that changed the framework version
+/// are labelled with the new version.
+pub(super) fn build_history_chart_data(
+    label: &str,
+    points: &[wfb_storage::HistoryPoint],
+) -> [OverlayChartClientData; 3] {
+    let x: Vec<f64> = (0..points.len()).map(|i| i as f64).collect();
+    let x_labels: Vec<String> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            if i == 0 || p.previous_framework_version.is_some() {
+                format!("{} (v{})", p.run_id, p.framework_version)
+            } else {
+                p.run_id.clone()
+            }
+        })
+        .collect();
+    let chart = |values: Vec<Option<f64>>, unit| OverlayChartClientData {
+        x: x.clone(),
+        x_labels: x_labels.clone(),
+        labels: vec![label.to_string()],
+        values: vec![values],
+        unit,
+    };
+
+    [
+        chart(
+            points.iter().map(|p| Some(p.requests_per_sec)).collect(),
+            "rps",
+        ),
+        chart(
+            points.iter().map(|p| Some(to_ms(p.latency_p99))).collect(),
+            "ms",
+        ),
+        chart(
+            points
+                .iter()
+                .map(|p| Some(to_mb(p.memory_usage_bytes)))
+                .collect(),
+            "mb",
+        ),
+    ]
+}
+
 fn to_ms(nanos: u64) -> f64 {
     // Raw latency values are microseconds.
     // Convert to milliseconds.
@@ -128,3 +389,27 @@ fn find_language_for_framework(
 
     None
 }
+
+fn frameworks_for_test(
+    state: &AppState,
+    run: &str,
+    env: &str,
+    test: &str,
+) -> Vec<(String, String)> {
+    let data = state.storage.data_read();
+    let Some(env_data) = data.get(run).and_then(|run_data| run_data.get(env)) else {
+        return Vec::new();
+    };
+
+    let mut frameworks: Vec<(String, String)> = env_data
+        .iter()
+        .flat_map(|(lang, lang_data)| {
+            lang_data
+                .iter()
+                .filter(|(_, bench_result)| bench_result.test_cases.contains_key(test))
+                .map(move |(framework, _)| (lang.clone(), framework.clone()))
+        })
+        .collect();
+    frameworks.sort_by(|a, b| a.1.cmp(&b.1));
+    frameworks
+}
@@ -0,0 +1,184 @@
+use super::bench_charts::build_overlay_chart_data;
+use super::context::select_common;
+use super::github::github_stars_value_string;
+use super::render::HtmlTemplate;
+use super::types::{
+    ChartView, ChromeContext, CompareOptionView, CompareQuery, CompareRowView, CompareView, Routes,
+    SelectionContext,
+};
+use askama::Template;
+use axum::extract::{Extension, Query, State};
+use axum::response::{IntoResponse, Redirect};
+use axum_extra::routing::TypedPath;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::handlers::common;
+use crate::handlers::web::context::chrome_context;
+use crate::middleware::CspNonce;
+use crate::routes;
+use crate::state::AppState;
+
+#[allow(unused_imports)]
+use crate::filters;
+
+#[derive(Template)]
+#[template(path = "pages/compare.rs.j2")]
+struct CompareTemplate {
+    chrome: ChromeContext,
+    selection: SelectionContext,
+    compare: CompareView,
+    csp_nonce: String,
+    routes: Routes,
+}
+
+pub async fn compare_path_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(CspNonce(csp_nonce)): Extension<CspNonce>,
+    params: routes::CompareViewPath,
+    Query(query): Query<CompareQuery>,
+) -> impl IntoResponse {
+    let page_path = params.to_uri().to_string();
+    let render_started = Instant::now();
+    let github_stars = github_stars_value_string().await;
+    let data = state.storage.data_read();
+    let runs_manifests = state.storage.runs();
+    let config = state.config_read();
+
+    let selection_query = super::types::IndexQuery {
+        run: Some(params.run.clone()),
+        env: Some(params.env.clone()),
+        test: Some(params.test.clone()),
+    };
+    let selection = select_common(&data, &runs_manifests, &config, &selection_query);
+
+    if selection.runs.is_empty() {
+        return Redirect::to(routes::IndexRoot::PATH).into_response();
+    }
+
+    let (run, env, test) = (
+        &selection.active_run_id,
+        &selection.active_env,
+        &selection.active_test,
+    );
+    let selected = common::parse_framework_list(query.f.as_deref());
+    let language_color = |lang: &str| {
+        config
+            .get_lang(lang)
+            .map(|lang| lang.color.clone())
+            .unwrap_or_else(|| "#94a3b8".to_string())
+    };
+
+    let mut rows = Vec::new();
+    let mut series = Vec::new();
+    for framework in &selected {
+        let Some((lang, bench_result)) =
+            common::find_framework_result(&data, run, env, test, framework)
+        else {
+            continue;
+        };
+        let Some(test_summary) = bench_result.test_cases.get(test) else {
+            continue;
+        };
+
+        rows.push(CompareRowView {
+            framework: framework.clone(),
+            framework_version: bench_result.manifest.framework_version.clone(),
+            language: lang.clone(),
+            language_color: language_color(lang),
+            rps: test_summary.requests_per_sec,
+            tps: test_summary.bytes_per_sec,
+            latency_p50: test_summary.latency_p50,
+            latency_p99: test_summary.latency_p99,
+            slo: test_summary.slo.clone(),
+            errors: test_summary.total_errors,
+            memory_usage_bytes: test_summary.memory_usage_bytes,
+        });
+
+        let raw = state
+            .storage
+            .get_raw_data(run, env, lang, framework, test)
+            .unwrap_or_default();
+        series.push((format!("{lang} / {framework}"), raw));
+    }
+
+    let mut options: Vec<CompareOptionView> = data
+        .get(run)
+        .and_then(|run_data| run_data.get(env))
+        .into_iter()
+        .flat_map(|env_data| env_data.iter())
+        .flat_map(|(lang, lang_data)| {
+            lang_data
+                .iter()
+                .filter(|(_, bench_result)| bench_result.test_cases.contains_key(test))
+                .map(move |(framework, _)| (lang, framework))
+        })
+        .map(|(lang, framework)| {
+            let is_selected = selected.contains(framework);
+            let toggled: Vec<&str> = if is_selected {
+                selected
+                    .iter()
+                    .filter(|f| *f != framework)
+                    .map(String::as_str)
+                    .collect()
+            } else {
+                selected
+                    .iter()
+                    .map(String::as_str)
+                    .chain([framework.as_str()])
+                    .collect()
+            };
+            CompareOptionView {
+                framework: framework.clone(),
+                language_color: language_color(lang),
+                selected: is_selected,
+                url: compare_url(run, env, test, &toggled),
+            }
+        })
+        .collect();
+    options.sort_by(|a, b| a.framework.cmp(&b.framework));
+
+    let [rps, p99, mem] = build_overlay_chart_data(&series, 240);
+    let charts = if series.is_empty() {
+        Vec::new()
+    } else {
+        vec![
+            ChartView {
+                title: "Requests/sec",
+                data: rps,
+            },
+            ChartView {
+                title: "Latency P99",
+                data: p99,
+            },
+            ChartView {
+                title: "Memory",
+                data: mem,
+            },
+        ]
+    };
+
+    let compare = CompareView {
+        rows,
+        options,
+        charts,
+    };
+
+    HtmlTemplate(CompareTemplate {
+        chrome: chrome_context(render_started, false, github_stars, &page_path),
+        selection,
+        compare,
+        csp_nonce,
+        routes: Routes,
+    })
+    .into_response()
+}
+
+fn compare_url(run: &str, env: &str, test: &str, frameworks: &[&str]) -> String {
+    let base = Routes.compare_url(run, env, test);
+    if frameworks.is_empty() {
+        base
+    } else {
+        format!("{base}?f={}", frameworks.join(","))
+    }
+}
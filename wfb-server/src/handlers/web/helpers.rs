@@ -1,10 +1,11 @@
 use crate::handlers::common;
+use crate::state::AppState;
 use crate::view_models::{EnvironmentView, RunView, TestView};
 use std::collections::HashMap;
 use std::sync::RwLockReadGuard;
 use wfb_storage::StorageData;
 
-use super::types::REPOSITORY_URL;
+use super::types::{ChangeBadge, FailureView, REPOSITORY_URL};
 
 pub fn select_important_table_tags(tags: &HashMap<String, String>) -> Vec<(String, String)> {
     let mut selected = Vec::new();
@@ -87,3 +88,58 @@ pub fn get_available_tests() -> Vec<TestView> {
         })
         .collect()
 }
+
+/// Badge for a test that regressed or improved against the previous run; `None` otherwise.
+pub fn change_badge(
+    comparison: Option<&wfb_storage::RunComparison>,
+    language: &str,
+    benchmark: &str,
+    test: &str,
+) -> Option<ChangeBadge> {
+    let comparison = comparison?;
+    let test_case = comparison.find(language, benchmark, test)?;
+    let regressed = match test_case.verdict {
+        wfb_storage::Verdict::Regressed => true,
+        wfb_storage::Verdict::Improved => false,
+        wfb_storage::Verdict::Unchanged | wfb_storage::Verdict::Noise => return None,
+    };
+
+    Some(ChangeBadge {
+        regressed,
+        base_run: test_case.base_run.clone(),
+        rps_change_pct: test_case.requests_per_sec.change_pct.unwrap_or(0.0),
+        latency_p99_change_pct: test_case.latency_p99.change_pct.unwrap_or(0.0),
+    })
+}
+
+/// Tests of `run`/`env` whose latest journal attempt of `test` failed.
+pub fn failed_attempts(
+    state: &AppState,
+    config: &wfb_storage::Config,
+    run: &str,
+    env: &str,
+    test: &str,
+) -> Vec<FailureView> {
+    let mut failures: Vec<FailureView> = wfb_storage::latest_attempts(&state.journal(run, env))
+        .into_iter()
+        .filter(|attempt| {
+            attempt.state == wfb_storage::AttemptState::Failed && attempt.test == test
+        })
+        .map(|attempt| FailureView {
+            language_color: config
+                .get_lang(&attempt.language)
+                .map(|lang| lang.color.clone())
+                .unwrap_or_else(|| "#94a3b8".to_string()),
+            framework: attempt.benchmark,
+            language: attempt.language,
+            error: attempt.error.unwrap_or_default(),
+            app_log_tail: attempt.app_log_tail,
+            finished_at: attempt
+                .finished_at
+                .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_default(),
+        })
+        .collect();
+    failures.sort_by(|a, b| a.framework.cmp(&b.framework));
+    failures
+}
@@ -0,0 +1,107 @@
+use super::bench_charts::build_history_chart_data;
+use super::context::select_common;
+use super::github::github_stars_value_string;
+use super::render::HtmlTemplate;
+use super::types::{ChartView, ChromeContext, HistoryView, Routes, SelectionContext};
+use askama::Template;
+use axum::extract::{Extension, State};
+use axum::response::{IntoResponse, Redirect};
+use axum_extra::routing::TypedPath;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::handlers::common;
+use crate::handlers::web::context::chrome_context;
+use crate::middleware::CspNonce;
+use crate::routes;
+use crate::state::AppState;
+
+#[allow(unused_imports)]
+use crate::filters;
+
+#[derive(Template)]
+#[template(path = "pages/history.rs.j2")]
+struct HistoryTemplate {
+    chrome: ChromeContext,
+    selection: SelectionContext,
+    history: Option<HistoryView>,
+    csp_nonce: String,
+    routes: Routes,
+}
+
+pub async fn bench_history_path_handler(
+    State(state): State<Arc<AppState>>,
+    Extension(CspNonce(csp_nonce)): Extension<CspNonce>,
+    params: routes::BenchHistoryViewPath,
+) -> impl IntoResponse {
+    let page_path = params.to_uri().to_string();
+    let render_started = Instant::now();
+    let github_stars = github_stars_value_string().await;
+    let data = state.storage.data_read();
+    let runs_manifests = state.storage.runs();
+    let config = state.config_read();
+
+    let selection_query = super::types::IndexQuery {
+        run: Some(params.run.clone()),
+        env: Some(params.env.clone()),
+        test: Some(params.test.clone()),
+    };
+    let selection = select_common(&data, &runs_manifests, &config, &selection_query);
+
+    if selection.runs.is_empty() {
+        return Redirect::to(routes::IndexRoot::PATH).into_response();
+    }
+
+    // The current run pins the language, so same-named benchmarks of other languages
+    // don't leak into the trend.
+    let history = common::find_framework_result(
+        &data,
+        &selection.active_run_id,
+        &selection.active_env,
+        &selection.active_test,
+        &params.framework,
+    )
+    .and_then(|(lang, _)| {
+        let mut points = wfb_storage::benchmark_history(
+            &data,
+            &runs_manifests,
+            &selection.active_env,
+            Some(lang),
+            &params.framework,
+            &selection.active_test,
+        )
+        .ok()?;
+        let [rps, p99, mem] =
+            build_history_chart_data(&format!("{lang} / {}", params.framework), &points);
+        points.reverse();
+
+        Some(HistoryView {
+            framework: params.framework.clone(),
+            language: lang.clone(),
+            points,
+            charts: vec![
+                ChartView {
+                    title: "Requests/sec",
+                    data: rps,
+                },
+                ChartView {
+                    title: "Latency P99",
+                    data: p99,
+                },
+                ChartView {
+                    title: "Memory",
+                    data: mem,
+                },
+            ],
+        })
+    });
+
+    HtmlTemplate(HistoryTemplate {
+        chrome: chrome_context(render_started, false, github_stars, &page_path),
+        selection,
+        history,
+        csp_nonce,
+        routes: Routes,
+    })
+    .into_response()
+}
@@ -1,8 +1,10 @@
 use super::context::{chrome_context, empty_selection_context, select_common};
 use super::github::github_stars_value_string;
-use super::helpers::select_important_table_tags;
+use super::helpers::{change_badge, failed_attempts, select_important_table_tags};
 use super::render::HtmlTemplate;
-use super::types::{BenchmarkView, ChromeContext, IndexQuery, Routes, SelectionContext};
+use super::types::{
+    BenchmarkView, ChromeContext, FailureView, IndexQuery, Routes, SelectionContext,
+};
 use askama::Template;
 use axum::extract::State;
 use axum::response::IntoResponse;
@@ -23,6 +25,7 @@ struct IndexTemplate {
     chrome: ChromeContext,
     selection: SelectionContext,
     benchmarks: Vec<BenchmarkView>,
+    failures: Vec<FailureView>,
     routes: Routes,
 }
 
@@ -34,7 +37,7 @@ async fn render_index(
     let render_started = Instant::now();
     let github_stars = github_stars_value_string().await;
     let data = state.storage.data_read();
-    let runs_manifests = state.storage.runs_read();
+    let runs_manifests = state.storage.runs();
     let config = state.config_read();
 
     let selection = select_common(&data, &runs_manifests, &config, &query);
@@ -44,11 +47,16 @@ async fn render_index(
             chrome: chrome_context(render_started, true, github_stars, page_path),
             selection: empty_selection_context(),
             benchmarks: vec![],
+            failures: vec![],
             routes: Routes,
         });
     }
 
     let run_data = data.get(&selection.active_run_id);
+    let regressions = state.regressions_read();
+    let comparison = regressions
+        .get(&selection.active_run_id)
+        .and_then(|envs| envs.get(&selection.active_env));
 
     let mut benchmarks = Vec::new();
     let mut max_rps = 0.0;
@@ -88,9 +96,11 @@ async fn render_index(
                         rps_percent: 0.0,
                         tps: test_summary.bytes_per_sec,
                         latency_p99: test_summary.latency_p99,
+                        slo: test_summary.slo.clone(),
                         errors: test_summary.total_errors,
                         database,
                         tags: select_important_table_tags(&manifest.tags),
+                        change: change_badge(comparison, lang, bench_name, &selection.active_test),
                     });
                 }
             }
@@ -110,10 +120,19 @@ async fn render_index(
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
+    let failures = failed_attempts(
+        &state,
+        &config,
+        &selection.active_run_id,
+        &selection.active_env,
+        &selection.active_test,
+    );
+
     HtmlTemplate(IndexTemplate {
         chrome: chrome_context(render_started, true, github_stars, page_path),
         selection,
         benchmarks,
+        failures,
         routes: Routes,
     })
 }
@@ -150,6 +169,7 @@ struct IndexUpdateTemplate {
     chrome: ChromeContext,
     selection: SelectionContext,
     benchmarks: Vec<BenchmarkView>,
+    failures: Vec<FailureView>,
     routes: Routes,
 }
 
@@ -161,7 +181,7 @@ async fn render_index_update(
     let render_started = Instant::now();
     let github_stars = github_stars_value_string().await;
     let data = state.storage.data_read();
-    let runs_manifests = state.storage.runs_read();
+    let runs_manifests = state.storage.runs();
     let config = state.config_read();
 
     let selection = select_common(&data, &runs_manifests, &config, &query);
@@ -170,11 +190,16 @@ async fn render_index_update(
             chrome: chrome_context(render_started, true, github_stars, page_path),
             selection: empty_selection_context(),
             benchmarks: vec![],
+            failures: vec![],
             routes: Routes,
         });
     }
 
     let run_data = data.get(&selection.active_run_id);
+    let regressions = state.regressions_read();
+    let comparison = regressions
+        .get(&selection.active_run_id)
+        .and_then(|envs| envs.get(&selection.active_env));
     let mut benchmarks = Vec::new();
     let mut max_rps = 0.0;
 
@@ -213,9 +238,11 @@ async fn render_index_update(
                         rps_percent: 0.0,
                         tps: test_summary.bytes_per_sec,
                         latency_p99: test_summary.latency_p99,
+                        slo: test_summary.slo.clone(),
                         errors: test_summary.total_errors,
                         database,
                         tags: select_important_table_tags(&manifest.tags),
+                        change: change_badge(comparison, lang, bench_name, &selection.active_test),
                     });
                 }
             }
@@ -234,10 +261,19 @@ async fn render_index_update(
             .unwrap_or(std::cmp::Ordering::Equal)
     });
 
+    let failures = failed_attempts(
+        &state,
+        &config,
+        &selection.active_run_id,
+        &selection.active_env,
+        &selection.active_test,
+    );
+
     HtmlTemplate(IndexUpdateTemplate {
         chrome: chrome_context(render_started, true, github_stars, page_path),
         selection,
         benchmarks,
+        failures,
         routes: Routes,
     })
 }
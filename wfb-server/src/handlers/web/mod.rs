@@ -3,9 +3,11 @@
 
 mod bench;
 mod bench_charts;
+mod compare;
 mod context;
 mod github;
 mod helpers;
+mod history;
 mod index;
 mod render;
 mod seo;
@@ -13,7 +15,10 @@
 
 pub use bench::bench_path_handler;
 pub use bench_charts::bench_charts_partials_path_handler;
+pub use bench_charts::bench_latency_spectrum_partials_path_handler;
+pub use compare::compare_path_handler;
 pub use github::github_stars_partials_handler;
+pub use history::bench_history_path_handler;
 pub use index::index_path_handler;
 pub use index::index_update_path_handler;
 pub use index::root_handler;
@@ -34,6 +34,8 @@ pub struct BenchDetailView {
     pub framework_version: String,
     pub language_version: String,
     pub database: Option<String>,
+    /// Host set the test ran on, for environments with a host pool.
+    pub host: Option<String>,
     pub repo_url: Option<String>,
     pub path: String,
     pub tags: Vec<(String, String)>,
@@ -41,6 +43,8 @@ pub struct BenchDetailView {
     pub tps: u64,
     pub latency_p99: u64,
     pub errors: u64,
+    /// Saved container logs of the test as `(kind, url)`.
+    pub logs: Vec<(String, String)>,
 }
 
 pub struct BenchmarkView {
@@ -52,9 +56,77 @@ pub struct BenchmarkView {
     pub rps_percent: f64,
     pub tps: u64,
     pub latency_p99: u64,
+    /// Max sustainable throughput within the p99 SLO; `None` for results recorded without it.
+    pub slo: Option<wfb_storage::SloThroughput>,
     pub errors: u64,
     pub database: Option<String>,
     pub tags: Vec<(String, String)>,
+    /// Regression or improvement against the previous run of the environment.
+    pub change: Option<ChangeBadge>,
+}
+
+pub struct ChangeBadge {
+    pub regressed: bool,
+    pub base_run: String,
+    pub rps_change_pct: f64,
+    pub latency_p99_change_pct: f64,
+}
+
+/// A test of the selected run whose latest attempt failed, so it has no result row.
+pub struct FailureView {
+    pub framework: String,
+    pub language: String,
+    pub language_color: String,
+    pub error: String,
+    pub app_log_tail: Option<String>,
+    pub finished_at: String,
+}
+
+pub struct CompareRowView {
+    pub framework: String,
+    pub framework_version: String,
+    pub language: String,
+    pub language_color: String,
+    pub rps: f64,
+    pub tps: u64,
+    pub latency_p50: u64,
+    pub latency_p99: u64,
+    pub slo: Option<wfb_storage::SloThroughput>,
+    pub errors: u64,
+    pub memory_usage_bytes: u64,
+}
+
+/// A framework that can be added to or removed from the comparison.
+pub struct CompareOptionView {
+    pub framework: String,
+    pub language_color: String,
+    pub selected: bool,
+    pub url: String,
+}
+
+pub struct ChartView {
+    pub title: &'static str,
+    pub data: super::bench_charts::OverlayChartClientData,
+}
+
+pub struct CompareView {
+    pub rows: Vec<CompareRowView>,
+    pub options: Vec<CompareOptionView>,
+    pub charts: Vec<ChartView>,
+}
+
+pub struct HistoryView {
+    pub framework: String,
+    pub language: String,
+    /// Newest run first.
+    pub points: Vec<wfb_storage::HistoryPoint>,
+    pub charts: Vec<ChartView>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct CompareQuery {
+    /// Comma-separated frameworks, e.g. `axum,actix-web,fiber`.
+    pub f: Option<String>,
 }
 
 #[derive(serde::Deserialize)]
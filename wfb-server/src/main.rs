@@ -5,10 +5,9 @@
 use clap::Parser;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
 use tracing::info;
 
-use wfb_storage::{Config, Storage};
+use wfb_storage::{Config, Storage, StorageBackend};
 
 mod api_models;
 mod assets_manifest;
@@ -21,7 +20,7 @@
 mod state;
 mod view_models;
 
-use file_watcher::{FileChangeEvent, FileWatcherService};
+use file_watcher::FileWatcherService;
 use routes::build_app;
 use state::AppState;
 
@@ -39,6 +38,14 @@ struct Args {
     /// Directory that contains static assets (CSS, JS, images)
     #[arg(long, env = "ASSETS_DIR")]
     assets_dir: Option<PathBuf>,
+
+    /// Result storage backend (fs, sqlite)
+    #[arg(long, env = "STORAGE_BACKEND", default_value_t = StorageBackend::Fs)]
+    storage: StorageBackend,
+
+    /// Change in RPS / p99 latency, in percent, between consecutive runs flagged as a regression
+    #[arg(long, env = "REGRESSION_THRESHOLD", default_value_t = 5.0)]
+    regression_threshold: f64,
 }
 
 #[tokio::main]
@@ -58,13 +65,14 @@ async fn main() -> anyhow::Result<()> {
     let data_path = std::path::PathBuf::from("./data");
     let config_path = std::path::PathBuf::from("./config");
 
-    let storage = Arc::new(Storage::new(&data_path)?);
+    let storage = Arc::new(Storage::open(args.storage, &data_path)?);
     let config = Arc::new(RwLock::new(Config::load(&config_path)?));
 
-    let state = Arc::new(AppState {
-        storage: storage.clone(),
-        config: config.clone(),
-    });
+    let state = Arc::new(AppState::new(
+        storage,
+        config.clone(),
+        args.regression_threshold,
+    ));
 
     let assets_dir = resolve_assets_dir(args.assets_dir.clone())?;
     info!("Serving assets from {}", assets_dir.display());
@@ -73,33 +81,39 @@ async fn main() -> anyhow::Result<()> {
     watcher.watch(&config_path)?;
     watcher.watch(&data_path)?;
 
-    let storage_clone = storage.clone();
+    let state_clone = state.clone();
     let config_clone = config.clone();
     let config_path_clone = config_path.clone();
     tokio::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            match event {
-                FileChangeEvent::ConfigChanged => {
-                    tracing::info!("Config changed, reloading...");
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-
-                    let mut config_guard =
-                        config_clone.write().unwrap_or_else(|err| err.into_inner());
-                    if let Err(e) = config_guard.reload(&config_path_clone) {
-                        tracing::error!("Failed to reload config: {}", e);
-                    } else {
-                        tracing::info!("Config reloaded successfully");
-                    }
+        while let Some(batch) = file_watcher::next_batch(&mut rx).await {
+            if batch.config_changed {
+                tracing::info!("Config changed, reloading...");
+
+                let mut config_guard = config_clone.write().unwrap_or_else(|err| err.into_inner());
+                if let Err(e) = config_guard.reload(&config_path_clone) {
+                    tracing::error!("Failed to reload config: {}", e);
+                } else {
+                    tracing::info!("Config reloaded successfully");
                 }
-                FileChangeEvent::DataChanged => {
-                    tracing::info!("Data changed, reloading...");
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-
-                    if let Err(e) = storage_clone.reload() {
-                        tracing::error!("Failed to reload data: {}", e);
-                    } else {
-                        tracing::info!("Data reloaded successfully");
-                    }
+            }
+
+            for path in &batch.journal_paths {
+                let mut parts = path.iter().map(|part| part.to_string_lossy());
+                if let (Some(run_id), Some(environment)) = (parts.next(), parts.next()) {
+                    state_clone.invalidate_journals(Some((&run_id, &environment)));
+                }
+            }
+
+            if !batch.data_paths.is_empty() {
+                let paths: Vec<PathBuf> = batch.data_paths.into_iter().collect();
+                tracing::info!("Data changed ({} paths), reloading...", paths.len());
+
+                if let Err(e) = state_clone.storage.reload_paths(&paths) {
+                    tracing::error!("Failed to reload data: {}", e);
+                } else {
+                    state_clone.refresh_regressions();
+                    state_clone.invalidate_journals(None);
+                    tracing::info!("Data reloaded successfully");
                 }
             }
         }
@@ -121,6 +121,26 @@ pub fn $tpl_name(&self, $( $field: &str ),* ) -> String {
         framework: String,
     } [tpl bench_charts_partials_url];
 
+    pub BenchLatencySpectrumPartialsViewPath => "/partials/runs/{run}/env/{env}/test/{test}/bench/{framework}/latency-spectrum" {
+        run: String,
+        env: String,
+        test: String,
+        framework: String,
+    } [tpl bench_latency_spectrum_partials_url];
+
+    pub BenchHistoryViewPath => "/runs/{run}/env/{env}/test/{test}/bench/{framework}/history" {
+        run: String,
+        env: String,
+        test: String,
+        framework: String,
+    } [tpl bench_history_url];
+
+    pub CompareViewPath => "/runs/{run}/env/{env}/test/{test}/compare" {
+        run: String,
+        env: String,
+        test: String,
+    } [tpl compare_url];
+
     // --- API routes ---
     pub ApiVersion => "/api/version";
     pub ApiTags => "/api/tags";
@@ -128,21 +148,62 @@ pub fn $tpl_name(&self, $( $field: &str ),* ) -> String {
     pub ApiTests => "/api/tests";
     pub ApiLanguages => "/api/languages";
     pub ApiFrameworks => "/api/frameworks";
+
+    pub ApiFrameworkHistoryPath => "/api/frameworks/{name}/history" {
+        name: String,
+    };
+
     pub ApiBenchmarks => "/api/benchmarks";
     pub ApiRuns => "/api/runs";
 
+    pub ApiRunRegressionsPath => "/api/runs/{run_id}/regressions" {
+        run_id: String,
+    };
+
+    pub ApiRunJournalPath => "/api/runs/{run_id}/environments/{env}/journal" {
+        run_id: String,
+        env: String,
+    };
+
     pub ApiRunResultsPath => "/api/runs/{run_id}/environments/{env}/tests/{test}" {
         run_id: String,
         env: String,
         test: String,
     };
 
+    pub ApiRunComparePath => "/api/runs/{run_id}/environments/{env}/tests/{test}/compare" {
+        run_id: String,
+        env: String,
+        test: String,
+    };
+
     pub ApiRunRawPath => "/api/runs/{run_id}/environments/{env}/tests/{test}/frameworks/{framework}/raw" {
         run_id: String,
         env: String,
         test: String,
         framework: String,
     };
+
+    pub ApiRunPercentilesPath => "/api/runs/{run_id}/environments/{env}/tests/{test}/frameworks/{framework}/raw/percentiles" {
+        run_id: String,
+        env: String,
+        test: String,
+        framework: String,
+    };
+
+    pub ApiRunLogsPath => "/api/runs/{run_id}/environments/{env}/tests/{test}/frameworks/{framework}/logs" {
+        run_id: String,
+        env: String,
+        test: String,
+        framework: String,
+    } [tpl run_logs_api_url];
+
+    pub ApiRunCurvePath => "/api/runs/{run_id}/environments/{env}/tests/{test}/frameworks/{framework}/curve" {
+        run_id: String,
+        env: String,
+        test: String,
+        framework: String,
+    };
 }
 
 /// Build the full Axum app (routes + middleware + static assets fallback).
@@ -169,17 +230,39 @@ pub fn build_app(state: Arc<AppState>, assets_dir: PathBuf) -> Router {
             BenchChartsPartialsViewPath::PATH,
             get(web::bench_charts_partials_path_handler),
         )
+        .route(
+            BenchLatencySpectrumPartialsViewPath::PATH,
+            get(web::bench_latency_spectrum_partials_path_handler),
+        )
+        .route(
+            BenchHistoryViewPath::PATH,
+            get(web::bench_history_path_handler),
+        )
+        .route(CompareViewPath::PATH, get(web::compare_path_handler))
         // API
         .route(ApiTags::PATH, get(api::get_tags))
         .route(ApiEnvironments::PATH, get(api::get_environments))
         .route(ApiTests::PATH, get(api::get_tests))
         .route(ApiLanguages::PATH, get(api::get_languages))
         .route(ApiFrameworks::PATH, get(api::get_frameworks))
+        .route(
+            ApiFrameworkHistoryPath::PATH,
+            get(api::get_framework_history),
+        )
         .route(ApiBenchmarks::PATH, get(api::get_benchmarks))
         .route(ApiRuns::PATH, get(api::get_runs))
         .route(ApiVersion::PATH, get(api::get_version))
+        .route(ApiRunRegressionsPath::PATH, get(api::get_run_regressions))
+        .route(ApiRunJournalPath::PATH, get(api::get_run_journal))
         .route(ApiRunResultsPath::PATH, get(api::get_run_results))
+        .route(ApiRunComparePath::PATH, get(api::get_run_comparison))
         .route(ApiRunRawPath::PATH, get(api::get_run_raw_data))
+        .route(
+            ApiRunPercentilesPath::PATH,
+            get(api::get_run_latency_percentiles),
+        )
+        .route(ApiRunCurvePath::PATH, get(api::get_run_latency_curve))
+        .route(ApiRunLogsPath::PATH, get(api::get_run_logs))
         .with_state(state)
         .layer(
             ServiceBuilder::new()
@@ -1,13 +1,86 @@
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock, RwLockReadGuard};
-use wfb_storage::{Config, Storage};
+use wfb_storage::{Config, ConsecutiveRunComparisons, JournalEntry, Storage};
+
+type JournalCache = HashMap<(String, String), Arc<Vec<JournalEntry>>>;
 
 pub struct AppState {
     pub storage: Arc<Storage>,
     pub config: Arc<RwLock<Config>>,
+    /// Every run compared against the previous run of the same environment.
+    pub regressions: RwLock<ConsecutiveRunComparisons>,
+    pub regression_threshold_pct: f64,
+    /// Run journals keyed by run id and environment, read from storage on first use.
+    journals: RwLock<JournalCache>,
 }
 
 impl AppState {
+    pub fn new(
+        storage: Arc<Storage>,
+        config: Arc<RwLock<Config>>,
+        regression_threshold_pct: f64,
+    ) -> Self {
+        let state = Self {
+            storage,
+            config,
+            regressions: RwLock::new(ConsecutiveRunComparisons::new()),
+            regression_threshold_pct,
+            journals: RwLock::new(HashMap::new()),
+        };
+        state.refresh_regressions();
+        state
+    }
+
     pub fn config_read(&self) -> RwLockReadGuard<'_, Config> {
         self.config.read().unwrap_or_else(|err| err.into_inner())
     }
+
+    pub fn regressions_read(&self) -> RwLockReadGuard<'_, ConsecutiveRunComparisons> {
+        self.regressions
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub fn journal(&self, run_id: &str, environment: &str) -> Arc<Vec<JournalEntry>> {
+        let key = (run_id.to_string(), environment.to_string());
+        if let Some(journal) = self
+            .journals
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&key)
+        {
+            return journal.clone();
+        }
+
+        let journal = Arc::new(self.storage.get_journal(run_id, environment));
+        self.journals
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(key, journal.clone());
+        journal
+    }
+
+    /// Drops the cached journal of one run and environment, or every journal with `None`.
+    pub fn invalidate_journals(&self, scope: Option<(&str, &str)>) {
+        let mut journals = self.journals.write().unwrap_or_else(|err| err.into_inner());
+        match scope {
+            Some((run_id, environment)) => {
+                journals.remove(&(run_id.to_string(), environment.to_string()));
+            }
+            None => journals.clear(),
+        }
+    }
+
+    /// Recomputes run-to-run comparisons; call after the storage reloads.
+    pub fn refresh_regressions(&self) {
+        let comparisons = {
+            let data = self.storage.data_read();
+            let runs = self.storage.runs();
+            wfb_storage::compare_consecutive_runs(&data, &runs, self.regression_threshold_pct)
+        };
+        *self
+            .regressions
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = comparisons;
+    }
 }
@@ -3,6 +3,9 @@
     fmt::{self},
 };
 
+use crate::environment::ContainerResources;
+use crate::load_profile::ResolvedLoadProfile;
+
 #[derive(
     Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
 )]
@@ -70,13 +73,24 @@ pub struct Benchmark {
     pub disabled: bool,
     #[serde(default)]
     pub only: bool,
+    /// Arguments appended to the container command, after the image name.
     #[serde(default)]
     pub arguments: Vec<String>,
+    /// Docker `--build-arg`s for the benchmark image, so one Dockerfile can back several
+    /// benchmark entries.
+    #[serde(default)]
+    pub build_args: HashMap<String, String>,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Load profile overriding the environment's one for this benchmark.
+    #[serde(default)]
+    pub load_profile: Option<String>,
+    /// Resource limits overriding the environment's ones for this benchmark.
+    #[serde(default)]
+    pub resources: ContainerResources,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct BenchmarkManifest {
     pub language_version: String,
@@ -84,4 +98,17 @@ pub struct BenchmarkManifest {
     pub tags: HashMap<String, String>,
     pub database: Option<DatabaseKind>,
     pub path: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub build_args: HashMap<String, String>,
+    /// Resource limits applied to the app and database containers.
+    #[serde(default, skip_serializing_if = "ContainerResources::is_empty")]
+    pub resources: ContainerResources,
+    /// Resolved load settings per test case that produced the stored results.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub load_profiles: HashMap<String, ResolvedLoadProfile>,
+    /// Host set of the environment's pool each test case was measured on.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hosts: HashMap<String, String>,
 }
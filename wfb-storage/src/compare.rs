@@ -0,0 +1,607 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::{MetricStats, t_critical_95};
+use crate::storage::{RunManifest, StorageData};
+use crate::testcase::TestCaseSummary;
+
+/// Absolute and relative change of one metric between two runs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MetricDelta {
+    pub base: f64,
+    pub head: f64,
+    /// Percent change relative to `base`; `None` when `base` is zero.
+    pub change_pct: Option<f64>,
+}
+
+impl MetricDelta {
+    pub fn new(base: f64, head: f64) -> Self {
+        let change_pct = if base != 0.0 {
+            Some((head - base) / base * 100.0)
+        } else {
+            None
+        };
+        Self {
+            base,
+            head,
+            change_pct,
+        }
+    }
+}
+
+/// Whether a difference is backed by run-to-run statistics.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Significance {
+    Significant,
+    NotSignificant,
+    /// At least one side was measured once, so there is no variance to test against.
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    Unchanged,
+    /// Past the threshold but not statistically significant.
+    Noise,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestCaseComparison {
+    /// Run the test was compared against; older than [`RunComparison::base_run`] when the
+    /// previous run did not include this test.
+    pub base_run: String,
+    pub language: String,
+    pub benchmark: String,
+    pub test: String,
+    pub requests_per_sec: MetricDelta,
+    pub latency_p50: MetricDelta,
+    pub latency_p99: MetricDelta,
+    pub memory_usage_bytes: MetricDelta,
+    pub total_errors: MetricDelta,
+    pub significance: Significance,
+    pub verdict: Verdict,
+}
+
+/// A test present in only one of the two runs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnmatchedTestCase {
+    pub language: String,
+    pub benchmark: String,
+    pub test: String,
+}
+
+impl UnmatchedTestCase {
+    fn new(language: &str, benchmark: &str, test: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            benchmark: benchmark.to_string(),
+            test: test.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunComparison {
+    /// For consecutive runs, the latest earlier run of the environment.
+    pub base_run: String,
+    pub head_run: String,
+    pub environment: String,
+    pub threshold_pct: f64,
+    pub tests: Vec<TestCaseComparison>,
+    pub only_in_base: Vec<UnmatchedTestCase>,
+    pub only_in_head: Vec<UnmatchedTestCase>,
+}
+
+impl RunComparison {
+    fn new(
+        base_run: &str,
+        head_run: &str,
+        environment: &str,
+        threshold_pct: f64,
+        mut tests: Vec<TestCaseComparison>,
+        mut only_in_base: Vec<UnmatchedTestCase>,
+        mut only_in_head: Vec<UnmatchedTestCase>,
+    ) -> Self {
+        tests.sort_by(|a, b| {
+            (&a.language, &a.benchmark, &a.test).cmp(&(&b.language, &b.benchmark, &b.test))
+        });
+        only_in_base.sort_by(|a, b| {
+            (&a.language, &a.benchmark, &a.test).cmp(&(&b.language, &b.benchmark, &b.test))
+        });
+        only_in_head.sort_by(|a, b| {
+            (&a.language, &a.benchmark, &a.test).cmp(&(&b.language, &b.benchmark, &b.test))
+        });
+        Self {
+            base_run: base_run.to_string(),
+            head_run: head_run.to_string(),
+            environment: environment.to_string(),
+            threshold_pct,
+            tests,
+            only_in_base,
+            only_in_head,
+        }
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &TestCaseComparison> {
+        self.tests
+            .iter()
+            .filter(|t| t.verdict == Verdict::Regressed)
+    }
+
+    pub fn find(&self, language: &str, benchmark: &str, test: &str) -> Option<&TestCaseComparison> {
+        self.tests
+            .iter()
+            .find(|t| t.language == language && t.benchmark == benchmark && t.test == test)
+    }
+}
+
+/// Run comparisons keyed by head run id, then environment.
+pub type ConsecutiveRunComparisons = HashMap<String, HashMap<String, RunComparison>>;
+
+/// Compares every run against the earlier runs (by [`RunManifest::created_at`]) of the
+/// same environment: each test against the most recent earlier run that contains it, so a
+/// run that skipped some tests does not hide their regressions from the next one.
+/// The first run of each environment has no entry.
+pub fn compare_consecutive_runs(
+    data: &StorageData,
+    runs: &HashMap<String, RunManifest>,
+    threshold_pct: f64,
+) -> ConsecutiveRunComparisons {
+    let mut ordered: Vec<&String> = data.keys().collect();
+    ordered.sort_by_key(|run_id| (runs.get(*run_id).map(|m| m.created_at), *run_id));
+
+    let mut previous: HashMap<&String, &String> = HashMap::new();
+    // Latest run and summary of every (environment, language, benchmark, test).
+    let mut latest: HashMap<(&String, &String, &String, &String), (&String, &TestCaseSummary)> =
+        HashMap::new();
+    let mut comparisons = ConsecutiveRunComparisons::new();
+    for run_id in ordered {
+        let mut environments: Vec<&String> = data[run_id].keys().collect();
+        environments.sort();
+        for environment in environments {
+            let head = &data[run_id][environment];
+            let mut tests = Vec::new();
+            let mut only_in_head = Vec::new();
+            for (language, benchmarks) in head {
+                for (benchmark, result) in benchmarks {
+                    for (test, head_summary) in &result.test_cases {
+                        let key = (environment, language, benchmark, test);
+                        match latest.insert(key, (run_id, head_summary)) {
+                            Some((base_run, base_summary)) => tests.push(compare_test_case(
+                                base_run,
+                                language,
+                                benchmark,
+                                test,
+                                base_summary,
+                                head_summary,
+                                threshold_pct,
+                            )),
+                            None => {
+                                only_in_head.push(UnmatchedTestCase::new(language, benchmark, test))
+                            }
+                        }
+                    }
+                }
+            }
+
+            let Some(base_run) = previous.insert(environment, run_id) else {
+                continue;
+            };
+            let mut only_in_base = Vec::new();
+            for (language, benchmarks) in &data[base_run][environment] {
+                for (benchmark, result) in benchmarks {
+                    for test in result.test_cases.keys() {
+                        let in_head = head
+                            .get(language)
+                            .and_then(|b| b.get(benchmark))
+                            .is_some_and(|r| r.test_cases.contains_key(test));
+                        if !in_head {
+                            only_in_base.push(UnmatchedTestCase::new(language, benchmark, test));
+                        }
+                    }
+                }
+            }
+
+            comparisons.entry(run_id.clone()).or_default().insert(
+                environment.clone(),
+                RunComparison::new(
+                    base_run,
+                    run_id,
+                    environment,
+                    threshold_pct,
+                    tests,
+                    only_in_base,
+                    only_in_head,
+                ),
+            );
+        }
+    }
+    comparisons
+}
+
+/// Compares every benchmark/test of `head_run` against `base_run` in `environment`.
+///
+/// A test regresses when RPS drops or p99 latency, memory or the error rate grows by more
+/// than `threshold_pct` percent, or when a test without errors starts failing requests.
+/// When both runs were repeated (`--repeat`), RPS and p99 changes must also pass a Welch
+/// t-test at 95% confidence; otherwise they are reported as [`Verdict::Noise`].
+pub fn compare_runs(
+    data: &StorageData,
+    base_run: &str,
+    head_run: &str,
+    environment: &str,
+    threshold_pct: f64,
+) -> RunComparison {
+    let empty = Default::default();
+    let base = data
+        .get(base_run)
+        .and_then(|envs| envs.get(environment))
+        .unwrap_or(&empty);
+    let head = data
+        .get(head_run)
+        .and_then(|envs| envs.get(environment))
+        .unwrap_or(&empty);
+
+    let mut tests = Vec::new();
+    let mut only_in_base = Vec::new();
+    let mut only_in_head = Vec::new();
+
+    for (language, benchmarks) in base {
+        for (benchmark, result) in benchmarks {
+            for (test, base_summary) in &result.test_cases {
+                let head_summary = head
+                    .get(language)
+                    .and_then(|b| b.get(benchmark))
+                    .and_then(|r| r.test_cases.get(test));
+                match head_summary {
+                    Some(head_summary) => tests.push(compare_test_case(
+                        base_run,
+                        language,
+                        benchmark,
+                        test,
+                        base_summary,
+                        head_summary,
+                        threshold_pct,
+                    )),
+                    None => only_in_base.push(UnmatchedTestCase::new(language, benchmark, test)),
+                }
+            }
+        }
+    }
+
+    for (language, benchmarks) in head {
+        for (benchmark, result) in benchmarks {
+            for test in result.test_cases.keys() {
+                let in_base = base
+                    .get(language)
+                    .and_then(|b| b.get(benchmark))
+                    .is_some_and(|r| r.test_cases.contains_key(test));
+                if !in_base {
+                    only_in_head.push(UnmatchedTestCase::new(language, benchmark, test));
+                }
+            }
+        }
+    }
+
+    RunComparison::new(
+        base_run,
+        head_run,
+        environment,
+        threshold_pct,
+        tests,
+        only_in_base,
+        only_in_head,
+    )
+}
+
+pub fn compare_test_case(
+    base_run: &str,
+    language: &str,
+    benchmark: &str,
+    test: &str,
+    base: &TestCaseSummary,
+    head: &TestCaseSummary,
+    threshold_pct: f64,
+) -> TestCaseComparison {
+    let requests_per_sec = MetricDelta::new(base.requests_per_sec, head.requests_per_sec);
+    let latency_p99 = MetricDelta::new(base.latency_p99 as f64, head.latency_p99 as f64);
+
+    let (rps_significance, p99_significance) = match (&base.stats, &head.stats) {
+        (Some(b), Some(h)) => (
+            welch_significance(
+                &b.requests_per_sec,
+                b.iterations,
+                &h.requests_per_sec,
+                h.iterations,
+            ),
+            welch_significance(&b.latency_p99, b.iterations, &h.latency_p99, h.iterations),
+        ),
+        _ => (Significance::Unknown, Significance::Unknown),
+    };
+
+    let memory_usage_bytes = MetricDelta::new(
+        base.memory_usage_bytes as f64,
+        head.memory_usage_bytes as f64,
+    );
+
+    // Lower RPS or higher p99, memory or error rate is worse. Memory and errors have no
+    // per-iteration statistics, so only the threshold applies to them.
+    let rps_change = requests_per_sec.change_pct.unwrap_or(0.0);
+    let p99_change = latency_p99.change_pct.unwrap_or(0.0);
+    let memory_change = memory_usage_bytes.change_pct.unwrap_or(0.0);
+    let verdicts = [
+        metric_verdict(-rps_change, threshold_pct, rps_significance),
+        metric_verdict(p99_change, threshold_pct, p99_significance),
+        metric_verdict(memory_change, threshold_pct, Significance::Unknown),
+        error_rate_verdict(base, head, threshold_pct),
+    ];
+
+    let verdict = [Verdict::Regressed, Verdict::Improved, Verdict::Noise]
+        .into_iter()
+        .find(|v| verdicts.contains(v))
+        .unwrap_or(Verdict::Unchanged);
+
+    let significance = match (rps_significance, p99_significance) {
+        (Significance::Significant, _) | (_, Significance::Significant) => {
+            Significance::Significant
+        }
+        (Significance::Unknown, _) | (_, Significance::Unknown) => Significance::Unknown,
+        _ => Significance::NotSignificant,
+    };
+
+    TestCaseComparison {
+        base_run: base_run.to_string(),
+        language: language.to_string(),
+        benchmark: benchmark.to_string(),
+        test: test.to_string(),
+        requests_per_sec,
+        latency_p50: MetricDelta::new(base.latency_p50 as f64, head.latency_p50 as f64),
+        latency_p99,
+        memory_usage_bytes,
+        total_errors: MetricDelta::new(base.total_errors as f64, head.total_errors as f64),
+        significance,
+        verdict,
+    }
+}
+
+/// Failed requests per request; any errors after an error-free base run are a regression.
+fn error_rate_verdict(
+    base: &TestCaseSummary,
+    head: &TestCaseSummary,
+    threshold_pct: f64,
+) -> Verdict {
+    let rate = |s: &TestCaseSummary| s.total_errors as f64 / s.total_requests.max(1) as f64;
+    match (base.total_errors, head.total_errors) {
+        (0, 0) => Verdict::Unchanged,
+        (0, _) => Verdict::Regressed,
+        (_, 0) => Verdict::Improved,
+        _ => {
+            let change = MetricDelta::new(rate(base), rate(head))
+                .change_pct
+                .unwrap_or(0.0);
+            metric_verdict(change, threshold_pct, Significance::Unknown)
+        }
+    }
+}
+
+/// `worse_pct` is the change in the "worse" direction (positive means worse).
+fn metric_verdict(worse_pct: f64, threshold_pct: f64, significance: Significance) -> Verdict {
+    if worse_pct.abs() <= threshold_pct {
+        return Verdict::Unchanged;
+    }
+    if significance == Significance::NotSignificant {
+        return Verdict::Noise;
+    }
+    if worse_pct > 0.0 {
+        Verdict::Regressed
+    } else {
+        Verdict::Improved
+    }
+}
+
+/// Two-sided Welch t-test on the means of two repeated measurements at 95% confidence.
+pub fn welch_significance(
+    base: &MetricStats,
+    base_n: u32,
+    head: &MetricStats,
+    head_n: u32,
+) -> Significance {
+    if base_n < 2 || head_n < 2 {
+        return Significance::Unknown;
+    }
+
+    let (n1, n2) = (base_n as f64, head_n as f64);
+    let v1 = base.stdev.powi(2) / n1;
+    let v2 = head.stdev.powi(2) / n2;
+    let se = (v1 + v2).sqrt();
+    if se == 0.0 {
+        return if base.mean == head.mean {
+            Significance::NotSignificant
+        } else {
+            Significance::Significant
+        };
+    }
+
+    let t = (head.mean - base.mean) / se;
+    let df = (v1 + v2).powi(2) / (v1.powi(2) / (n1 - 1.0) + v2.powi(2) / (n2 - 1.0));
+    let df = (df.floor() as usize).max(1);
+
+    if t.abs() > t_critical_95(df) {
+        Significance::Significant
+    } else {
+        Significance::NotSignificant
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{
+        Significance, Verdict, compare_consecutive_runs, compare_test_case, welch_significance,
+    };
+    use crate::stats::MetricStats;
+    use crate::storage::{BenchmarkResult, RunManifest, StorageData};
+    use crate::testcase::TestCaseSummary;
+
+    #[test]
+    fn consecutive_runs_compare_against_previous_run_of_same_environment() {
+        let mut data = StorageData::new();
+        let mut runs = HashMap::new();
+        for (run_id, day, env, rps) in [
+            ("3", 3, "local", 80.0),
+            ("1", 1, "local", 100.0),
+            ("2", 2, "ci", 200.0),
+        ] {
+            let result = BenchmarkResult {
+                manifest: Default::default(),
+                test_cases: HashMap::from([(
+                    "plaintext".to_string(),
+                    TestCaseSummary {
+                        requests_per_sec: rps,
+                        ..Default::default()
+                    },
+                )]),
+            };
+            data.entry(run_id.to_string())
+                .or_default()
+                .entry(env.to_string())
+                .or_default()
+                .entry("Rust".to_string())
+                .or_default()
+                .insert("axum".to_string(), result);
+            runs.insert(
+                run_id.to_string(),
+                RunManifest {
+                    created_at: Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let comparisons = compare_consecutive_runs(&data, &runs, 5.0);
+
+        assert!(!comparisons.contains_key("1"));
+        assert!(!comparisons.contains_key("2"));
+        let local = &comparisons["3"]["local"];
+        assert_eq!(local.base_run, "1");
+        let axum = local
+            .find("Rust", "axum", "plaintext")
+            .expect("axum compared");
+        assert_eq!(axum.verdict, Verdict::Regressed);
+    }
+
+    #[test]
+    fn consecutive_runs_compare_each_test_against_its_latest_earlier_run() {
+        let mut data = StorageData::new();
+        let mut runs = HashMap::new();
+        // Run 2 only re-ran `json`, so `plaintext` of run 3 is compared against run 1.
+        for (run_id, tests) in [
+            ("1", vec![("plaintext", 100.0), ("json", 100.0)]),
+            ("2", vec![("json", 100.0)]),
+            ("3", vec![("plaintext", 80.0), ("json", 100.0)]),
+        ] {
+            let result = BenchmarkResult {
+                manifest: Default::default(),
+                test_cases: tests
+                    .into_iter()
+                    .map(|(test, rps)| {
+                        (
+                            test.to_string(),
+                            TestCaseSummary {
+                                requests_per_sec: rps,
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .collect(),
+            };
+            data.entry(run_id.to_string())
+                .or_default()
+                .entry("local".to_string())
+                .or_default()
+                .entry("Rust".to_string())
+                .or_default()
+                .insert("axum".to_string(), result);
+            runs.insert(
+                run_id.to_string(),
+                RunManifest {
+                    created_at: Utc
+                        .with_ymd_and_hms(2025, 1, run_id.parse().unwrap(), 0, 0, 0)
+                        .unwrap(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let comparisons = compare_consecutive_runs(&data, &runs, 5.0);
+
+        let second = &comparisons["2"]["local"];
+        assert_eq!(second.only_in_base.len(), 1);
+        assert_eq!(second.only_in_base[0].test, "plaintext");
+
+        let third = &comparisons["3"]["local"];
+        assert_eq!(third.base_run, "2");
+        assert!(third.only_in_head.is_empty());
+        let plaintext = third
+            .find("Rust", "axum", "plaintext")
+            .expect("plaintext compared");
+        assert_eq!(plaintext.base_run, "1");
+        assert_eq!(plaintext.verdict, Verdict::Regressed);
+        let json = third.find("Rust", "axum", "json").expect("json compared");
+        assert_eq!(json.base_run, "2");
+        assert_eq!(json.verdict, Verdict::Unchanged);
+    }
+
+    #[test]
+    fn new_errors_and_memory_growth_regress() {
+        let base = TestCaseSummary {
+            requests_per_sec: 100.0,
+            total_requests: 1000,
+            memory_usage_bytes: 100,
+            ..Default::default()
+        };
+        let compare = |head: &TestCaseSummary| {
+            compare_test_case("1", "Rust", "axum", "plaintext", &base, head, 5.0).verdict
+        };
+
+        assert_eq!(compare(&base), Verdict::Unchanged);
+        let failing = TestCaseSummary {
+            total_errors: 1,
+            ..base.clone()
+        };
+        assert_eq!(compare(&failing), Verdict::Regressed);
+        let bloated = TestCaseSummary {
+            memory_usage_bytes: 150,
+            ..base.clone()
+        };
+        assert_eq!(compare(&bloated), Verdict::Regressed);
+    }
+
+    #[test]
+    fn welch_significance_separates_distinct_means() {
+        let base = MetricStats::from_samples(&[100.0, 101.0, 99.0, 100.5, 99.5]).expect("stats");
+        let head = MetricStats::from_samples(&[90.0, 91.0, 89.0, 90.5, 89.5]).expect("stats");
+        let noisy = MetricStats::from_samples(&[80.0, 120.0, 95.0, 110.0, 90.0]).expect("stats");
+
+        assert_eq!(
+            welch_significance(&base, 5, &head, 5),
+            Significance::Significant
+        );
+        assert_eq!(
+            welch_significance(&base, 5, &noisy, 5),
+            Significance::NotSignificant
+        );
+        assert_eq!(
+            welch_significance(&base, 1, &head, 5),
+            Significance::Unknown
+        );
+    }
+}
@@ -3,7 +3,10 @@
 use serde::Deserialize;
 use walkdir::WalkDir;
 
-use crate::{Benchmark, Environment, EnvironmentSecrets, Error, Framework, Lang, Result};
+use crate::{
+    Benchmark, BenchmarkTests, DEFAULT_LOAD_PROFILE, Environment, EnvironmentSecrets, Error,
+    Framework, Lang, LoadProfile, LoadSettings, Result,
+};
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -16,6 +19,7 @@ struct ConfigInner {
     frameworks: Vec<Framework>,
     benchmarks: Vec<Benchmark>,
     environments: Vec<Environment>,
+    load_profiles: Vec<LoadProfile>,
 }
 
 struct ConfigAccumulator {
@@ -23,6 +27,7 @@ struct ConfigAccumulator {
     frameworks: Vec<Framework>,
     benchmarks: Vec<Benchmark>,
     environments: Vec<Environment>,
+    load_profiles: Vec<LoadProfile>,
     secrets: Vec<EnvironmentSecrets>,
 }
 
@@ -34,6 +39,7 @@ enum ConfigFile {
     Benchmark(Box<Benchmark>),
     Environment(Box<Environment>),
     EnvironmentSecrets(Box<EnvironmentSecrets>),
+    LoadProfile(Box<LoadProfile>),
 }
 
 impl Config {
@@ -54,6 +60,7 @@ pub fn load(dir: &PathBuf) -> Result<Self> {
                     frameworks: Vec::new(),
                     benchmarks: Vec::new(),
                     environments: Vec::new(),
+                    load_profiles: Vec::new(),
                     secrets: Vec::new(),
                 },
                 |mut acc, entry| -> Result<_> {
@@ -101,29 +108,32 @@ pub fn load(dir: &PathBuf) -> Result<Self> {
                                 acc.environments.push(*environment)
                             }
                             ConfigFile::EnvironmentSecrets(secret) => acc.secrets.push(*secret),
+                            ConfigFile::LoadProfile(profile) => acc.load_profiles.push(*profile),
                         }
                     }
 
                     Ok(acc)
                 },
             )
-            .map(|mut acc| {
+            .and_then(|mut acc| {
                 for env in &mut acc.environments {
-                    if let Environment::Ssh(ssh_env) = env
-                        && let Some(secret) = acc.secrets.iter().find(|s| s.name == ssh_env.name)
-                    {
-                        ssh_env.merge_secrets(secret.clone());
+                    if let Environment::Ssh(ssh_env) = env {
+                        if let Some(secret) = acc.secrets.iter().find(|s| s.name == ssh_env.name) {
+                            ssh_env.merge_secrets(secret.clone());
+                        }
+                        ssh_env.validate_host_sets()?;
                     }
                 }
 
-                Config {
+                Ok(Config {
                     inner: Arc::new(ConfigInner {
                         langs: acc.langs,
                         frameworks: acc.frameworks,
                         benchmarks: acc.benchmarks,
                         environments: acc.environments,
+                        load_profiles: acc.load_profiles,
                     }),
-                }
+                })
             })
     }
 
@@ -171,6 +181,53 @@ pub fn environments(&self) -> &[Environment] {
         &self.inner.environments
     }
 
+    pub fn load_profiles(&self) -> &[LoadProfile] {
+        &self.inner.load_profiles
+    }
+
+    pub fn get_load_profile(&self, name: &str) -> Option<&LoadProfile> {
+        self.inner.load_profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Layers the load settings for `test`: the `default` profile (if defined), then the
+    /// environment's profile, then the benchmark's profile.
+    ///
+    /// Returns the names of the applied profiles with the merged settings; fields left
+    /// unset fall back to the runner's built-in defaults.
+    pub fn load_settings_for(
+        &self,
+        environment: &Environment,
+        benchmark: &Benchmark,
+        test: BenchmarkTests,
+    ) -> Result<(Vec<String>, LoadSettings)> {
+        let mut applied = Vec::new();
+        let mut settings = LoadSettings::default();
+
+        if let Some(profile) = self.get_load_profile(DEFAULT_LOAD_PROFILE) {
+            settings.merge(&profile.settings_for(test));
+            applied.push(profile.name.clone());
+        }
+
+        for name in [
+            environment.load_profile(),
+            benchmark.load_profile.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if name == DEFAULT_LOAD_PROFILE {
+                continue;
+            }
+            let profile = self
+                .get_load_profile(name)
+                .ok_or_else(|| Error::UnknownLoadProfile(name.to_string()))?;
+            settings.merge(&profile.settings_for(test));
+            applied.push(profile.name.clone());
+        }
+
+        Ok((applied, settings))
+    }
+
     pub fn get_lang(&self, name: &str) -> Option<&Lang> {
         self.inner.langs.iter().find(|l| l.name == name)
     }
@@ -181,3 +238,93 @@ pub fn reload(&mut self, dir: &PathBuf) -> Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, ConfigInner};
+    use crate::{Benchmark, BenchmarkTests, Environment, Error, LoadProfile};
+    use std::sync::Arc;
+
+    fn config(profiles: &[&str]) -> Config {
+        Config {
+            inner: Arc::new(ConfigInner {
+                langs: Vec::new(),
+                frameworks: Vec::new(),
+                benchmarks: Vec::new(),
+                environments: Vec::new(),
+                load_profiles: profiles
+                    .iter()
+                    .map(|yaml| serde_yaml::from_str::<LoadProfile>(yaml).expect("profile"))
+                    .collect(),
+            }),
+        }
+    }
+
+    fn environment(load_profile: &str) -> Environment {
+        serde_yaml::from_str(&format!(
+            "executor: local\nname: local\ntitle: Local\nspec: null\nicon: null\nload_profile: {}",
+            load_profile
+        ))
+        .expect("environment")
+    }
+
+    fn benchmark(load_profile: &str) -> Benchmark {
+        serde_yaml::from_str(&format!(
+            "name: axum\nlanguage: rust\nlanguage_version: '1'\nframework: axum\nframework_version: '0.8'\npath: rust/axum\nload_profile: {}",
+            load_profile
+        ))
+        .expect("benchmark")
+    }
+
+    #[test]
+    fn load_settings_layer_defaults_environment_benchmark_and_test() {
+        let config = config(&[
+            "name: default\nduration_secs: 10\nwarmup_vus: 5\nmax_vus: 100",
+            "name: cluster\nmax_vus: 200\ntests:\n  plain_text:\n    duration_secs: 20\n    max_vus: 250",
+            "name: tuned\nmax_vus: 300\nrate: 7\ntests:\n  plain_text:\n    rate: 9",
+        ]);
+        let environment = environment("cluster");
+        let benchmark = benchmark("tuned");
+
+        let (applied, plaintext) = config
+            .load_settings_for(&environment, &benchmark, BenchmarkTests::PlainText)
+            .expect("settings");
+        assert_eq!(applied, ["default", "cluster", "tuned"]);
+        // The environment's per-test override beats the defaults...
+        assert_eq!(plaintext.duration_secs, Some(20));
+        // ...and loses to the benchmark's profile, whose own per-test override wins last.
+        assert_eq!(plaintext.max_vus, Some(300));
+        assert_eq!(plaintext.rate, Some(9));
+        // Fields no layer above sets keep the default profile's value.
+        assert_eq!(plaintext.warmup_vus, Some(5));
+        assert_eq!(plaintext.executor, None);
+
+        let (_, json) = config
+            .load_settings_for(&environment, &benchmark, BenchmarkTests::JsonAggregate)
+            .expect("settings");
+        assert_eq!(json.duration_secs, Some(10));
+        assert_eq!(json.max_vus, Some(300));
+        assert_eq!(json.rate, Some(7));
+    }
+
+    #[test]
+    fn load_settings_reject_unknown_profiles() {
+        let config = config(&["name: default\nduration_secs: 10"]);
+        let result = config.load_settings_for(
+            &environment("missing"),
+            &benchmark("default"),
+            BenchmarkTests::PlainText,
+        );
+        assert!(matches!(result, Err(Error::UnknownLoadProfile(name)) if name == "missing"));
+
+        let (applied, settings) = config
+            .load_settings_for(
+                &environment("default"),
+                &benchmark("default"),
+                BenchmarkTests::PlainText,
+            )
+            .expect("settings");
+        assert_eq!(applied, ["default"]);
+        assert_eq!(settings.duration_secs, Some(10));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -5,6 +6,7 @@
 pub enum Environment {
     Local(Box<LocalEnvironment>),
     Ssh(Box<SshEnvironment>),
+    Kubernetes(Box<KubernetesEnvironment>),
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -13,11 +15,78 @@ pub struct LocalEnvironment {
     pub title: String,
     pub spec: Option<String>,
     pub icon: Option<String>,
+    /// Load profile applied to every benchmark in this environment.
+    #[serde(default)]
+    pub load_profile: Option<String>,
+    #[serde(default)]
+    pub resources: ContainerResources,
+    #[serde(default)]
+    pub endpoints: EndpointOverrides,
+    #[serde(default)]
+    pub docker_backend: DockerBackend,
+    #[serde(default)]
+    pub container_runtime: ContainerRuntime,
+}
+
+/// Pods in a Kubernetes cluster, driven with `kubectl` from this machine.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct KubernetesEnvironment {
+    pub name: String,
+    pub title: String,
+    pub spec: Option<String>,
+    pub icon: Option<String>,
+    /// Load profile applied to every benchmark in this environment.
+    #[serde(default)]
+    pub load_profile: Option<String>,
+    #[serde(default)]
+    pub resources: ContainerResources,
+    #[serde(default)]
+    pub endpoints: EndpointOverrides,
+    /// CLI that builds and pushes the images on this machine.
+    #[serde(default)]
+    pub container_runtime: ContainerRuntime,
+    /// `kubectl` context; the current one when unset.
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    /// Registry the benchmark and database images are pushed to and pulled from,
+    /// e.g. `localhost:5001` for a kind cluster with a local registry.
+    pub registry: String,
+    /// Node labels the pods of each role are pinned to.
+    #[serde(default)]
+    pub nodes: KubernetesNodes,
+    /// Run benchmarks even though pods cannot apply their ulimits (and cpusets that do not
+    /// map to whole CPUs); the dropped constraints are recorded in the run manifest.
+    #[serde(default)]
+    pub allow_dropped_constraints: bool,
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_namespace() -> String {
+    "wfb".to_string()
+}
+
+/// `nodeSelector` labels of the app, database and wrkr pods.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct KubernetesNodes {
+    #[serde(default)]
+    pub app: HashMap<String, String>,
+    #[serde(default)]
+    pub db: HashMap<String, String>,
+    #[serde(default)]
+    pub wrkr: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SshConnection {
     pub ip: String,
+    /// SSH port of `ip`; 22 when unset.
+    #[serde(default = "default_ssh_port")]
+    pub port: u16,
     pub internal_ip: String,
     pub user: String,
     pub ssh_key_path: PathBuf,
@@ -29,6 +98,34 @@ pub struct SshEnvironment {
     pub title: String,
     pub spec: Option<String>,
     pub icon: Option<String>,
+    /// Load profile applied to every benchmark in this environment.
+    #[serde(default)]
+    pub load_profile: Option<String>,
+    #[serde(default)]
+    pub resources: ContainerResources,
+    #[serde(default)]
+    pub endpoints: EndpointOverrides,
+    #[serde(default)]
+    pub docker_backend: DockerBackend,
+    #[serde(default)]
+    pub container_runtime: ContainerRuntime,
+    #[serde(default)]
+    pub wrkr: Option<SshConnection>,
+    #[serde(default)]
+    pub db: Option<SshConnection>,
+    #[serde(default)]
+    pub app: Option<SshConnection>,
+    /// Identical app/db/wrkr triples that `run` schedules benchmarks across concurrently.
+    /// When empty, the `app`/`db`/`wrkr` connections above are the only triple.
+    #[serde(default)]
+    pub hosts: Vec<SshHostSet>,
+}
+
+/// One app/db/wrkr triple of an SSH environment's host pool.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SshHostSet {
+    /// Recorded with every result measured on this triple.
+    pub name: String,
     #[serde(default)]
     pub wrkr: Option<SshConnection>,
     #[serde(default)]
@@ -37,12 +134,126 @@ pub struct SshEnvironment {
     pub app: Option<SshConnection>,
 }
 
+impl SshHostSet {
+    fn merge(&mut self, other: SshHostSet) {
+        if let Some(wrkr) = other.wrkr {
+            self.wrkr = Some(wrkr);
+        }
+        if let Some(db) = other.db {
+            self.db = Some(db);
+        }
+        if let Some(app) = other.app {
+            self.app = Some(app);
+        }
+    }
+}
+
+/// Container engine CLI installed on the hosts of an environment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    /// Rootless Podman; never run through `sudo`.
+    Podman,
+    Nerdctl,
+}
+
+/// How the runner talks to the Docker daemons of an environment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DockerBackend {
+    /// `docker` CLI commands run through the environment's executor.
+    #[default]
+    Cli,
+    /// Docker Engine API over the daemon's unix socket, forwarded over SSH for remote hosts.
+    Api,
+}
+
+/// Ports and addresses the runner wires the containers together with; unset fields keep
+/// the runner's defaults.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EndpointOverrides {
+    /// Host port the app container is published on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_port: Option<u16>,
+    /// Host port the database container is published on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_port: Option<u16>,
+    /// Address the app connects to the database with, instead of the db host's
+    /// `internal_ip` (`host.docker.internal` for local environments).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_host: Option<String>,
+}
+
+/// Docker resource limits for one container; unset fields leave Docker's defaults.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ResourceLimits {
+    /// `--cpus`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<f64>,
+    /// `--cpuset-cpus`, e.g. `"0-3"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpuset_cpus: Option<String>,
+    /// `--memory`, e.g. `"8g"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// `--network`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+}
+
+impl ResourceLimits {
+    /// Overrides fields of `self` with the ones set in `other`.
+    pub fn merge(&mut self, other: &ResourceLimits) {
+        if other.cpus.is_some() {
+            self.cpus = other.cpus;
+        }
+        if other.cpuset_cpus.is_some() {
+            self.cpuset_cpus = other.cpuset_cpus.clone();
+        }
+        if other.memory.is_some() {
+            self.memory = other.memory.clone();
+        }
+        if other.network.is_some() {
+            self.network = other.network.clone();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == ResourceLimits::default()
+    }
+}
+
+/// Resource limits for the app and database containers of a benchmark.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ContainerResources {
+    #[serde(default, skip_serializing_if = "ResourceLimits::is_empty")]
+    pub app: ResourceLimits,
+    #[serde(default, skip_serializing_if = "ResourceLimits::is_empty")]
+    pub db: ResourceLimits,
+}
+
+impl ContainerResources {
+    pub fn merge(&mut self, other: &ContainerResources) {
+        self.app.merge(&other.app);
+        self.db.merge(&other.db);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.app.is_empty() && self.db.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct EnvironmentSecrets {
     pub name: String,
     pub wrkr: Option<SshConnection>,
     pub db: Option<SshConnection>,
     pub app: Option<SshConnection>,
+    /// Connections of the host pool, matched to `hosts` by name.
+    #[serde(default)]
+    pub hosts: Vec<SshHostSet>,
 }
 
 impl SshEnvironment {
@@ -56,6 +267,50 @@ pub fn merge_secrets(&mut self, secrets: EnvironmentSecrets) {
         if let Some(app) = secrets.app {
             self.app = Some(app);
         }
+        for host in secrets.hosts {
+            match self.hosts.iter_mut().find(|h| h.name == host.name) {
+                Some(existing) => existing.merge(host),
+                None => self.hosts.push(host),
+            }
+        }
+    }
+
+    /// The host pool: `hosts`, or the single `app`/`db`/`wrkr` triple named after the
+    /// environment when no pool is configured.
+    pub fn host_sets(&self) -> Vec<SshHostSet> {
+        if !self.hosts.is_empty() {
+            return self.hosts.clone();
+        }
+        vec![SshHostSet {
+            name: self.name.clone(),
+            wrkr: self.wrkr.clone(),
+            db: self.db.clone(),
+            app: self.app.clone(),
+        }]
+    }
+
+    /// Fails when two host sets share an app/db/wrkr host (same user, ip and port), since
+    /// concurrent runners would then contend for the same machine.
+    pub fn validate_host_sets(&self) -> crate::Result<()> {
+        let mut seen: HashMap<(&str, &str, u16), &str> = HashMap::new();
+        for set in &self.hosts {
+            let connections: std::collections::HashSet<_> = [&set.app, &set.db, &set.wrkr]
+                .into_iter()
+                .flatten()
+                .map(|c| (c.user.as_str(), c.ip.as_str(), c.port))
+                .collect();
+            for key in connections {
+                if let Some(first) = seen.insert(key, &set.name) {
+                    return Err(crate::Error::OverlappingHostSets {
+                        environment: self.name.clone(),
+                        first: first.to_string(),
+                        second: set.name.clone(),
+                        host: format!("{}@{}:{}", key.0, key.1, key.2),
+                    });
+                }
+            }
+        }
+        Ok(())
     }
 }
 
@@ -64,6 +319,7 @@ pub fn name(&self) -> &str {
         match self {
             Environment::Local(env) => &env.name,
             Environment::Ssh(env) => &env.name,
+            Environment::Kubernetes(env) => &env.name,
         }
     }
 
@@ -71,6 +327,7 @@ pub fn title(&self) -> &str {
         match self {
             Environment::Local(env) => &env.title,
             Environment::Ssh(env) => &env.title,
+            Environment::Kubernetes(env) => &env.title,
         }
     }
 
@@ -78,6 +335,7 @@ pub fn spec(&self) -> Option<&str> {
         match self {
             Environment::Local(env) => env.spec.as_deref(),
             Environment::Ssh(env) => env.spec.as_deref(),
+            Environment::Kubernetes(env) => env.spec.as_deref(),
         }
     }
 
@@ -85,6 +343,105 @@ pub fn icon(&self) -> Option<&str> {
         match self {
             Environment::Local(env) => env.icon.as_deref(),
             Environment::Ssh(env) => env.icon.as_deref(),
+            Environment::Kubernetes(env) => env.icon.as_deref(),
         }
     }
+
+    pub fn resources(&self) -> &ContainerResources {
+        match self {
+            Environment::Local(env) => &env.resources,
+            Environment::Ssh(env) => &env.resources,
+            Environment::Kubernetes(env) => &env.resources,
+        }
+    }
+
+    pub fn endpoints(&self) -> &EndpointOverrides {
+        match self {
+            Environment::Local(env) => &env.endpoints,
+            Environment::Ssh(env) => &env.endpoints,
+            Environment::Kubernetes(env) => &env.endpoints,
+        }
+    }
+
+    pub fn docker_backend(&self) -> DockerBackend {
+        match self {
+            Environment::Local(env) => env.docker_backend,
+            Environment::Ssh(env) => env.docker_backend,
+            Environment::Kubernetes(_) => DockerBackend::Cli,
+        }
+    }
+
+    pub fn container_runtime(&self) -> ContainerRuntime {
+        match self {
+            Environment::Local(env) => env.container_runtime,
+            Environment::Ssh(env) => env.container_runtime,
+            Environment::Kubernetes(env) => env.container_runtime,
+        }
+    }
+
+    pub fn load_profile(&self) -> Option<&str> {
+        match self {
+            Environment::Local(env) => env.load_profile.as_deref(),
+            Environment::Ssh(env) => env.load_profile.as_deref(),
+            Environment::Kubernetes(env) => env.load_profile.as_deref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EnvironmentSecrets, SshEnvironment};
+
+    #[test]
+    fn secrets_fill_in_the_host_pool_by_name() {
+        let mut env: SshEnvironment = serde_yaml::from_str(
+            "name: r640\ntitle: R640\nspec: null\nicon: null\nhosts:\n  - name: a\n  - name: b\n",
+        )
+        .unwrap();
+        let conn =
+            "{ ip: 10.0.0.1, port: 2222, internal_ip: 192.168.0.1, user: wfb, ssh_key_path: k }";
+        let secrets: EnvironmentSecrets = serde_yaml::from_str(&format!(
+            "name: r640\nwrkr: null\ndb: null\napp: null\nhosts:\n  - {{ name: b, app: {conn} }}\n"
+        ))
+        .unwrap();
+
+        env.merge_secrets(secrets);
+
+        let hosts = env.host_sets();
+        assert_eq!(hosts.len(), 2);
+        assert!(hosts[0].app.is_none());
+        assert_eq!(hosts[1].name, "b");
+        assert_eq!(hosts[1].app.as_ref().unwrap().ip, "10.0.0.1");
+        assert_eq!(hosts[1].app.as_ref().unwrap().port, 2222);
+
+        env.hosts.clear();
+        assert_eq!(env.host_sets()[0].name, "r640");
+    }
+
+    #[test]
+    fn host_sets_may_not_share_a_host() {
+        let env = |second_app_port: u16| -> SshEnvironment {
+            let conn = |ip: &str, port: u16| {
+                format!(
+                    "{{ ip: {ip}, port: {port}, internal_ip: {ip}, user: wfb, ssh_key_path: k }}"
+                )
+            };
+            serde_yaml::from_str(&format!(
+                "name: r640\ntitle: R640\nspec: null\nicon: null\nhosts:\n  - {{ name: a, app: {}, db: {}, wrkr: {} }}\n  - {{ name: b, app: {}, wrkr: {} }}\n",
+                conn("10.0.0.1", 22),
+                conn("10.0.0.1", 22),
+                conn("10.0.0.2", 22),
+                conn("10.0.0.1", second_app_port),
+                conn("10.0.0.3", 22),
+            ))
+            .unwrap()
+        };
+
+        // One set may put app and db on the same machine.
+        assert!(env(2222).validate_host_sets().is_ok());
+        assert!(matches!(
+            env(22).validate_host_sets(),
+            Err(crate::Error::OverlappingHostSets { first, second, .. }) if first == "a" && second == "b"
+        ));
+    }
 }
@@ -6,6 +6,22 @@ pub enum Error {
     YamlParse(#[from] serde_yaml::Error),
     #[error("Serialize error: {0}")]
     Serialize(#[from] serde_json::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Load profile '{0}' not found")]
+    UnknownLoadProfile(String),
+    #[error("Benchmark '{benchmark}' exists in several languages ({}); pick one", languages.join(", "))]
+    AmbiguousBenchmark {
+        benchmark: String,
+        languages: Vec<String>,
+    },
+    #[error("Environment '{environment}': host sets '{first}' and '{second}' share host {host}")]
+    OverlappingHostSets {
+        environment: String,
+        first: String,
+        second: String,
+        host: String,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
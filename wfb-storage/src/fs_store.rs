@@ -0,0 +1,765 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::benchmark::{Benchmark, BenchmarkManifest, BenchmarkTests};
+use crate::environment::Environment;
+use crate::error::Result;
+use crate::journal::JournalEntry;
+use crate::lang::Lang;
+use crate::logs::LogKind;
+use crate::percentiles::LatencyPercentiles;
+use crate::storage::{
+    BenchmarkResult, RawDataCache, ResultStore, RunEnvironmentManifest, RunManifest, StorageData,
+    insert_test_case,
+};
+use crate::testcase::{LatencyCurve, TestCaseRaw, TestCaseSummary};
+
+type LangData = HashMap<String, BenchmarkResult>;
+type EnvData = HashMap<String, LangData>;
+type RunData = HashMap<String, EnvData>;
+
+/// Filesystem backend: `<base>/<run>/<env>/<lang>/<bench>/{manifest.yaml,<test>.yaml,<test>_raw.jsonl,<test>_curve.json,<test>_percentiles.json}`.
+pub struct FsStore {
+    base_path: PathBuf,
+    data: RwLock<StorageData>,
+    runs: RwLock<HashMap<String, RunManifest>>,
+    raw_cache: RawDataCache,
+}
+
+impl FsStore {
+    pub fn new(base_path: impl Into<PathBuf>) -> Result<Self> {
+        let base_path = base_path.into();
+        let (data, runs) = Self::load_all(&base_path)?;
+        Ok(Self {
+            base_path,
+            data: RwLock::new(data),
+            runs: RwLock::new(runs),
+            raw_cache: RawDataCache::default(),
+        })
+    }
+
+    fn data_write(&self) -> RwLockWriteGuard<'_, StorageData> {
+        self.data.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn runs_write(&self) -> RwLockWriteGuard<'_, HashMap<String, RunManifest>> {
+        self.runs.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn load_all(base_path: &Path) -> Result<(StorageData, HashMap<String, RunManifest>)> {
+        let mut data = HashMap::new();
+        let mut runs = HashMap::new();
+        if !base_path.exists() {
+            return Ok((data, runs));
+        }
+
+        for (run_id, run_path) in Self::sub_dirs(base_path)? {
+            if let Some(manifest) = Self::load_run_manifest(&run_path) {
+                runs.insert(run_id.clone(), manifest);
+            }
+            data.insert(run_id, Self::load_run(&run_path)?);
+        }
+        Ok((data, runs))
+    }
+
+    fn sub_dirs(path: &Path) -> Result<Vec<(String, PathBuf)>> {
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                dirs.push((
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                ));
+            }
+        }
+        Ok(dirs)
+    }
+
+    fn load_run_manifest(run_path: &Path) -> Option<RunManifest> {
+        let file = fs::File::open(run_path.join("manifest.yaml")).ok()?;
+        serde_yaml::from_reader(file).ok()
+    }
+
+    fn load_run(run_path: &Path) -> Result<RunData> {
+        let mut run_data = HashMap::new();
+        for (environment, env_path) in Self::sub_dirs(run_path)? {
+            run_data.insert(environment, Self::load_env(&env_path)?);
+        }
+        Ok(run_data)
+    }
+
+    fn load_env(env_path: &Path) -> Result<EnvData> {
+        let mut env_data = HashMap::new();
+        for (language, lang_path) in Self::sub_dirs(env_path)? {
+            env_data.insert(language, Self::load_lang(&lang_path)?);
+        }
+        Ok(env_data)
+    }
+
+    fn load_lang(lang_path: &Path) -> Result<LangData> {
+        let mut lang_results = HashMap::new();
+        for (benchmark_name, benchmark_path) in Self::sub_dirs(lang_path)? {
+            if let Some(result) = Self::load_benchmark(&benchmark_path)? {
+                lang_results.insert(benchmark_name, result);
+            }
+        }
+        Ok(lang_results)
+    }
+
+    #[allow(clippy::collapsible_if)]
+    fn load_benchmark(benchmark_path: &Path) -> Result<Option<BenchmarkResult>> {
+        // Load manifest
+        let manifest_path = benchmark_path.join("manifest.yaml");
+        if !manifest_path.exists() {
+            return Ok(None);
+        }
+        let manifest_file = fs::File::open(&manifest_path)?;
+        let manifest: BenchmarkManifest = match serde_yaml::from_reader(manifest_file) {
+            Ok(m) => m,
+            Err(_) => return Ok(None),
+        };
+
+        let mut test_cases = HashMap::new();
+
+        // Load test case summaries; raw time-series are read on demand by `get_raw_data`.
+        if let Ok(entries) = fs::read_dir(benchmark_path) {
+            for file_entry in entries.flatten() {
+                let path = file_entry.path();
+                if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
+                    let file_stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+                    if file_stem == "manifest" {
+                        continue;
+                    }
+
+                    if extension == "yaml" {
+                        if let Ok(summary_file) = fs::File::open(&path) {
+                            if let Ok(summary) =
+                                serde_yaml::from_reader::<_, TestCaseSummary>(summary_file)
+                            {
+                                test_cases.insert(file_stem.to_string(), summary);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Some(BenchmarkResult {
+            manifest,
+            test_cases,
+        }))
+    }
+
+    /// Maps a changed path to the `run/env/lang/benchmark` prefix that has to be re-read.
+    ///
+    /// Files and removed entries above the benchmark level resolve to their parent directory,
+    /// except environment-level files such as the journal, which is read straight from disk
+    /// and needs no reload (`None`). An empty scope means the whole tree.
+    fn reload_scope(&self, path: &Path) -> Option<Vec<String>> {
+        let relative = path.strip_prefix(&self.base_path).unwrap_or(path);
+        let mut scope: Vec<String> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .take(4)
+            .collect();
+
+        if scope.len() == 3 && (scope[2] == "journal.jsonl" || self.scope_path(&scope).is_file()) {
+            return None;
+        }
+        if scope.len() < 4 && !self.scope_path(&scope).is_dir() {
+            scope.pop();
+        }
+        Some(scope)
+    }
+
+    fn scope_path(&self, scope: &[String]) -> PathBuf {
+        scope
+            .iter()
+            .fold(self.base_path.clone(), |path, part| path.join(part))
+    }
+
+    fn reload_subtree(&self, scope: &[String]) -> Result<()> {
+        let path = self.scope_path(scope);
+        let exists = path.is_dir();
+
+        match scope {
+            [run_id] => {
+                let manifest = Self::load_run_manifest(&path);
+                let run_data = exists.then(|| Self::load_run(&path)).transpose()?;
+
+                let mut data = self.data_write();
+                match run_data {
+                    Some(run_data) => data.insert(run_id.clone(), run_data),
+                    None => data.remove(run_id),
+                };
+                drop(data);
+
+                let mut runs = self.runs_write();
+                match manifest {
+                    Some(manifest) => runs.insert(run_id.clone(), manifest),
+                    None => runs.remove(run_id),
+                };
+            }
+            [run_id, environment] => {
+                let env_data = exists.then(|| Self::load_env(&path)).transpose()?;
+
+                let mut data = self.data_write();
+                match env_data {
+                    Some(env_data) => {
+                        data.entry(run_id.clone())
+                            .or_default()
+                            .insert(environment.clone(), env_data);
+                    }
+                    None => {
+                        if let Some(run_data) = data.get_mut(run_id) {
+                            run_data.remove(environment);
+                        }
+                    }
+                }
+            }
+            [run_id, environment, language] => {
+                let lang_data = exists.then(|| Self::load_lang(&path)).transpose()?;
+
+                let mut data = self.data_write();
+                match lang_data {
+                    Some(lang_data) => {
+                        data.entry(run_id.clone())
+                            .or_default()
+                            .entry(environment.clone())
+                            .or_default()
+                            .insert(language.clone(), lang_data);
+                    }
+                    None => {
+                        if let Some(env_data) = data
+                            .get_mut(run_id)
+                            .and_then(|run_data| run_data.get_mut(environment))
+                        {
+                            env_data.remove(language);
+                        }
+                    }
+                }
+            }
+            [run_id, environment, language, benchmark] => {
+                let result = if exists {
+                    Self::load_benchmark(&path)?
+                } else {
+                    None
+                };
+
+                let mut data = self.data_write();
+                match result {
+                    Some(result) => {
+                        data.entry(run_id.clone())
+                            .or_default()
+                            .entry(environment.clone())
+                            .or_default()
+                            .entry(language.clone())
+                            .or_default()
+                            .insert(benchmark.clone(), result);
+                    }
+                    None => {
+                        if let Some(lang_data) = data
+                            .get_mut(run_id)
+                            .and_then(|run_data| run_data.get_mut(environment))
+                            .and_then(|env_data| env_data.get_mut(language))
+                        {
+                            lang_data.remove(benchmark);
+                        }
+                    }
+                }
+            }
+            _ => return self.reload(),
+        }
+
+        self.raw_cache.invalidate_prefix(scope);
+        Ok(())
+    }
+
+    fn get_benchmark_path(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+    ) -> PathBuf {
+        self.base_path
+            .join(run_id)
+            .join(environment.name())
+            .join(&language.name)
+            .join(&benchmark.name)
+    }
+
+    /// Saved iterations of a repeated test, ordered by iteration number.
+    pub(crate) fn iterations(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Vec<(u32, TestCaseSummary, Vec<TestCaseRaw>)> {
+        let iterations_path = self
+            .base_path
+            .join(run_id)
+            .join(environment)
+            .join(language)
+            .join(benchmark)
+            .join("iterations");
+        let Ok(dirs) = Self::sub_dirs(&iterations_path) else {
+            return Vec::new();
+        };
+        let mut iterations: Vec<_> = dirs
+            .into_iter()
+            .filter_map(|(iteration, path)| {
+                let iteration = iteration.parse().ok()?;
+                let file = fs::File::open(path.join(format!("{}.yaml", testcase))).ok()?;
+                let summary = serde_yaml::from_reader(file).ok()?;
+                let raw = Self::read_raw(&path.join(format!("{}_raw.jsonl", testcase)))?;
+                Some((iteration, summary, raw))
+            })
+            .collect();
+        iterations.sort_by_key(|(iteration, _, _)| *iteration);
+        iterations
+    }
+
+    fn read_raw(path: &Path) -> Option<Vec<TestCaseRaw>> {
+        let file = fs::File::open(path).ok()?;
+        let reader = BufReader::new(file);
+        let mut results = Vec::new();
+        for line in reader.lines().map_while(|l| l.ok()) {
+            if line.is_empty() {
+                continue;
+            }
+            if let Ok(item) = serde_json::from_str::<TestCaseRaw>(&line) {
+                results.push(item);
+            }
+        }
+        Some(results)
+    }
+
+    fn write_test_case(
+        dir: &Path,
+        testcase: BenchmarkTests,
+        summary: &TestCaseSummary,
+        raw_data: &[TestCaseRaw],
+    ) -> Result<()> {
+        // Save summary
+        let summary_path = dir.join(format!("{}.yaml", testcase));
+        let summary_file = fs::File::create(&summary_path)?;
+        serde_yaml::to_writer(summary_file, summary)?;
+
+        // Save raw data
+        let raw_path = dir.join(format!("{}_raw.jsonl", testcase));
+        let mut raw_file = fs::File::create(&raw_path)?;
+        for item in raw_data {
+            serde_json::to_writer(&mut raw_file, item)?;
+            raw_file.write_all(b"\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ResultStore for FsStore {
+    fn data_read(&self) -> RwLockReadGuard<'_, StorageData> {
+        self.data.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn runs(&self) -> RwLockReadGuard<'_, HashMap<String, RunManifest>> {
+        self.runs.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn save_benchmark_result(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        manifest: &BenchmarkManifest,
+        summary: &TestCaseSummary,
+        raw_data: &[TestCaseRaw],
+    ) -> Result<()> {
+        // Update memory
+        let manifest = insert_test_case(
+            &mut self.data_write(),
+            run_id,
+            environment.name(),
+            &language.name,
+            &benchmark.name,
+            manifest,
+            &testcase.to_string(),
+            summary,
+        )
+        .manifest
+        .clone();
+
+        // Save to disk
+        let benchmark_path = self.get_benchmark_path(run_id, environment, language, benchmark);
+        fs::create_dir_all(&benchmark_path)?;
+
+        // Save run manifest if it doesn't exist
+        let run_path = self.base_path.join(run_id);
+        let run_manifest_path = run_path.join("manifest.yaml");
+        if !run_manifest_path.exists() {
+            let manifest = RunManifest::new();
+            let file = fs::File::create(&run_manifest_path)?;
+            serde_yaml::to_writer(file, &manifest)?;
+
+            // Update memory
+            self.runs_write().insert(run_id.to_string(), manifest);
+        }
+
+        // Save manifest, merged with the load profiles and hosts of previously saved test cases
+        let manifest_path = benchmark_path.join("manifest.yaml");
+        let manifest_file = fs::File::create(&manifest_path)?;
+        serde_yaml::to_writer(manifest_file, &manifest)?;
+
+        Self::write_test_case(&benchmark_path, testcase, summary, raw_data)?;
+        self.raw_cache.invalidate(
+            run_id,
+            environment.name(),
+            &language.name,
+            &benchmark.name,
+            &testcase.to_string(),
+        );
+
+        Ok(())
+    }
+
+    /// Iterations are written under `<benchmark>/iterations/<iteration>/`.
+    fn save_iteration_result(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        iteration: u32,
+        summary: &TestCaseSummary,
+        raw_data: &[TestCaseRaw],
+    ) -> Result<()> {
+        let iteration_path = self
+            .get_benchmark_path(run_id, environment, language, benchmark)
+            .join("iterations")
+            .join(iteration.to_string());
+        fs::create_dir_all(&iteration_path)?;
+
+        Self::write_test_case(&iteration_path, testcase, summary, raw_data)
+    }
+
+    fn get_raw_data(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<Arc<Vec<TestCaseRaw>>> {
+        self.raw_cache
+            .get_or_load(run_id, environment, language, benchmark, testcase, || {
+                let path = self
+                    .base_path
+                    .join(run_id)
+                    .join(environment)
+                    .join(language)
+                    .join(benchmark)
+                    .join(format!("{}_raw.jsonl", testcase));
+                Self::read_raw(&path)
+            })
+    }
+
+    /// Curves are written as `<benchmark>/<test>_curve.json`.
+    fn save_latency_curve(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        curve: &LatencyCurve,
+    ) -> Result<()> {
+        let benchmark_path = self.get_benchmark_path(run_id, environment, language, benchmark);
+        fs::create_dir_all(&benchmark_path)?;
+
+        let curve_file = fs::File::create(benchmark_path.join(format!("{}_curve.json", testcase)))?;
+        serde_json::to_writer(curve_file, curve)?;
+        Ok(())
+    }
+
+    fn get_latency_curve(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<LatencyCurve> {
+        let path = self
+            .base_path
+            .join(run_id)
+            .join(environment)
+            .join(language)
+            .join(benchmark)
+            .join(format!("{}_curve.json", testcase));
+        let file = fs::File::open(path).ok()?;
+        serde_json::from_reader(file).ok()
+    }
+
+    /// Percentile summaries are written as `<benchmark>/<test>_percentiles.json`.
+    fn save_latency_percentiles(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        percentiles: &LatencyPercentiles,
+    ) -> Result<()> {
+        let benchmark_path = self.get_benchmark_path(run_id, environment, language, benchmark);
+        fs::create_dir_all(&benchmark_path)?;
+
+        let percentiles_file =
+            fs::File::create(benchmark_path.join(format!("{}_percentiles.json", testcase)))?;
+        serde_json::to_writer(percentiles_file, percentiles)?;
+        Ok(())
+    }
+
+    fn get_latency_percentiles(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<LatencyPercentiles> {
+        let path = self
+            .base_path
+            .join(run_id)
+            .join(environment)
+            .join(language)
+            .join(benchmark)
+            .join(format!("{}_percentiles.json", testcase));
+        let file = fs::File::open(path).ok()?;
+        serde_json::from_reader(file).ok()
+    }
+
+    /// Logs are written as `<benchmark>/<test>_<kind>.log`, next to the raw JSONL.
+    fn save_test_log(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        kind: LogKind,
+        log: &str,
+    ) -> Result<()> {
+        let benchmark_path = self.get_benchmark_path(run_id, environment, language, benchmark);
+        fs::create_dir_all(&benchmark_path)?;
+
+        fs::write(
+            benchmark_path.join(format!("{}_{}.log", testcase, kind)),
+            log,
+        )?;
+        Ok(())
+    }
+
+    fn get_test_log(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+        kind: LogKind,
+    ) -> Option<String> {
+        let path = self
+            .base_path
+            .join(run_id)
+            .join(environment)
+            .join(language)
+            .join(benchmark)
+            .join(format!("{}_{}.log", testcase, kind));
+        fs::read_to_string(path).ok()
+    }
+
+    fn update_run_manifest(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        update: &dyn Fn(&mut RunEnvironmentManifest),
+    ) -> Result<()> {
+        // Holding the lock serialises concurrent runners of this process.
+        let mut runs = self.runs_write();
+        let run_path = self.base_path.join(run_id);
+        fs::create_dir_all(&run_path)?;
+
+        let mut manifest = runs
+            .get(run_id)
+            .cloned()
+            .or_else(|| Self::load_run_manifest(&run_path))
+            .unwrap_or_else(RunManifest::new);
+        update(
+            manifest
+                .environments
+                .entry(environment.name().to_string())
+                .or_default(),
+        );
+
+        let file = fs::File::create(run_path.join("manifest.yaml"))?;
+        serde_yaml::to_writer(file, &manifest)?;
+        runs.insert(run_id.to_string(), manifest);
+        Ok(())
+    }
+
+    /// The journal is appended to `<run>/<env>/journal.jsonl`.
+    fn append_journal(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        entry: &JournalEntry,
+    ) -> Result<()> {
+        let env_path = self.base_path.join(run_id).join(environment.name());
+        fs::create_dir_all(&env_path)?;
+
+        let mut journal_file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(env_path.join("journal.jsonl"))?;
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        journal_file.write_all(&line)?;
+        Ok(())
+    }
+
+    fn get_journal(&self, run_id: &str, environment: &str) -> Vec<JournalEntry> {
+        let path = self
+            .base_path
+            .join(run_id)
+            .join(environment)
+            .join("journal.jsonl");
+        let Ok(file) = fs::File::open(path) else {
+            return Vec::new();
+        };
+        BufReader::new(file)
+            .lines()
+            .map_while(|l| l.ok())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect()
+    }
+
+    fn reload_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        let scopes: BTreeSet<Vec<String>> =
+            paths.iter().filter_map(|p| self.reload_scope(p)).collect();
+        if scopes.iter().any(|scope| scope.is_empty()) {
+            return self.reload();
+        }
+
+        // Sorted order puts a directory before everything below it.
+        let mut reloaded: Vec<&Vec<String>> = Vec::new();
+        for scope in &scopes {
+            if reloaded.iter().any(|parent| scope.starts_with(parent)) {
+                continue;
+            }
+            self.reload_subtree(scope)?;
+            reloaded.push(scope);
+        }
+        Ok(())
+    }
+
+    fn reload(&self) -> Result<()> {
+        let (new_data, new_runs) = Self::load_all(&self.base_path)?;
+
+        let mut data = self.data_write();
+        *data = new_data;
+
+        let mut runs = self.runs_write();
+        *runs = new_runs;
+
+        self.raw_cache.clear();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FsStore;
+    use crate::test_support::TempDir;
+    use crate::{Environment, JournalEntry, ResultStore};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn reload_paths_updates_only_the_changed_benchmark() {
+        let temp = TempDir::new("wfb-fs-store");
+        let base = temp.path();
+        let manifest =
+            "language_version: '1'\nframework_version: '1'\ntags: {}\ndatabase: null\npath: x\n";
+        let write_bench = |name: &str| {
+            let dir = base.join("1/local/rust").join(name);
+            fs::create_dir_all(&dir).expect("create dir");
+            fs::write(dir.join("manifest.yaml"), manifest).expect("write manifest");
+        };
+
+        write_bench("axum");
+        let store = FsStore::new(base).expect("open");
+        assert_eq!(store.data_read()["1"]["local"]["rust"].len(), 1);
+
+        write_bench("actix");
+        store
+            .reload_paths(&[PathBuf::from("1/local/rust/actix/manifest.yaml")])
+            .expect("reload");
+        assert!(store.data_read()["1"]["local"]["rust"].contains_key("actix"));
+
+        fs::remove_dir_all(base.join("1/local/rust/axum")).expect("remove");
+        store
+            .reload_paths(&[PathBuf::from("1/local/rust/axum")])
+            .expect("reload");
+        assert!(!store.data_read()["1"]["local"]["rust"].contains_key("axum"));
+    }
+
+    #[test]
+    fn journal_appends_do_not_reload_results() {
+        let temp = TempDir::new("wfb-fs-journal");
+        let base = temp.path();
+        let manifest =
+            "language_version: '1'\nframework_version: '1'\ntags: {}\ndatabase: null\npath: x\n";
+        let write_bench = |name: &str| {
+            let dir = base.join("1/local/rust").join(name);
+            fs::create_dir_all(&dir).expect("create dir");
+            fs::write(dir.join("manifest.yaml"), manifest).expect("write manifest");
+        };
+
+        write_bench("axum");
+        let store = FsStore::new(base).expect("open");
+        let environment: Environment = serde_yaml::from_str(
+            "executor: local\nname: local\ntitle: Local\nspec: null\nicon: null",
+        )
+        .expect("environment");
+
+        // Written behind the store's back: only a reload of the benchmark dirs would see it.
+        write_bench("actix");
+        store
+            .append_journal(
+                "1",
+                &environment,
+                &JournalEntry::running("rust", "axum", "plaintext"),
+            )
+            .expect("append");
+        store
+            .reload_paths(&[PathBuf::from("1/local/journal.jsonl")])
+            .expect("reload");
+
+        assert!(!store.data_read()["1"]["local"]["rust"].contains_key("actix"));
+        assert_eq!(store.get_journal("1", "local").len(), 1);
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::storage::{RunManifest, StorageData};
+
+/// Result of one benchmark test in one run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryPoint {
+    pub run_id: String,
+    /// `None` for runs without a manifest.
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub language: String,
+    pub framework_version: String,
+    /// Version measured in the previous point, when it differs from `framework_version`.
+    pub previous_framework_version: Option<String>,
+    pub requests_per_sec: f64,
+    pub latency_p99: u64,
+    pub memory_usage_bytes: u64,
+    pub total_errors: u64,
+}
+
+/// Collects `benchmark`'s `test` results in `environment` across all runs, oldest first.
+///
+/// Runs are ordered by [`RunManifest::created_at`]; runs without a manifest come first,
+/// ordered by id. `language` picks between benchmarks that share a name across languages;
+/// without it, a benchmark whose `test` was recorded in more than one language is an
+/// [`Error::AmbiguousBenchmark`].
+pub fn benchmark_history(
+    data: &StorageData,
+    runs: &HashMap<String, RunManifest>,
+    environment: &str,
+    language: Option<&str>,
+    benchmark: &str,
+    test: &str,
+) -> Result<Vec<HistoryPoint>> {
+    // Every run and language that recorded `test` for `benchmark`.
+    let results = data.iter().flat_map(|(run_id, envs)| {
+        envs.get(environment)
+            .into_iter()
+            .flatten()
+            .filter(|(lang, _)| language.is_none_or(|l| l == lang.as_str()))
+            .filter_map(move |(lang, benches)| {
+                let result = benches.get(benchmark)?;
+                let summary = result.test_cases.get(test)?;
+                Some((run_id, lang, result, summary))
+            })
+    });
+
+    if language.is_none() {
+        let languages: BTreeSet<&String> = results.clone().map(|(_, lang, _, _)| lang).collect();
+        if languages.len() > 1 {
+            return Err(Error::AmbiguousBenchmark {
+                benchmark: benchmark.to_string(),
+                languages: languages.into_iter().cloned().collect(),
+            });
+        }
+    }
+
+    let mut points: Vec<HistoryPoint> = results
+        .map(|(run_id, lang, result, summary)| HistoryPoint {
+            run_id: run_id.clone(),
+            created_at: runs.get(run_id).map(|m| m.created_at),
+            language: lang.clone(),
+            framework_version: result.manifest.framework_version.clone(),
+            previous_framework_version: None,
+            requests_per_sec: summary.requests_per_sec,
+            latency_p99: summary.latency_p99,
+            memory_usage_bytes: summary.memory_usage_bytes,
+            total_errors: summary.total_errors,
+        })
+        .collect();
+
+    points.sort_by(|a, b| (a.created_at, &a.run_id).cmp(&(b.created_at, &b.run_id)));
+
+    for i in 1..points.len() {
+        if points[i].framework_version != points[i - 1].framework_version {
+            points[i].previous_framework_version = Some(points[i - 1].framework_version.clone());
+        }
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::benchmark_history;
+    use crate::benchmark::BenchmarkManifest;
+    use crate::error::Error;
+    use crate::storage::{BenchmarkResult, RunManifest, StorageData};
+    use crate::testcase::TestCaseSummary;
+
+    fn result(version: &str, rps: f64) -> BenchmarkResult {
+        BenchmarkResult {
+            manifest: BenchmarkManifest {
+                framework_version: version.to_string(),
+                ..Default::default()
+            },
+            test_cases: HashMap::from([(
+                "plaintext".to_string(),
+                TestCaseSummary {
+                    requests_per_sec: rps,
+                    ..Default::default()
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn history_is_ordered_by_run_date_and_marks_version_changes() {
+        let mut data = StorageData::new();
+        let mut runs = HashMap::new();
+        for (run_id, day, version, rps) in [
+            ("b", 3, "0.8.0", 120.0),
+            ("c", 1, "0.7.0", 100.0),
+            ("a", 2, "0.7.0", 110.0),
+        ] {
+            data.entry(run_id.to_string())
+                .or_default()
+                .entry("local".to_string())
+                .or_default()
+                .entry("Rust".to_string())
+                .or_default()
+                .insert("axum".to_string(), result(version, rps));
+            runs.insert(
+                run_id.to_string(),
+                RunManifest {
+                    created_at: Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let history =
+            benchmark_history(&data, &runs, "local", None, "axum", "plaintext").expect("history");
+
+        let ids: Vec<&str> = history.iter().map(|p| p.run_id.as_str()).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+        assert_eq!(history[1].previous_framework_version, None);
+        assert_eq!(
+            history[2].previous_framework_version.as_deref(),
+            Some("0.7.0")
+        );
+        assert!(
+            benchmark_history(&data, &runs, "local", Some("Go"), "axum", "plaintext")
+                .expect("history")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn history_needs_a_language_when_the_test_ran_in_several() {
+        let mut data = StorageData::new();
+        let mut insert = |run_id: &str, lang: &str, result: BenchmarkResult| {
+            data.entry(run_id.to_string())
+                .or_default()
+                .entry("local".to_string())
+                .or_default()
+                .entry(lang.to_string())
+                .or_default()
+                .insert("app".to_string(), result);
+        };
+        insert("a", "Rust", result("1.0", 100.0));
+        let mut other_test = result("2.0", 50.0);
+        other_test.test_cases =
+            HashMap::from([("json_aggregate".to_string(), TestCaseSummary::default())]);
+        insert("b", "Go", other_test);
+        let runs = HashMap::new();
+
+        // Go's `app` never ran plaintext, so the name is not ambiguous for it.
+        let history =
+            benchmark_history(&data, &runs, "local", None, "app", "plaintext").expect("history");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].language, "Rust");
+
+        data.get_mut("b")
+            .and_then(|envs| envs.get_mut("local"))
+            .and_then(|langs| langs.get_mut("Go"))
+            .and_then(|benches| benches.get_mut("app"))
+            .expect("go app")
+            .test_cases
+            .insert("plaintext".to_string(), TestCaseSummary::default());
+        let err = benchmark_history(&data, &runs, "local", None, "app", "plaintext")
+            .expect_err("ambiguous");
+        assert!(matches!(
+            err,
+            Error::AmbiguousBenchmark { languages, .. } if languages == ["Go", "Rust"]
+        ));
+
+        let history = benchmark_history(&data, &runs, "local", Some("Go"), "app", "plaintext")
+            .expect("history");
+        let ids: Vec<&str> = history.iter().map(|p| p.run_id.as_str()).collect();
+        assert_eq!(ids, ["b"]);
+    }
+}
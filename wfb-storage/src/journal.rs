@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of one benchmark test attempt.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttemptState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One record of the run journal.
+///
+/// The runner appends a `Running` entry when it starts a test and a `Succeeded` or
+/// `Failed` entry when it is done, so a run that was interrupted ends with a `Running` entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub language: String,
+    pub benchmark: String,
+    pub test: String,
+    pub state: AttemptState,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub error: Option<String>,
+    /// Last lines of the application log, captured on failure.
+    pub app_log_tail: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn running(language: &str, benchmark: &str, test: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            benchmark: benchmark.to_string(),
+            test: test.to_string(),
+            state: AttemptState::Running,
+            started_at: chrono::Utc::now(),
+            finished_at: None,
+            error: None,
+            app_log_tail: None,
+        }
+    }
+
+    pub fn succeeded(self) -> Self {
+        Self {
+            state: AttemptState::Succeeded,
+            finished_at: Some(chrono::Utc::now()),
+            ..self
+        }
+    }
+
+    pub fn failed(self, error: String, app_log_tail: Option<String>) -> Self {
+        Self {
+            state: AttemptState::Failed,
+            finished_at: Some(chrono::Utc::now()),
+            error: Some(error),
+            app_log_tail,
+            ..self
+        }
+    }
+}
+
+/// Reduces a journal to the latest attempt of every language/benchmark/test, in journal order.
+///
+/// A latest attempt that is still `Running` is either in progress or was interrupted.
+pub fn latest_attempts(entries: &[JournalEntry]) -> Vec<JournalEntry> {
+    let mut index: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    let mut latest: Vec<JournalEntry> = Vec::new();
+    for entry in entries {
+        let key = (
+            entry.language.as_str(),
+            entry.benchmark.as_str(),
+            entry.test.as_str(),
+        );
+        match index.get(&key) {
+            Some(&i) => latest[i] = entry.clone(),
+            None => {
+                index.insert(key, latest.len());
+                latest.push(entry.clone());
+            }
+        }
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AttemptState, JournalEntry, latest_attempts};
+
+    #[test]
+    fn latest_attempt_of_each_test_wins() {
+        let journal = [
+            JournalEntry::running("Rust", "axum", "plaintext"),
+            JournalEntry::running("Rust", "axum", "plaintext").failed("boom".to_string(), None),
+            JournalEntry::running("Go", "gin", "plaintext"),
+            JournalEntry::running("Rust", "axum", "plaintext").succeeded(),
+            JournalEntry::running("Rust", "axum", "json"),
+        ];
+
+        let latest = latest_attempts(&journal);
+
+        assert_eq!(latest.len(), 3);
+        assert_eq!(latest[0].state, AttemptState::Succeeded);
+        assert_eq!(latest[0].error, None);
+        assert!(latest[0].finished_at.is_some());
+        assert_eq!(latest[1].benchmark, "gin");
+        assert_eq!(latest[1].state, AttemptState::Running);
+        assert_eq!(latest[2].test, "json");
+    }
+}
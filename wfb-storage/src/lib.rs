@@ -1,17 +1,37 @@
 mod benchmark;
+mod compare;
 mod config;
 mod environment;
 mod error;
 mod framework;
+mod fs_store;
+mod history;
+mod journal;
 mod lang;
+mod load_profile;
+mod logs;
+mod percentiles;
+mod sqlite_store;
+mod stats;
 mod storage;
+#[cfg(test)]
+mod test_support;
 mod testcase;
 
 pub use benchmark::*;
+pub use compare::*;
 pub use config::*;
 pub use environment::*;
 pub use error::*;
 pub use framework::*;
+pub use fs_store::*;
+pub use history::*;
+pub use journal::*;
 pub use lang::*;
+pub use load_profile::*;
+pub use logs::*;
+pub use percentiles::*;
+pub use sqlite_store::*;
+pub use stats::*;
 pub use storage::*;
 pub use testcase::*;
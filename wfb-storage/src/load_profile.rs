@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::benchmark::BenchmarkTests;
+
+/// Name of the profile applied to every environment and benchmark, when defined.
+pub const DEFAULT_LOAD_PROFILE: &str = "default";
+
+/// Load generator executor used for the measured phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadExecutor {
+    /// Ramp VUs up to the target along `stages` (or the default 4/5 ramp + 1/5 hold).
+    #[default]
+    RampingVus,
+    /// Hold `max_vus` for the whole duration.
+    ConstantVus,
+    /// Start iterations at a fixed `rate` per second, using up to `max_vus`.
+    ConstantArrivalRate,
+}
+
+impl fmt::Display for LoadExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadExecutor::RampingVus => write!(f, "ramping-vus"),
+            LoadExecutor::ConstantVus => write!(f, "constant-vus"),
+            LoadExecutor::ConstantArrivalRate => write!(f, "constant-arrival-rate"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadStage {
+    pub duration_secs: u64,
+    pub target: u64,
+}
+
+/// Load settings where every field is optional; unset fields inherit from the layer below.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup_duration_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warmup_vus: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_vus: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executor: Option<LoadExecutor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<LoadStage>>,
+    /// Iterations per second for [`LoadExecutor::ConstantArrivalRate`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<u64>,
+    /// Offered loads (iterations per second) of the constant-arrival-rate sweep that runs
+    /// after the measured phase and records a latency-vs-throughput curve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rates: Option<Vec<u64>>,
+    /// Duration of each sweep step.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_duration_secs: Option<u64>,
+    /// p99 latency objective for the "RPS @ SLO" metric, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo_p99_ms: Option<f64>,
+}
+
+impl LoadSettings {
+    /// Overrides fields of `self` with the ones set in `other`.
+    pub fn merge(&mut self, other: &LoadSettings) {
+        macro_rules! take {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field.clone();
+                })*
+            };
+        }
+        take!(
+            duration_secs,
+            warmup_duration_secs,
+            warmup_vus,
+            max_vus,
+            executor,
+            stages,
+            rate,
+            rates,
+            rate_duration_secs,
+            slo_p99_ms
+        );
+    }
+}
+
+/// `type: load_profile` config document.
+///
+/// Top-level settings apply to every test; `tests` overrides them per test case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadProfile {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: LoadSettings,
+    #[serde(default)]
+    pub tests: HashMap<BenchmarkTests, LoadSettings>,
+}
+
+impl LoadProfile {
+    pub fn settings_for(&self, test: BenchmarkTests) -> LoadSettings {
+        let mut settings = self.settings.clone();
+        if let Some(overrides) = self.tests.get(&test) {
+            settings.merge(overrides);
+        }
+        settings
+    }
+}
+
+/// Load settings actually used for a test, recorded alongside its results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedLoadProfile {
+    /// Profiles applied on top of the built-in defaults, lowest precedence first.
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    pub duration_secs: u64,
+    pub warmup_duration_secs: u64,
+    pub warmup_vus: u64,
+    pub max_vus: u64,
+    pub executor: LoadExecutor,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<LoadStage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<u64>,
+    /// Sweep rates; empty when no latency curve was recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rates: Vec<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_duration_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo_p99_ms: Option<f64>,
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Which container's output a stored test log holds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LogKind {
+    /// Application container (stdout and stderr).
+    App,
+    /// Database container (stdout and stderr).
+    Db,
+    /// Load generator stderr; its stdout is the raw JSONL.
+    Wrkr,
+}
+
+impl LogKind {
+    pub const ALL: [LogKind; 3] = [LogKind::App, LogKind::Db, LogKind::Wrkr];
+}
+
+impl fmt::Display for LogKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogKind::App => write!(f, "app"),
+            LogKind::Db => write!(f, "db"),
+            LogKind::Wrkr => write!(f, "wrkr"),
+        }
+    }
+}
+
+impl FromStr for LogKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "app" => Ok(LogKind::App),
+            "db" => Ok(LogKind::Db),
+            "wrkr" => Ok(LogKind::Wrkr),
+            other => Err(format!(
+                "unknown log kind '{}', expected 'app', 'db' or 'wrkr'",
+                other
+            )),
+        }
+    }
+}
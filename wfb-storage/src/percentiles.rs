@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// One percentile of a latency distribution.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct LatencyPercentile {
+    /// Percentile in `0.0..=100.0`; `100.0` is the maximum.
+    pub percentile: f64,
+    /// Latency in microseconds.
+    pub value: u64,
+}
+
+/// Latency percentile summary of a test, as reported by wrkr at the end of the run and
+/// ordered by increasing percentile.
+///
+/// wrkr does not export its histogram buckets, so this only holds the percentiles it
+/// reports; values between them are not known and are never made up.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LatencyPercentiles {
+    /// Requests the summary covers.
+    pub total_count: u64,
+    pub points: Vec<LatencyPercentile>,
+}
+
+impl LatencyPercentiles {
+    /// Builds the summary from `(percentile, value in microseconds)` pairs, dropping
+    /// percentiles outside `0..=100`.
+    pub fn from_percentiles(
+        total_count: u64,
+        percentiles: impl IntoIterator<Item = (f64, u64)>,
+    ) -> Self {
+        let mut points: Vec<LatencyPercentile> = percentiles
+            .into_iter()
+            .filter(|(percentile, _)| (0.0..=100.0).contains(percentile))
+            .map(|(percentile, value)| LatencyPercentile { percentile, value })
+            .collect();
+        points.sort_by(|a, b| a.percentile.total_cmp(&b.percentile));
+
+        Self {
+            total_count,
+            points,
+        }
+    }
+
+    /// Value recorded for exactly `percentile`, if wrkr reported it.
+    pub fn value_at(&self, percentile: f64) -> Option<u64> {
+        self.points
+            .iter()
+            .find(|p| (p.percentile - percentile).abs() < 1e-9)
+            .map(|p| p.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LatencyPercentiles;
+
+    #[test]
+    fn percentiles_are_ordered_and_only_recorded_values_are_returned() {
+        let percentiles = LatencyPercentiles::from_percentiles(
+            100,
+            [(99.0, 2_000), (50.0, 1_000), (100.0, 50_000), (999.0, 7)],
+        );
+
+        assert_eq!(percentiles.total_count, 100);
+        assert_eq!(percentiles.points.len(), 3);
+        assert_eq!(percentiles.points[1].percentile, 99.0);
+        assert_eq!(percentiles.value_at(50.0), Some(1_000));
+        assert_eq!(percentiles.value_at(100.0), Some(50_000));
+        // p75 was not reported, so it is not borrowed from p99.
+        assert_eq!(percentiles.value_at(75.0), None);
+        assert_eq!(percentiles.value_at(99.9), None);
+    }
+}
@@ -0,0 +1,891 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+
+use crate::benchmark::{Benchmark, BenchmarkManifest, BenchmarkTests};
+use crate::environment::Environment;
+use crate::error::Result;
+use crate::fs_store::FsStore;
+use crate::journal::JournalEntry;
+use crate::lang::Lang;
+use crate::logs::LogKind;
+use crate::percentiles::LatencyPercentiles;
+use crate::storage::{
+    RawDataCache, ResultStore, RunEnvironmentManifest, RunManifest, StorageData, insert_test_case,
+};
+use crate::testcase::{LatencyCurve, TestCaseRaw, TestCaseSummary};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    run_id TEXT PRIMARY KEY,
+    manifest TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS benchmarks (
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    language TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    manifest TEXT NOT NULL,
+    PRIMARY KEY (run_id, environment, language, benchmark)
+);
+CREATE TABLE IF NOT EXISTS test_cases (
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    language TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    test_case TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    raw TEXT NOT NULL,
+    PRIMARY KEY (run_id, environment, language, benchmark, test_case)
+);
+CREATE TABLE IF NOT EXISTS iterations (
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    language TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    test_case TEXT NOT NULL,
+    iteration INTEGER NOT NULL,
+    summary TEXT NOT NULL,
+    raw TEXT NOT NULL,
+    PRIMARY KEY (run_id, environment, language, benchmark, test_case, iteration)
+);
+CREATE TABLE IF NOT EXISTS latency_percentiles (
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    language TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    test_case TEXT NOT NULL,
+    percentiles TEXT NOT NULL,
+    PRIMARY KEY (run_id, environment, language, benchmark, test_case)
+);
+CREATE TABLE IF NOT EXISTS latency_curves (
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    language TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    test_case TEXT NOT NULL,
+    curve TEXT NOT NULL,
+    PRIMARY KEY (run_id, environment, language, benchmark, test_case)
+);
+CREATE TABLE IF NOT EXISTS test_logs (
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    language TEXT NOT NULL,
+    benchmark TEXT NOT NULL,
+    test_case TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    log TEXT NOT NULL,
+    PRIMARY KEY (run_id, environment, language, benchmark, test_case, kind)
+);
+CREATE TABLE IF NOT EXISTS journal (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL,
+    environment TEXT NOT NULL,
+    entry TEXT NOT NULL
+);
+";
+
+/// Embedded SQLite backend.
+///
+/// Only manifests and summaries are held in memory; raw time-series stay in the
+/// database and are read per request.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+    /// Next to the database file; `None` in memory.
+    run_finished_path: Option<PathBuf>,
+    data: RwLock<StorageData>,
+    runs: RwLock<HashMap<String, RunManifest>>,
+    raw_cache: RawDataCache,
+}
+
+impl SqliteStore {
+    pub const FILE_NAME: &'static str = "results.sqlite";
+    /// Rewritten with the run id whenever a run finishes.
+    pub const RUN_FINISHED_FILE: &'static str = "results.sqlite.finished";
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let run_finished_path = path.with_file_name(Self::RUN_FINISHED_FILE);
+        Self::from_connection(Connection::open(path)?, Some(run_finished_path))
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?, None)
+    }
+
+    /// Whether `file_name` is the database or one of the files SQLite keeps next to it.
+    pub fn is_database_file(file_name: &str) -> bool {
+        file_name
+            .strip_prefix(Self::FILE_NAME)
+            .is_some_and(|suffix| matches!(suffix, "" | "-wal" | "-shm" | "-journal"))
+    }
+
+    fn from_connection(conn: Connection, run_finished_path: Option<PathBuf>) -> Result<Self> {
+        // The runner writes while the server reads the same file.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)?;
+
+        let (data, runs) = Self::load_all(&conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            run_finished_path,
+            data: RwLock::new(data),
+            runs: RwLock::new(runs),
+            raw_cache: RawDataCache::default(),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn data_write(&self) -> RwLockWriteGuard<'_, StorageData> {
+        self.data.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn runs_write(&self) -> RwLockWriteGuard<'_, HashMap<String, RunManifest>> {
+        self.runs.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn load_all(conn: &Connection) -> Result<(StorageData, HashMap<String, RunManifest>)> {
+        let mut runs = HashMap::new();
+        let mut stmt = conn.prepare("SELECT run_id, manifest FROM runs")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (run_id, manifest) = row?;
+            if let Ok(manifest) = serde_json::from_str::<RunManifest>(&manifest) {
+                runs.insert(run_id, manifest);
+            }
+        }
+
+        let mut manifests = HashMap::new();
+        let mut stmt = conn
+            .prepare("SELECT run_id, environment, language, benchmark, manifest FROM benchmarks")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                (
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ),
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (key, manifest) = row?;
+            if let Ok(manifest) = serde_json::from_str::<BenchmarkManifest>(&manifest) {
+                manifests.insert(key, manifest);
+            }
+        }
+
+        let mut data = StorageData::new();
+        let mut stmt = conn.prepare(
+            "SELECT run_id, environment, language, benchmark, test_case, summary FROM test_cases",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                (
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ),
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+        for row in rows {
+            let (key, test_case, summary) = row?;
+            let Some(manifest) = manifests.get(&key) else {
+                continue;
+            };
+            let Ok(summary) = serde_json::from_str::<TestCaseSummary>(&summary) else {
+                continue;
+            };
+            let (run_id, environment, language, benchmark) = &key;
+            insert_test_case(
+                &mut data,
+                run_id,
+                environment,
+                language,
+                benchmark,
+                manifest,
+                &test_case,
+                &summary,
+            );
+        }
+
+        Ok((data, runs))
+    }
+}
+
+impl SqliteStore {
+    /// Copies every run of a filesystem store into the database, replacing what it already
+    /// holds for those runs. Returns the number of imported test cases.
+    pub fn import_fs(&self, source: &FsStore) -> Result<usize> {
+        let data = source.data_read().clone();
+        let mut imported = 0;
+        {
+            let mut conn = self.conn();
+            let tx = conn.transaction()?;
+            for (run_id, manifest) in source.runs().iter() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO runs (run_id, manifest) VALUES (?1, ?2)",
+                    params![run_id, serde_json::to_string(manifest)?],
+                )?;
+            }
+            for (run_id, run_data) in &data {
+                // Runs saved before run manifests existed.
+                tx.execute(
+                    "INSERT OR IGNORE INTO runs (run_id, manifest) VALUES (?1, ?2)",
+                    params![run_id, serde_json::to_string(&RunManifest::new())?],
+                )?;
+                for (environment, env_data) in run_data {
+                    tx.execute(
+                        "DELETE FROM journal WHERE run_id = ?1 AND environment = ?2",
+                        params![run_id, environment],
+                    )?;
+                    for entry in source.get_journal(run_id, environment) {
+                        tx.execute(
+                            "INSERT INTO journal (run_id, environment, entry) VALUES (?1, ?2, ?3)",
+                            params![run_id, environment, serde_json::to_string(&entry)?],
+                        )?;
+                    }
+                    for (language, lang_data) in env_data {
+                        for (benchmark, result) in lang_data {
+                            tx.execute(
+                                "INSERT OR REPLACE INTO benchmarks (run_id, environment, language, benchmark, manifest)
+                                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                                params![
+                                    run_id,
+                                    environment,
+                                    language,
+                                    benchmark,
+                                    serde_json::to_string(&result.manifest)?
+                                ],
+                            )?;
+                            let key = [run_id, environment, language, benchmark];
+                            for (testcase, summary) in &result.test_cases {
+                                Self::import_test_case(&tx, source, key, testcase, summary)?;
+                                imported += 1;
+                            }
+                        }
+                    }
+                }
+            }
+            tx.commit()?;
+        }
+        self.reload()?;
+        Ok(imported)
+    }
+
+    fn import_test_case(
+        tx: &Transaction<'_>,
+        source: &FsStore,
+        key: [&String; 4],
+        testcase: &str,
+        summary: &TestCaseSummary,
+    ) -> Result<()> {
+        let [run_id, environment, language, benchmark] = key;
+        let raw = source
+            .get_raw_data(run_id, environment, language, benchmark, testcase)
+            .unwrap_or_default();
+        tx.execute(
+            "INSERT OR REPLACE INTO test_cases (run_id, environment, language, benchmark, test_case, summary, raw)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                run_id,
+                environment,
+                language,
+                benchmark,
+                testcase,
+                serde_json::to_string(summary)?,
+                serde_json::to_string(raw.as_slice())?
+            ],
+        )?;
+        for (iteration, summary, raw) in
+            source.iterations(run_id, environment, language, benchmark, testcase)
+        {
+            tx.execute(
+                "INSERT OR REPLACE INTO iterations (run_id, environment, language, benchmark, test_case, iteration, summary, raw)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    run_id,
+                    environment,
+                    language,
+                    benchmark,
+                    testcase,
+                    iteration,
+                    serde_json::to_string(&summary)?,
+                    serde_json::to_string(&raw)?
+                ],
+            )?;
+        }
+        if let Some(curve) =
+            source.get_latency_curve(run_id, environment, language, benchmark, testcase)
+        {
+            tx.execute(
+                "INSERT OR REPLACE INTO latency_curves (run_id, environment, language, benchmark, test_case, curve)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    run_id,
+                    environment,
+                    language,
+                    benchmark,
+                    testcase,
+                    serde_json::to_string(&curve)?
+                ],
+            )?;
+        }
+        if let Some(percentiles) =
+            source.get_latency_percentiles(run_id, environment, language, benchmark, testcase)
+        {
+            tx.execute(
+                "INSERT OR REPLACE INTO latency_percentiles (run_id, environment, language, benchmark, test_case, percentiles)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    run_id,
+                    environment,
+                    language,
+                    benchmark,
+                    testcase,
+                    serde_json::to_string(&percentiles)?
+                ],
+            )?;
+        }
+        for kind in LogKind::ALL {
+            if let Some(log) =
+                source.get_test_log(run_id, environment, language, benchmark, testcase, kind)
+            {
+                tx.execute(
+                    "INSERT OR REPLACE INTO test_logs (run_id, environment, language, benchmark, test_case, kind, log)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        run_id,
+                        environment,
+                        language,
+                        benchmark,
+                        testcase,
+                        kind.to_string(),
+                        log
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ResultStore for SqliteStore {
+    fn data_read(&self) -> RwLockReadGuard<'_, StorageData> {
+        self.data.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn runs(&self) -> RwLockReadGuard<'_, HashMap<String, RunManifest>> {
+        self.runs.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn save_benchmark_result(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        manifest: &BenchmarkManifest,
+        summary: &TestCaseSummary,
+        raw_data: &[TestCaseRaw],
+    ) -> Result<()> {
+        let run_manifest = RunManifest::new();
+
+        // Merge with the load profiles and hosts of previously saved test cases before persisting.
+        let (mut merged, mut hosts) = self
+            .data_read()
+            .get(run_id)
+            .and_then(|run_data| run_data.get(environment.name()))
+            .and_then(|env_data| env_data.get(&language.name))
+            .and_then(|lang_data| lang_data.get(&benchmark.name))
+            .map(|result| {
+                (
+                    result.manifest.load_profiles.clone(),
+                    result.manifest.hosts.clone(),
+                )
+            })
+            .unwrap_or_default();
+        merged.extend(manifest.load_profiles.clone());
+        hosts.extend(manifest.hosts.clone());
+        let manifest = &BenchmarkManifest {
+            load_profiles: merged,
+            hosts,
+            ..manifest.clone()
+        };
+
+        {
+            let mut conn = self.conn();
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT OR IGNORE INTO runs (run_id, manifest) VALUES (?1, ?2)",
+                params![run_id, serde_json::to_string(&run_manifest)?],
+            )?;
+            tx.execute(
+                "INSERT OR REPLACE INTO benchmarks (run_id, environment, language, benchmark, manifest)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    run_id,
+                    environment.name(),
+                    language.name,
+                    benchmark.name,
+                    serde_json::to_string(manifest)?
+                ],
+            )?;
+            tx.execute(
+                "INSERT OR REPLACE INTO test_cases (run_id, environment, language, benchmark, test_case, summary, raw)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    run_id,
+                    environment.name(),
+                    language.name,
+                    benchmark.name,
+                    testcase.to_string(),
+                    serde_json::to_string(summary)?,
+                    serde_json::to_string(raw_data)?
+                ],
+            )?;
+            tx.commit()?;
+        }
+
+        insert_test_case(
+            &mut self.data_write(),
+            run_id,
+            environment.name(),
+            &language.name,
+            &benchmark.name,
+            manifest,
+            &testcase.to_string(),
+            summary,
+        );
+        self.runs_write()
+            .entry(run_id.to_string())
+            .or_insert(run_manifest);
+        self.raw_cache.invalidate(
+            run_id,
+            environment.name(),
+            &language.name,
+            &benchmark.name,
+            &testcase.to_string(),
+        );
+
+        Ok(())
+    }
+
+    fn save_iteration_result(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        iteration: u32,
+        summary: &TestCaseSummary,
+        raw_data: &[TestCaseRaw],
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO iterations (run_id, environment, language, benchmark, test_case, iteration, summary, raw)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                run_id,
+                environment.name(),
+                language.name,
+                benchmark.name,
+                testcase.to_string(),
+                iteration,
+                serde_json::to_string(summary)?,
+                serde_json::to_string(raw_data)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_raw_data(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<Arc<Vec<TestCaseRaw>>> {
+        self.raw_cache
+            .get_or_load(run_id, environment, language, benchmark, testcase, || {
+                let raw: Option<String> = self
+                    .conn()
+                    .query_row(
+                        "SELECT raw FROM test_cases
+                         WHERE run_id = ?1 AND environment = ?2 AND language = ?3 AND benchmark = ?4 AND test_case = ?5",
+                        params![run_id, environment, language, benchmark, testcase],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .ok()
+                    .flatten();
+                raw.and_then(|raw| serde_json::from_str(&raw).ok())
+            })
+    }
+
+    fn save_latency_curve(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        curve: &LatencyCurve,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO latency_curves (run_id, environment, language, benchmark, test_case, curve)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run_id,
+                environment.name(),
+                language.name,
+                benchmark.name,
+                testcase.to_string(),
+                serde_json::to_string(curve)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_latency_curve(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<LatencyCurve> {
+        let curve: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT curve FROM latency_curves
+                 WHERE run_id = ?1 AND environment = ?2 AND language = ?3 AND benchmark = ?4 AND test_case = ?5",
+                params![run_id, environment, language, benchmark, testcase],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+        curve.and_then(|curve| serde_json::from_str(&curve).ok())
+    }
+
+    fn save_latency_percentiles(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        percentiles: &LatencyPercentiles,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO latency_percentiles (run_id, environment, language, benchmark, test_case, percentiles)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run_id,
+                environment.name(),
+                language.name,
+                benchmark.name,
+                testcase.to_string(),
+                serde_json::to_string(percentiles)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_latency_percentiles(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<LatencyPercentiles> {
+        let percentiles: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT percentiles FROM latency_percentiles
+                 WHERE run_id = ?1 AND environment = ?2 AND language = ?3 AND benchmark = ?4 AND test_case = ?5",
+                params![run_id, environment, language, benchmark, testcase],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten();
+        percentiles.and_then(|percentiles| serde_json::from_str(&percentiles).ok())
+    }
+
+    fn save_test_log(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        kind: LogKind,
+        log: &str,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO test_logs (run_id, environment, language, benchmark, test_case, kind, log)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                run_id,
+                environment.name(),
+                language.name,
+                benchmark.name,
+                testcase.to_string(),
+                kind.to_string(),
+                log
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn get_test_log(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+        kind: LogKind,
+    ) -> Option<String> {
+        self.conn()
+            .query_row(
+                "SELECT log FROM test_logs
+                 WHERE run_id = ?1 AND environment = ?2 AND language = ?3 AND benchmark = ?4 AND test_case = ?5 AND kind = ?6",
+                params![run_id, environment, language, benchmark, testcase, kind.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    fn update_run_manifest(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        update: &dyn Fn(&mut RunEnvironmentManifest),
+    ) -> Result<()> {
+        let mut runs = self.runs_write();
+        let mut manifest = runs.get(run_id).cloned().unwrap_or_else(RunManifest::new);
+        update(
+            manifest
+                .environments
+                .entry(environment.name().to_string())
+                .or_default(),
+        );
+
+        self.conn().execute(
+            "INSERT OR REPLACE INTO runs (run_id, manifest) VALUES (?1, ?2)",
+            params![run_id, serde_json::to_string(&manifest)?],
+        )?;
+        runs.insert(run_id.to_string(), manifest);
+        Ok(())
+    }
+
+    fn append_journal(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        entry: &JournalEntry,
+    ) -> Result<()> {
+        self.conn().execute(
+            "INSERT INTO journal (run_id, environment, entry) VALUES (?1, ?2, ?3)",
+            params![run_id, environment.name(), serde_json::to_string(entry)?],
+        )?;
+        Ok(())
+    }
+
+    fn get_journal(&self, run_id: &str, environment: &str) -> Vec<JournalEntry> {
+        let conn = self.conn();
+        let Ok(mut stmt) = conn.prepare(
+            "SELECT entry FROM journal WHERE run_id = ?1 AND environment = ?2 ORDER BY seq",
+        ) else {
+            return Vec::new();
+        };
+        let Ok(rows) = stmt.query_map(params![run_id, environment], |row| row.get::<_, String>(0))
+        else {
+            return Vec::new();
+        };
+        rows.filter_map(|entry| entry.ok())
+            .filter_map(|entry| serde_json::from_str(&entry).ok())
+            .collect()
+    }
+
+    fn finish_run(&self, run_id: &str) -> Result<()> {
+        if let Some(path) = &self.run_finished_path {
+            fs::write(path, run_id)?;
+        }
+        Ok(())
+    }
+
+    fn reload(&self) -> Result<()> {
+        let (new_data, new_runs) = Self::load_all(&self.conn())?;
+
+        let mut data = self.data_write();
+        *data = new_data;
+
+        let mut runs = self.runs_write();
+        *runs = new_runs;
+
+        self.raw_cache.clear();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteStore;
+    use crate::test_support::TempDir;
+    use crate::testcase::{TestCaseRaw, TestCaseSummary};
+    use crate::{
+        Benchmark, BenchmarkManifest, BenchmarkTests, Environment, FsStore, JournalEntry, Lang,
+        ResultStore,
+    };
+
+    type Fixture = (
+        Environment,
+        Lang,
+        Benchmark,
+        BenchmarkManifest,
+        TestCaseSummary,
+        Vec<TestCaseRaw>,
+    );
+
+    fn fixture() -> Fixture {
+        let environment: Environment = serde_yaml::from_str(
+            "executor: local\nname: local\ntitle: Local\nspec: null\nicon: null",
+        )
+        .expect("environment");
+        let language: Lang =
+            serde_yaml::from_str("name: rust\nurl: https://rust-lang.org\ncolor: '#000'")
+                .expect("lang");
+        let benchmark: Benchmark = serde_yaml::from_str(
+            "name: axum\nlanguage: rust\nlanguage_version: '1'\nframework: axum\nframework_version: '0.8'\npath: rust/axum",
+        )
+        .expect("benchmark");
+        let manifest = BenchmarkManifest {
+            language_version: "1".to_string(),
+            framework_version: "0.8".to_string(),
+            tags: Default::default(),
+            database: None,
+            path: "rust/axum".to_string(),
+            arguments: Default::default(),
+            build_args: Default::default(),
+            resources: Default::default(),
+            load_profiles: Default::default(),
+            hosts: Default::default(),
+        };
+        let summary = TestCaseSummary {
+            requests_per_sec: 1000.0,
+            ..Default::default()
+        };
+        let raw = vec![TestCaseRaw {
+            elapsed_secs: 1,
+            requests_per_sec: 1000.0,
+            ..Default::default()
+        }];
+        (environment, language, benchmark, manifest, summary, raw)
+    }
+
+    #[test]
+    fn sqlite_store_round_trip() {
+        let (environment, language, benchmark, manifest, summary, raw) = fixture();
+        let store = SqliteStore::open_in_memory().expect("open");
+        store
+            .save_benchmark_result(
+                "1",
+                &environment,
+                &language,
+                &benchmark,
+                BenchmarkTests::PlainText,
+                &manifest,
+                &summary,
+                &raw,
+            )
+            .expect("save");
+        store.reload().expect("reload");
+
+        assert!(store.runs().contains_key("1"));
+        assert!(store.has_test_result(
+            "1",
+            &environment,
+            &language,
+            &benchmark,
+            BenchmarkTests::PlainText
+        ));
+        let raw = store
+            .get_raw_data("1", "local", "rust", "axum", "plaintext")
+            .expect("raw data");
+        assert_eq!(raw.len(), 1);
+    }
+
+    #[test]
+    fn import_fs_copies_results_and_journals() {
+        let (environment, language, benchmark, manifest, summary, raw) = fixture();
+        let temp = TempDir::new("wfb-import");
+        let source = FsStore::new(temp.path()).expect("fs store");
+        source
+            .save_benchmark_result(
+                "1",
+                &environment,
+                &language,
+                &benchmark,
+                BenchmarkTests::PlainText,
+                &manifest,
+                &summary,
+                &raw,
+            )
+            .expect("save");
+        source
+            .append_journal(
+                "1",
+                &environment,
+                &JournalEntry::running("rust", "axum", "plaintext"),
+            )
+            .expect("journal");
+
+        let store = SqliteStore::open_in_memory().expect("open");
+        assert_eq!(store.import_fs(&source).expect("import"), 1);
+        // A second import replaces instead of duplicating.
+        assert_eq!(store.import_fs(&source).expect("import"), 1);
+
+        assert!(store.runs().contains_key("1"));
+        assert!(store.has_test_result(
+            "1",
+            &environment,
+            &language,
+            &benchmark,
+            BenchmarkTests::PlainText
+        ));
+        assert_eq!(
+            store
+                .get_raw_data("1", "local", "rust", "axum", "plaintext")
+                .expect("raw data")
+                .len(),
+            1
+        );
+        assert_eq!(store.get_journal("1", "local").len(), 1);
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+/// Descriptive statistics for one metric sampled across repeated iterations.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MetricStats {
+    pub mean: f64,
+    pub median: f64,
+    pub stdev: f64,
+    pub ci95_low: f64,
+    pub ci95_high: f64,
+}
+
+impl MetricStats {
+    /// Computes mean, median, sample standard deviation and a two-sided 95%
+    /// confidence interval for the mean (Student's t).
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let n = samples.len();
+        let mean = samples.iter().sum::<f64>() / n as f64;
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+
+        let stdev = if n > 1 {
+            let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+            var.sqrt()
+        } else {
+            0.0
+        };
+
+        let half_width = if n > 1 {
+            t_critical_95(n - 1) * stdev / (n as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            mean,
+            median,
+            stdev,
+            ci95_low: mean - half_width,
+            ci95_high: mean + half_width,
+        })
+    }
+}
+
+/// Two-sided 95% critical value of Student's t distribution.
+pub fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => TABLE[df - 1],
+        31..=40 => 2.021,
+        41..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MetricStats;
+
+    #[test]
+    fn metric_stats_from_samples() {
+        let stats = MetricStats::from_samples(&[10.0, 12.0, 14.0]).expect("stats");
+        assert!((stats.mean - 12.0).abs() < 1e-9);
+        assert!((stats.median - 12.0).abs() < 1e-9);
+        assert!((stats.stdev - 2.0).abs() < 1e-9);
+        // t(2) = 4.303, half width = 4.303 * 2 / sqrt(3)
+        let half = 4.303 * 2.0 / 3f64.sqrt();
+        assert!((stats.ci95_low - (12.0 - half)).abs() < 1e-9);
+        assert!((stats.ci95_high - (12.0 + half)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn metric_stats_single_sample_has_zero_width_interval() {
+        let stats = MetricStats::from_samples(&[5.0]).expect("stats");
+        assert_eq!(stats.stdev, 0.0);
+        assert_eq!(stats.ci95_low, 5.0);
+        assert_eq!(stats.ci95_high, 5.0);
+        assert!(MetricStats::from_samples(&[]).is_none());
+    }
+}
@@ -1,301 +1,218 @@
-use std::collections::HashMap;
-use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLockReadGuard};
 
 use crate::benchmark::{Benchmark, BenchmarkManifest, BenchmarkTests};
-use crate::environment::Environment;
+use crate::environment::{ContainerResources, Environment};
 use crate::error::Result;
+use crate::fs_store::FsStore;
+use crate::journal::JournalEntry;
 use crate::lang::Lang;
-use crate::testcase::{TestCaseRaw, TestCaseSummary};
+use crate::load_profile::ResolvedLoadProfile;
+use crate::logs::LogKind;
+use crate::percentiles::LatencyPercentiles;
+use crate::sqlite_store::SqliteStore;
+use crate::testcase::{LatencyCurve, TestCaseRaw, TestCaseSummary};
 
 // RunId -> Environment -> Language -> BenchmarkName -> BenchmarkResult
 pub type StorageData =
     HashMap<String, HashMap<String, HashMap<String, HashMap<String, BenchmarkResult>>>>;
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct RunManifest {
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// What the runner applied in each environment of the run.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub environments: HashMap<String, RunEnvironmentManifest>,
 }
 
-#[derive(Clone)]
-pub struct Storage {
-    pub base_path: PathBuf,
-    pub data: Arc<RwLock<StorageData>>,
-    pub runs: Arc<RwLock<HashMap<String, RunManifest>>>,
+impl RunManifest {
+    pub fn new() -> Self {
+        Self {
+            created_at: chrono::Utc::now(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Settings the runner applied to one environment of a run.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RunEnvironmentManifest {
+    /// Load settings each test ran with, keyed by `<language>/<benchmark>/<test>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub load_profiles: BTreeMap<String, ResolvedLoadProfile>,
+    /// CPU and memory limits of the app and database containers, keyed by
+    /// `<language>/<benchmark>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, ContainerResources>,
+    /// Run options the environment could not apply, e.g. `app: ulimit nofile=...` on
+    /// Kubernetes, keyed by `<language>/<benchmark>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dropped_constraints: BTreeMap<String, BTreeSet<String>>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BenchmarkResult {
     pub manifest: BenchmarkManifest,
     pub test_cases: HashMap<String, TestCaseSummary>,
-    #[serde(skip)]
-    pub raw_data: HashMap<String, Vec<TestCaseRaw>>,
 }
 
-impl Storage {
-    pub fn data_read(&self) -> RwLockReadGuard<'_, StorageData> {
-        self.data.read().unwrap_or_else(|err| err.into_inner())
-    }
-
-    pub fn runs_read(&self) -> RwLockReadGuard<'_, HashMap<String, RunManifest>> {
-        self.runs.read().unwrap_or_else(|err| err.into_inner())
-    }
-
-    pub fn data_write(&self) -> RwLockWriteGuard<'_, StorageData> {
-        self.data.write().unwrap_or_else(|err| err.into_inner())
-    }
-
-    pub fn runs_write(&self) -> RwLockWriteGuard<'_, HashMap<String, RunManifest>> {
-        self.runs.write().unwrap_or_else(|err| err.into_inner())
-    }
-
-    pub fn new(base_path: impl Into<PathBuf>) -> Result<Self> {
-        let base_path = base_path.into();
-        let (data, runs) = Self::load_all(&base_path)?;
-        Ok(Self {
-            base_path,
-            data: Arc::new(RwLock::new(data)),
-            runs: Arc::new(RwLock::new(runs)),
-        })
-    }
-
-    #[allow(clippy::collapsible_if)]
-    fn load_all(base_path: &Path) -> Result<(StorageData, HashMap<String, RunManifest>)> {
-        let mut data = HashMap::new();
-        let mut runs = HashMap::new();
-        if !base_path.exists() {
-            return Ok((data, runs));
-        }
+/// Persistence backend for benchmark results.
+///
+/// Summaries and manifests of every run are kept in memory and exposed through
+/// [`ResultStore::data_read`]; raw time-series are fetched with [`ResultStore::get_raw_data`].
+pub trait ResultStore: Send + Sync {
+    fn data_read(&self) -> RwLockReadGuard<'_, StorageData>;
 
-        for run_entry in fs::read_dir(base_path)? {
-            let run_entry = run_entry?;
-            if !run_entry.file_type()?.is_dir() {
-                continue;
-            }
-            let run_id = run_entry.file_name().to_string_lossy().to_string();
-
-            // Load run manifest
-            let manifest_path = run_entry.path().join("manifest.yaml");
-            if manifest_path.exists() {
-                if let Ok(file) = fs::File::open(&manifest_path) {
-                    if let Ok(manifest) = serde_yaml::from_reader(file) {
-                        runs.insert(run_id.clone(), manifest);
-                    }
-                }
-            }
+    fn runs(&self) -> RwLockReadGuard<'_, HashMap<String, RunManifest>>;
 
-            let run_data = data.entry(run_id.clone()).or_default();
-
-            for env_entry in fs::read_dir(run_entry.path())? {
-                let env_entry = env_entry?;
-                if !env_entry.file_type()?.is_dir() {
-                    continue;
-                }
-                let environment = env_entry.file_name().to_string_lossy().to_string();
-                let env_data = run_data.entry(environment.clone()).or_default();
-
-                for lang_entry in fs::read_dir(env_entry.path())? {
-                    let lang_entry = lang_entry?;
-                    if !lang_entry.file_type()?.is_dir() {
-                        continue;
-                    }
-                    let language = lang_entry.file_name().to_string_lossy().to_string();
-                    let lang_results = env_data.entry(language.clone()).or_default();
-
-                    for bench_entry in fs::read_dir(lang_entry.path())? {
-                        let bench_entry = bench_entry?;
-                        if !bench_entry.file_type()?.is_dir() {
-                            continue;
-                        }
-                        let benchmark_name = bench_entry.file_name().to_string_lossy().to_string();
-                        let benchmark_path = bench_entry.path();
-
-                        // Load manifest
-                        let manifest_path = benchmark_path.join("manifest.yaml");
-                        if !manifest_path.exists() {
-                            continue;
-                        }
-                        let manifest_file = fs::File::open(&manifest_path)?;
-                        let manifest: BenchmarkManifest =
-                            match serde_yaml::from_reader(manifest_file) {
-                                Ok(m) => m,
-                                Err(_) => continue,
-                            };
-
-                        let mut test_cases = HashMap::new();
-                        let mut raw_data = HashMap::new();
-
-                        // Load test cases
-                        if let Ok(entries) = fs::read_dir(&benchmark_path) {
-                            for file_entry in entries.flatten() {
-                                let path = file_entry.path();
-                                if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
-                                    let file_stem =
-                                        path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-                                    if file_stem == "manifest" {
-                                        continue;
-                                    }
-
-                                    if extension == "yaml" {
-                                        if let Ok(summary_file) = fs::File::open(&path) {
-                                            if let Ok(summary) =
-                                                serde_yaml::from_reader::<_, TestCaseSummary>(
-                                                    summary_file,
-                                                )
-                                            {
-                                                test_cases.insert(file_stem.to_string(), summary);
-                                            }
-                                        }
-                                    } else if extension == "jsonl" && file_stem.ends_with("_raw") {
-                                        let test_name = file_stem.trim_end_matches("_raw");
-                                        if let Ok(file) = fs::File::open(&path) {
-                                            let reader = std::io::BufReader::new(file);
-                                            let mut results = Vec::new();
-                                            use std::io::BufRead;
-                                            for line in reader.lines().map_while(|l| l.ok()) {
-                                                if line.is_empty() {
-                                                    continue;
-                                                }
-                                                if let Ok(item) =
-                                                    serde_json::from_str::<TestCaseRaw>(&line)
-                                                {
-                                                    results.push(item);
-                                                }
-                                            }
-                                            raw_data.insert(test_name.to_string(), results);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        lang_results.insert(
-                            benchmark_name,
-                            BenchmarkResult {
-                                manifest,
-                                test_cases,
-                                raw_data,
-                            },
-                        );
-                    }
-                }
-            }
-        }
-        Ok((data, runs))
-    }
-
-    fn get_benchmark_path(
+    #[allow(clippy::too_many_arguments)]
+    fn save_benchmark_result(
         &self,
         run_id: &str,
         environment: &Environment,
         language: &Lang,
         benchmark: &Benchmark,
-    ) -> PathBuf {
-        self.base_path
-            .join(run_id)
-            .join(environment.name())
-            .join(&language.name)
-            .join(&benchmark.name)
-    }
+        testcase: BenchmarkTests,
+        manifest: &BenchmarkManifest,
+        summary: &TestCaseSummary,
+        raw_data: &[TestCaseRaw],
+    ) -> Result<()>;
 
+    /// Saves a single iteration of a repeated test.
+    ///
+    /// Iterations are not part of [`ResultStore::data_read`]; the aggregated result is
+    /// stored with [`ResultStore::save_benchmark_result`] once all iterations have finished.
     #[allow(clippy::too_many_arguments)]
-    pub fn save_benchmark_result(
+    fn save_iteration_result(
         &self,
         run_id: &str,
         environment: &Environment,
         language: &Lang,
         benchmark: &Benchmark,
         testcase: BenchmarkTests,
-        manifest: &BenchmarkManifest,
+        iteration: u32,
         summary: &TestCaseSummary,
         raw_data: &[TestCaseRaw],
-    ) -> Result<()> {
-        // Update memory
-        {
-            let mut data = self.data_write();
-            let run_data = data.entry(run_id.to_string()).or_default();
-            let env_data = run_data.entry(environment.name().to_string()).or_default();
-            let lang_data = env_data.entry(language.name.to_string()).or_default();
-
-            let bench_result = lang_data
-                .entry(benchmark.name.to_string())
-                .or_insert_with(|| BenchmarkResult {
-                    manifest: manifest.clone(),
-                    test_cases: HashMap::new(),
-                    raw_data: HashMap::new(),
-                });
-
-            // Update manifest in case it changed (though usually it shouldn't for same benchmark)
-            bench_result.manifest = manifest.clone();
-            bench_result
-                .test_cases
-                .insert(testcase.to_string(), summary.clone());
-            bench_result
-                .raw_data
-                .insert(testcase.to_string(), raw_data.to_vec());
-        }
+    ) -> Result<()>;
 
-        // Save to disk
-        let benchmark_path = self.get_benchmark_path(run_id, environment, language, benchmark);
-        fs::create_dir_all(&benchmark_path)?;
-
-        // Save run manifest if it doesn't exist
-        let run_path = self.base_path.join(run_id);
-        let run_manifest_path = run_path.join("manifest.yaml");
-        if !run_manifest_path.exists() {
-            let manifest = RunManifest {
-                created_at: chrono::Utc::now(),
-            };
-            let file = fs::File::create(&run_manifest_path)?;
-            serde_yaml::to_writer(file, &manifest)?;
-
-            // Update memory
-            self.runs_write().insert(run_id.to_string(), manifest);
-        }
+    fn get_raw_data(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<Arc<Vec<TestCaseRaw>>>;
 
-        // Save manifest if it doesn't exist
-        let manifest_path = benchmark_path.join("manifest.yaml");
-        if !manifest_path.exists() {
-            let manifest_file = fs::File::create(&manifest_path)?;
-            serde_yaml::to_writer(manifest_file, manifest)?;
-        }
+    /// Saves the latency-vs-throughput curve of a constant-arrival-rate sweep
+    /// (`wfb-runner run --arrival-rates`).
+    fn save_latency_curve(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        curve: &LatencyCurve,
+    ) -> Result<()>;
 
-        // Save summary
-        let summary_path = benchmark_path.join(format!("{}.yaml", testcase));
-        let summary_file = fs::File::create(&summary_path)?;
-        serde_yaml::to_writer(summary_file, summary)?;
-
-        // Save raw data
-        let raw_path = benchmark_path.join(format!("{}_raw.jsonl", testcase));
-        let mut raw_file = fs::File::create(&raw_path)?;
-        for item in raw_data {
-            serde_json::to_writer(&mut raw_file, item)?;
-            raw_file.write_all(b"\n")?;
-        }
+    fn get_latency_curve(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> Option<LatencyCurve>;
 
-        Ok(())
-    }
+    /// Saves the latency percentile summary of a test, read from wrkr's end-of-run summary.
+    fn save_latency_percentiles(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        percentiles: &LatencyPercentiles,
+    ) -> Result<()>;
 
-    pub fn get_raw_data(
+    fn get_latency_percentiles(
         &self,
         run_id: &str,
         environment: &str,
         language: &str,
         benchmark: &str,
         testcase: &str,
-    ) -> Option<Vec<TestCaseRaw>> {
-        let data = self.data_read();
-        data.get(run_id)
-            .and_then(|env| env.get(environment))
-            .and_then(|lang| lang.get(language))
-            .and_then(|bench| bench.get(benchmark))
-            .and_then(|result| result.raw_data.get(testcase))
-            .cloned()
+    ) -> Option<LatencyPercentiles>;
+
+    /// Saves the `kind` container log of a test, replacing an earlier one.
+    #[allow(clippy::too_many_arguments)]
+    fn save_test_log(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        language: &Lang,
+        benchmark: &Benchmark,
+        testcase: BenchmarkTests,
+        kind: LogKind,
+        log: &str,
+    ) -> Result<()>;
+
+    fn get_test_log(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+        kind: LogKind,
+    ) -> Option<String>;
+
+    /// Applies `update` to the manifest of `run_id` in `environment`, creating the run
+    /// manifest when the run has none yet.
+    fn update_run_manifest(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        update: &dyn Fn(&mut RunEnvironmentManifest),
+    ) -> Result<()>;
+
+    /// Appends an attempt record to the journal of `run_id` in `environment`.
+    fn append_journal(
+        &self,
+        run_id: &str,
+        environment: &Environment,
+        entry: &JournalEntry,
+    ) -> Result<()>;
+
+    /// Journal of `run_id` in `environment`, oldest entry first.
+    ///
+    /// Use [`crate::latest_attempts`] to get the current state of every test.
+    fn get_journal(&self, run_id: &str, environment: &str) -> Vec<JournalEntry>;
+
+    /// Tells readers of the store that `run_id` has finished. The SQLite backend touches
+    /// [`SqliteStore::RUN_FINISHED_FILE`], which a watching server reloads on instead of
+    /// on every write to the database.
+    fn finish_run(&self, run_id: &str) -> Result<()> {
+        let _ = run_id;
+        Ok(())
     }
 
-    pub fn load_run(
+    fn reload(&self) -> Result<()>;
+
+    /// Re-reads only what the changed `paths` (absolute or relative to the data directory)
+    /// affect. Backends that can't scope a reload fall back to [`ResultStore::reload`].
+    fn reload_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        let _ = paths;
+        self.reload()
+    }
+
+    fn load_run(
         &self,
         run_id: &str,
         environment: &Environment,
@@ -310,7 +227,7 @@ pub fn load_run(
         Ok(HashMap::new())
     }
 
-    pub fn has_test_result(
+    fn has_test_result(
         &self,
         run_id: &str,
         environment: &Environment,
@@ -326,16 +243,211 @@ pub fn has_test_result(
             .map(|bench_result| bench_result.test_cases.contains_key(&testcase.to_string()))
             .unwrap_or(false)
     }
+}
 
-    pub fn reload(&self) -> Result<()> {
-        let (new_data, new_runs) = Self::load_all(&self.base_path)?;
+/// Which [`ResultStore`] implementation to open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageBackend {
+    /// `data/<run>/<env>/<lang>/<bench>/*.yaml|jsonl` directory tree.
+    #[default]
+    Fs,
+    /// Single embedded SQLite database (`data/results.sqlite`).
+    Sqlite,
+}
 
-        let mut data = self.data_write();
-        *data = new_data;
+impl fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageBackend::Fs => write!(f, "fs"),
+            StorageBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
 
-        let mut runs = self.runs_write();
-        *runs = new_runs;
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fs" | "filesystem" => Ok(StorageBackend::Fs),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            other => Err(format!(
+                "unknown storage backend '{}', expected 'fs' or 'sqlite'",
+                other
+            )),
+        }
+    }
+}
 
-        Ok(())
+/// Shared handle to the configured [`ResultStore`].
+#[derive(Clone)]
+pub struct Storage {
+    pub base_path: PathBuf,
+    store: Arc<dyn ResultStore>,
+}
+
+impl Storage {
+    /// Opens the filesystem backend rooted at `base_path`.
+    pub fn new(base_path: impl Into<PathBuf>) -> Result<Self> {
+        Self::open(StorageBackend::Fs, base_path)
+    }
+
+    pub fn open(backend: StorageBackend, base_path: impl Into<PathBuf>) -> Result<Self> {
+        let base_path = base_path.into();
+        let store: Arc<dyn ResultStore> = match backend {
+            StorageBackend::Fs => Arc::new(FsStore::new(&base_path)?),
+            StorageBackend::Sqlite => {
+                Arc::new(SqliteStore::open(base_path.join(SqliteStore::FILE_NAME))?)
+            }
+        };
+        Ok(Self { base_path, store })
+    }
+}
+
+impl Deref for Storage {
+    type Target = dyn ResultStore;
+
+    fn deref(&self) -> &Self::Target {
+        self.store.as_ref()
+    }
+}
+
+/// Inserts a saved test case into the in-memory index shared by all backends.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn insert_test_case<'a>(
+    data: &'a mut StorageData,
+    run_id: &str,
+    environment: &str,
+    language: &str,
+    benchmark: &str,
+    manifest: &BenchmarkManifest,
+    testcase: &str,
+    summary: &TestCaseSummary,
+) -> &'a mut BenchmarkResult {
+    let bench_result = data
+        .entry(run_id.to_string())
+        .or_default()
+        .entry(environment.to_string())
+        .or_default()
+        .entry(language.to_string())
+        .or_default()
+        .entry(benchmark.to_string())
+        .or_insert_with(|| BenchmarkResult {
+            manifest: manifest.clone(),
+            test_cases: HashMap::new(),
+        });
+
+    // Update manifest in case it changed (though usually it shouldn't for same benchmark),
+    // keeping the load profiles and hosts recorded for other test cases.
+    let mut load_profiles = std::mem::take(&mut bench_result.manifest.load_profiles);
+    load_profiles.extend(manifest.load_profiles.clone());
+    let mut hosts = std::mem::take(&mut bench_result.manifest.hosts);
+    hosts.extend(manifest.hosts.clone());
+    bench_result.manifest = manifest.clone();
+    bench_result.manifest.load_profiles = load_profiles;
+    bench_result.manifest.hosts = hosts;
+    bench_result
+        .test_cases
+        .insert(testcase.to_string(), summary.clone());
+    bench_result
+}
+
+/// Number of raw time-series kept in memory by [`RawDataCache`].
+const RAW_CACHE_CAPACITY: usize = 64;
+
+type RawDataKey = (String, String, String, String, String);
+
+/// LRU cache of raw time-series, keyed by run/environment/language/benchmark/test case.
+pub(crate) struct RawDataCache {
+    entries: Mutex<lru::LruCache<RawDataKey, Arc<Vec<TestCaseRaw>>>>,
+}
+
+impl Default for RawDataCache {
+    fn default() -> Self {
+        let capacity = NonZeroUsize::new(RAW_CACHE_CAPACITY).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            entries: Mutex::new(lru::LruCache::new(capacity)),
+        }
+    }
+}
+
+impl RawDataCache {
+    fn key(
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) -> RawDataKey {
+        (
+            run_id.to_string(),
+            environment.to_string(),
+            language.to_string(),
+            benchmark.to_string(),
+            testcase.to_string(),
+        )
+    }
+
+    fn entries(
+        &self,
+    ) -> std::sync::MutexGuard<'_, lru::LruCache<RawDataKey, Arc<Vec<TestCaseRaw>>>> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns the cached series or loads it with `load`, caching successful loads.
+    /// Hits share the cached series instead of copying it.
+    pub(crate) fn get_or_load(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+        load: impl FnOnce() -> Option<Vec<TestCaseRaw>>,
+    ) -> Option<Arc<Vec<TestCaseRaw>>> {
+        let key = Self::key(run_id, environment, language, benchmark, testcase);
+        if let Some(cached) = self.entries().get(&key) {
+            return Some(cached.clone());
+        }
+
+        // Load without holding the lock so slow reads don't block other lookups.
+        let loaded = Arc::new(load()?);
+        self.entries().put(key, loaded.clone());
+        Some(loaded)
+    }
+
+    pub(crate) fn invalidate(
+        &self,
+        run_id: &str,
+        environment: &str,
+        language: &str,
+        benchmark: &str,
+        testcase: &str,
+    ) {
+        let key = Self::key(run_id, environment, language, benchmark, testcase);
+        self.entries().pop(&key);
+    }
+
+    /// Drops every entry below a `run/env/lang/benchmark` prefix.
+    pub(crate) fn invalidate_prefix(&self, scope: &[String]) {
+        let mut entries = self.entries();
+        let stale: Vec<RawDataKey> = entries
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|(run_id, environment, language, benchmark, _)| {
+                [run_id, environment, language, benchmark]
+                    .iter()
+                    .zip(scope)
+                    .all(|(part, expected)| *part == expected)
+            })
+            .cloned()
+            .collect();
+        for key in stale {
+            entries.pop(&key);
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries().clear();
     }
 }
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+use crate::stats::MetricStats;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TestCaseRaw {
     pub elapsed_secs: u64,
     pub connections: u64,
@@ -34,7 +36,7 @@ pub struct TestCaseRaw {
     pub req_per_sec_stdev_pct: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TestCaseSummary {
     pub requests_per_sec: f64,
     pub bytes_per_sec: u64,
@@ -51,6 +53,8 @@ pub struct TestCaseSummary {
     pub latency_p90: u64,
     pub latency_p99: u64,
     pub latency_stdev_pct: f64,
+    /// `(percentile, microseconds)` over the whole run, from wrkr's end-of-run summary.
+    /// The other latency fields come from the best 5-second window.
     pub latency_distribution: Vec<(u8, u64)>,
 
     pub errors: HashMap<String, u64>,
@@ -61,4 +65,57 @@ pub struct TestCaseSummary {
     pub req_per_sec_stdev: f64,
     pub req_per_sec_max: f64,
     pub req_per_sec_stdev_pct: f64,
+
+    /// Run-to-run statistics when the test was repeated (`wfb-runner run --repeat N`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<TestCaseStats>,
+
+    /// Highest throughput sustained within the latency SLO of the test's load profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo: Option<SloThroughput>,
+}
+
+/// Max sustainable throughput: the best 5-second window whose samples all kept p99 latency
+/// within `latency_p99_target` without any errors.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SloThroughput {
+    /// p99 latency objective in microseconds.
+    pub latency_p99_target: u64,
+    /// Average RPS of the best qualifying window; 0 when no window met the SLO.
+    pub requests_per_sec: f64,
+    /// Worst p99 latency within that window, in microseconds.
+    pub latency_p99: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestCaseStats {
+    pub iterations: u32,
+    pub requests_per_sec: MetricStats,
+    pub latency_p99: MetricStats,
+}
+
+/// One step of a constant-arrival-rate sweep: the latency observed at a fixed offered load.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LatencyCurvePoint {
+    /// Offered load in iterations per second.
+    pub target_rate: u64,
+    pub achieved_rps: f64,
+
+    // Latency metrics are stored as microseconds.
+    pub latency_p50: u64,
+    pub latency_p90: u64,
+    pub latency_p99: u64,
+    pub latency_max: u64,
+
+    /// Iterations wrkr could not start on time because all VUs were busy.
+    pub dropped_iterations: u64,
+    pub total_requests: u64,
+    pub total_errors: u64,
+    pub duration_secs: u64,
+}
+
+/// Latency-vs-throughput curve of a test, ordered by increasing `target_rate`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LatencyCurve {
+    pub points: Vec<LatencyCurvePoint>,
 }